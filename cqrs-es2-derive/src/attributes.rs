@@ -0,0 +1,229 @@
+use syn::{
+    parenthesized,
+    Attribute,
+    Expr,
+    LitInt,
+    LitStr,
+    Result,
+    Token,
+};
+
+/// Reads `#[<attribute>(type = "...")]`
+pub(crate) fn type_name(
+    attrs: &[Attribute],
+    attribute: &str,
+) -> Result<Option<String>> {
+    let mut result = None;
+
+    for attr in attrs
+        .iter()
+        .filter(|x| x.path().is_ident(attribute))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("type") {
+                let value: LitStr = meta.value()?.parse()?;
+                result = Some(value.value());
+                Ok(())
+            }
+            else {
+                Err(meta.error(format!(
+                    "unsupported {attribute} attribute, expected \
+                     `type`"
+                )))
+            }
+        })?;
+    }
+
+    Ok(result)
+}
+
+/// Reads `#[event(version = N)]`
+pub(crate) fn event_version(
+    attrs: &[Attribute]
+) -> Result<Option<u32>> {
+    let mut result = None;
+
+    for attr in attrs
+        .iter()
+        .filter(|x| x.path().is_ident("event"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("version") {
+                let value: LitInt = meta.value()?.parse()?;
+                result = Some(value.base10_parse()?);
+                Ok(())
+            }
+            else {
+                Err(meta.error(
+                    "unsupported event attribute, expected `version`",
+                ))
+            }
+        })?;
+    }
+
+    Ok(result)
+}
+
+/// Serde naming attributes of a container or a variant
+#[derive(Default)]
+pub(crate) struct SerdeNames {
+    pub(crate) rename: Option<String>,
+    pub(crate) rename_all: Option<String>,
+}
+
+/// Reads the serialized names from `#[serde(rename = "...")]` and
+/// `#[serde(rename_all = "...")]`, including their
+/// `serialize = "..."` forms, and skips every other serde attribute
+pub(crate) fn serde_names(attrs: &[Attribute]) -> Result<SerdeNames> {
+    let mut result = SerdeNames::default();
+
+    for attr in attrs
+        .iter()
+        .filter(|x| x.path().is_ident("serde"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("rename") {
+                result.rename = serialized_name(&meta)?;
+            }
+            else if meta.path.is_ident("rename_all") {
+                result.rename_all = serialized_name(&meta)?;
+            }
+            else {
+                skip(&meta)?;
+            }
+
+            Ok(())
+        })?;
+    }
+
+    Ok(result)
+}
+
+fn serialized_name(
+    meta: &syn::meta::ParseNestedMeta<'_>
+) -> Result<Option<String>> {
+    if meta.input.peek(Token![=]) {
+        let value: LitStr = meta.value()?.parse()?;
+        return Ok(Some(value.value()));
+    }
+
+    let mut result = None;
+
+    meta.parse_nested_meta(|nested| {
+        if nested.path.is_ident("serialize") {
+            let value: LitStr = nested.value()?.parse()?;
+            result = Some(value.value());
+        }
+        else {
+            skip(&nested)?;
+        }
+
+        Ok(())
+    })?;
+
+    Ok(result)
+}
+
+fn skip(meta: &syn::meta::ParseNestedMeta<'_>) -> Result<()> {
+    if meta.input.peek(Token![=]) {
+        meta.value()?.parse::<Expr>()?;
+    }
+    else if meta.input.peek(syn::token::Paren) {
+        let content;
+        parenthesized!(content in meta.input);
+        content.parse::<proc_macro2::TokenStream>()?;
+    }
+
+    Ok(())
+}
+
+/// Applies a serde `rename_all` rule to a variant name, following
+/// serde's own algorithm exactly so that the event type matches the
+/// serialized name, e.g., `HTTPRequestSent` becomes
+/// `h_t_t_p_request_sent` in snake case
+pub(crate) fn apply_rename_all(
+    rule: &str,
+    name: &str,
+) -> Result<String> {
+    let result = match rule {
+        "lowercase" => name.to_ascii_lowercase(),
+        "UPPERCASE" => name.to_ascii_uppercase(),
+        "PascalCase" => name.to_string(),
+        "camelCase" => {
+            let mut chars = name.chars();
+            match chars.next() {
+                None => String::new(),
+                Some(x) => {
+                    let mut result =
+                        String::with_capacity(name.len());
+                    result.push(x.to_ascii_lowercase());
+                    result.extend(chars);
+                    result
+                },
+            }
+        },
+        "snake_case" => serde_snake_case(name),
+        "SCREAMING_SNAKE_CASE" => {
+            serde_snake_case(name).to_ascii_uppercase()
+        },
+        "kebab-case" => serde_snake_case(name).replace('_', "-"),
+        "SCREAMING-KEBAB-CASE" => {
+            serde_snake_case(name)
+                .to_ascii_uppercase()
+                .replace('_', "-")
+        },
+        _ => {
+            return Err(syn::Error::new(
+                proc_macro2::Span::call_site(),
+                format!("unsupported serde rename_all rule '{rule}'"),
+            ));
+        },
+    };
+
+    Ok(result)
+}
+
+/// Converts a variant name to `snake_case` the way serde does, with
+/// an underscore before every uppercase character but the first
+fn serde_snake_case(name: &str) -> String {
+    let mut result = String::with_capacity(name.len() + 4);
+
+    for (i, x) in name.char_indices() {
+        if i > 0 && x.is_uppercase() {
+            result.push('_');
+        }
+
+        result.push(x.to_ascii_lowercase());
+    }
+
+    result
+}
+
+/// Converts a `PascalCase` type name to `snake_case`, keeping
+/// acronyms together, e.g., `HTTPClient` becomes `http_client`. Only
+/// used for the default aggregate and query types, serialized names
+/// follow `apply_rename_all` instead.
+pub(crate) fn snake_case(name: &str) -> String {
+    let chars: Vec<char> = name.chars().collect();
+    let mut result = String::with_capacity(name.len() + 4);
+
+    for (i, x) in chars.iter().enumerate() {
+        if x.is_uppercase() && i > 0 {
+            let previous = chars[i - 1];
+            let next_is_lower = chars
+                .get(i + 1)
+                .is_some_and(|x| x.is_lowercase());
+
+            if previous.is_lowercase() ||
+                previous.is_ascii_digit() ||
+                (previous.is_uppercase() && next_is_lower)
+            {
+                result.push('_');
+            }
+        }
+
+        result.extend(x.to_lowercase());
+    }
+
+    result
+}
@@ -0,0 +1,42 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{
+    Data,
+    DeriveInput,
+};
+
+pub(crate) fn expand(input: &DeriveInput) -> TokenStream {
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) =
+        input.generics.split_for_impl();
+
+    let names = if let Data::Enum(data) = &input.data {
+        data.variants
+            .iter()
+            .map(|variant| {
+                let ident = &variant.ident;
+                let command_name = ident.to_string();
+
+                quote! { Self::#ident { .. } => #command_name }
+            })
+            .collect()
+    }
+    else {
+        let command_name = name.to_string();
+
+        vec![quote! { _ => #command_name }]
+    };
+
+    quote! {
+        #[automatically_derived]
+        impl #impl_generics ::cqrs_es2::ICommand
+            for #name #ty_generics #where_clause
+        {
+            fn command_name(&self) -> &'static str {
+                match self {
+                    #(#names,)*
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,88 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{
+    Data,
+    DeriveInput,
+    Result,
+};
+
+use crate::attributes::{
+    apply_rename_all,
+    event_version,
+    serde_names,
+};
+
+const DEFAULT_EVENT_VERSION: u32 = 1;
+
+pub(crate) fn expand(input: &DeriveInput) -> Result<TokenStream> {
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) =
+        input.generics.split_for_impl();
+
+    let container_names = serde_names(&input.attrs)?;
+    let container_version =
+        event_version(&input.attrs)?.unwrap_or(DEFAULT_EVENT_VERSION);
+
+    let (types, versions) = if let Data::Enum(data) = &input.data {
+        let mut types = Vec::with_capacity(data.variants.len());
+        let mut versions = Vec::with_capacity(data.variants.len());
+
+        for variant in &data.variants {
+            let ident = &variant.ident;
+
+            let event_type = match serde_names(&variant.attrs)?.rename
+            {
+                Some(x) => x,
+                None => {
+                    match &container_names.rename_all {
+                        None => ident.to_string(),
+                        Some(rule) => {
+                            apply_rename_all(
+                                rule,
+                                &ident.to_string(),
+                            )?
+                        },
+                    }
+                },
+            };
+
+            let version = event_version(&variant.attrs)?
+                .unwrap_or(container_version);
+
+            types.push(quote! { Self::#ident { .. } => #event_type });
+            versions.push(quote! { Self::#ident { .. } => #version });
+        }
+
+        (types, versions)
+    }
+    else {
+        let event_type = container_names
+            .rename
+            .unwrap_or_else(|| name.to_string());
+
+        (
+            vec![quote! { _ => #event_type }],
+            vec![quote! { _ => #container_version }],
+        )
+    };
+
+    Ok(quote! {
+        #[automatically_derived]
+        #[allow(clippy::match_same_arms)]
+        impl #impl_generics ::cqrs_es2::IEvent
+            for #name #ty_generics #where_clause
+        {
+            fn event_type(&self) -> &'static str {
+                match self {
+                    #(#types,)*
+                }
+            }
+
+            fn event_version(&self) -> u32 {
+                match self {
+                    #(#versions,)*
+                }
+            }
+        }
+    })
+}
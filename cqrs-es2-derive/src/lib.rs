@@ -0,0 +1,276 @@
+//! # cqrs-es2-derive
+//!
+//! Derive macros removing the boilerplate of implementing the
+//! `cqrs-es2` marker and type traits. The macros are re-exported by
+//! `cqrs-es2` with the `derive` feature:
+//!
+//! ```toml
+//! [dependencies]
+//! cqrs-es2 = { version = "*", features = ["derive"] }
+//! ```
+//!
+//! - `#[derive(Command)]` implements `ICommand` with per-variant
+//!   `command_name()`
+//! - `#[derive(Event)]` implements `IEvent` with per-variant
+//!   `event_type()` and `event_version()`
+//! - `#[derive(Aggregate)]` implements `IAggregate` for every command
+//!   and event the type handles
+//! - `#[derive(Query)]` implements `IQuery` for every command and
+//!   event the type consumes
+//!
+//! The generated code refers to the framework as `::cqrs_es2`.
+
+#![forbid(unsafe_code)]
+#![deny(missing_docs)]
+#![deny(clippy::all)]
+#![warn(rust_2018_idioms)]
+#![warn(clippy::pedantic)]
+
+use proc_macro::TokenStream;
+use syn::{
+    parse_macro_input,
+    DeriveInput,
+};
+
+mod aggregate;
+mod attributes;
+mod command;
+mod event;
+mod query;
+
+/// Implements `ICommand` for a command enum or struct, including its
+/// `command_name()` function, i.e., the name of the variant or of the
+/// struct.
+///
+/// # Examples
+/// ```rust
+/// use cqrs_es2::{
+///     Command,
+///     ICommand,
+/// };
+///
+/// #[derive(Debug, PartialEq, Clone, Command)]
+/// pub enum CustomerCommand {
+///     AddCustomerName { changed_name: String },
+/// }
+///
+/// let command = CustomerCommand::AddCustomerName {
+///     changed_name: "John Doe".to_string(),
+/// };
+///
+/// assert_eq!(
+///     command.command_name(),
+///     "AddCustomerName"
+/// );
+/// ```
+#[proc_macro_derive(Command)]
+pub fn derive_command(input: TokenStream) -> TokenStream {
+    command::expand(&parse_macro_input!(
+        input as DeriveInput
+    ))
+    .into()
+}
+
+/// Implements `IEvent` for an event enum or struct, including its
+/// `event_type()` and `event_version()` functions.
+///
+/// The event type of a variant is its serialized name, which honors
+/// `#[serde(rename = "...")]` and `#[serde(rename_all = "...")]`
+/// exactly the way serde applies them.
+/// The event version defaults to 1 and is set per variant, or for the
+/// whole enum, with `#[event(version = 2)]`.
+///
+/// # Examples
+/// ```rust
+/// use serde::{
+///     Deserialize,
+///     Serialize,
+/// };
+///
+/// use cqrs_es2::{
+///     Event,
+///     IEvent,
+/// };
+///
+/// #[derive(
+///     Debug,
+///     PartialEq,
+///     Clone,
+///     Serialize,
+///     Deserialize,
+///     Event
+/// )]
+/// pub enum CustomerEvent {
+///     #[event(version = 2)]
+///     NameAdded {
+///         changed_name: String,
+///     },
+///     EmailUpdated {
+///         new_email: String,
+///     },
+/// }
+///
+/// let event = CustomerEvent::NameAdded {
+///     changed_name: "John Doe".to_string(),
+/// };
+///
+/// assert_eq!(event.event_type(), "NameAdded");
+/// assert_eq!(event.event_version(), 2);
+/// ```
+#[proc_macro_derive(Event, attributes(event))]
+pub fn derive_event(input: TokenStream) -> TokenStream {
+    event::expand(&parse_macro_input!(
+        input as DeriveInput
+    ))
+    .unwrap_or_else(syn::Error::into_compile_error)
+    .into()
+}
+
+/// Implements `IAggregate` for every command and event handled by
+/// the type, i.e., for which it implements `ICommandHandler` and
+/// `IEventHandler`, and adds an inherent `aggregate_type()`.
+///
+/// The aggregate type is set with `#[aggregate(type = "...")]` and
+/// defaults to the snake case name of the type.
+///
+/// # Examples
+/// ```rust
+/// use serde::{
+///     Deserialize,
+///     Serialize,
+/// };
+///
+/// use cqrs_es2::{
+///     example_impl::{
+///         CustomerCommand,
+///         CustomerEvent,
+///     },
+///     Aggregate,
+///     Error,
+///     IAggregate,
+///     ICommandHandler,
+///     IEventHandler,
+/// };
+///
+/// #[derive(
+///     Debug,
+///     PartialEq,
+///     Default,
+///     Clone,
+///     Serialize,
+///     Deserialize,
+///     Aggregate
+/// )]
+/// #[aggregate(type = "customer")]
+/// pub struct Customer {
+///     name: String,
+/// }
+///
+/// impl ICommandHandler<CustomerCommand, CustomerEvent> for Customer {
+///     fn handle(
+///         &self,
+///         _command: CustomerCommand,
+///     ) -> Result<Vec<CustomerEvent>, Error> {
+///         Ok(Vec::new())
+///     }
+/// }
+///
+/// impl IEventHandler<CustomerEvent> for Customer {
+///     fn apply(
+///         &mut self,
+///         event: &CustomerEvent,
+///     ) {
+///         if let CustomerEvent::NameAdded(payload) = event {
+///             self.name = payload.changed_name.clone();
+///         }
+///     }
+/// }
+///
+/// assert_eq!(Customer::aggregate_type(), "customer");
+/// assert_eq!(
+///     <Customer as IAggregate<CustomerCommand, CustomerEvent>>::aggregate_type(),
+///     "customer"
+/// );
+/// ```
+#[proc_macro_derive(Aggregate, attributes(aggregate))]
+pub fn derive_aggregate(input: TokenStream) -> TokenStream {
+    aggregate::expand(&parse_macro_input!(
+        input as DeriveInput
+    ))
+    .unwrap_or_else(syn::Error::into_compile_error)
+    .into()
+}
+
+/// Implements `IQuery` for every command and event consumed by the
+/// type, i.e., for which it implements `IEventConsumer`, and adds an
+/// inherent `query_type()`.
+///
+/// The query type is set with `#[query(type = "...")]` and defaults
+/// to the snake case name of the type.
+///
+/// # Examples
+/// ```rust
+/// use serde::{
+///     Deserialize,
+///     Serialize,
+/// };
+///
+/// use cqrs_es2::{
+///     example_impl::{
+///         CustomerCommand,
+///         CustomerEvent,
+///     },
+///     EventContext,
+///     IEventConsumer,
+///     IQuery,
+///     Query,
+/// };
+///
+/// #[derive(
+///     Debug,
+///     PartialEq,
+///     Default,
+///     Clone,
+///     Serialize,
+///     Deserialize,
+///     Query
+/// )]
+/// #[query(type = "customer_contact_query")]
+/// pub struct CustomerContactQuery {
+///     name: String,
+/// }
+///
+/// impl IEventConsumer<CustomerCommand, CustomerEvent>
+///     for CustomerContactQuery
+/// {
+///     fn update(
+///         &mut self,
+///         event: &EventContext<CustomerCommand, CustomerEvent>,
+///     ) {
+///         if let CustomerEvent::NameAdded(payload) = &event.payload
+///         {
+///             self.name = payload.changed_name.clone();
+///         }
+///     }
+/// }
+///
+/// assert_eq!(
+///     CustomerContactQuery::query_type(),
+///     "customer_contact_query"
+/// );
+/// assert_eq!(
+///     <CustomerContactQuery as IQuery<
+///         CustomerCommand,
+///         CustomerEvent,
+///     >>::query_type(),
+///     "customer_contact_query"
+/// );
+/// ```
+#[proc_macro_derive(Query, attributes(query))]
+pub fn derive_query(input: TokenStream) -> TokenStream {
+    query::expand(&parse_macro_input!(
+        input as DeriveInput
+    ))
+    .unwrap_or_else(syn::Error::into_compile_error)
+    .into()
+}
@@ -0,0 +1,57 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{
+    parse_quote,
+    DeriveInput,
+    Result,
+};
+
+use crate::attributes::{
+    snake_case,
+    type_name,
+};
+
+pub(crate) fn expand(input: &DeriveInput) -> Result<TokenStream> {
+    let name = &input.ident;
+    let query_type = type_name(&input.attrs, "query")?
+        .unwrap_or_else(|| snake_case(&name.to_string()));
+
+    let (impl_generics, ty_generics, where_clause) =
+        input.generics.split_for_impl();
+
+    let mut generics = input.generics.clone();
+    generics
+        .params
+        .push(parse_quote!(__C: ::cqrs_es2::ICommand));
+    generics
+        .params
+        .push(parse_quote!(__E: ::cqrs_es2::IEvent));
+    generics
+        .make_where_clause()
+        .predicates
+        .push(parse_quote!(
+            Self: ::cqrs_es2::IEventConsumer<__C, __E>
+        ));
+    let (trait_impl_generics, _, trait_where_clause) =
+        generics.split_for_impl();
+
+    Ok(quote! {
+        #[automatically_derived]
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// The unique identifier of this query
+            #[must_use]
+            pub fn query_type() -> &'static str {
+                #query_type
+            }
+        }
+
+        #[automatically_derived]
+        impl #trait_impl_generics ::cqrs_es2::IQuery<__C, __E>
+            for #name #ty_generics #trait_where_clause
+        {
+            fn query_type() -> &'static str {
+                #query_type
+            }
+        }
+    })
+}
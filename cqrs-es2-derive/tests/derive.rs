@@ -0,0 +1,492 @@
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use cqrs_es2::{
+    Aggregate,
+    Command,
+    CqrsFramework,
+    Error,
+    Event,
+    EventContext,
+    HandlerTester,
+    IAggregate,
+    ICommand,
+    ICommandHandler,
+    IEvent,
+    IEventConsumer,
+    IEventHandler,
+    IQuery,
+    InMemoryEventStore,
+    Query,
+    SerializedEvent,
+};
+
+#[derive(Debug, PartialEq, Clone, Command)]
+enum AccountCommand {
+    Open { owner: String },
+    Deposit { amount: u64 },
+    Close,
+}
+
+#[derive(
+    Debug,
+    PartialEq,
+    Clone,
+    Serialize,
+    Deserialize,
+    Event
+)]
+enum AccountEvent {
+    Opened {
+        owner: String,
+    },
+    #[event(version = 3)]
+    Deposited {
+        amount: u64,
+    },
+    #[serde(rename = "account_closed")]
+    Closed,
+}
+
+#[derive(
+    Debug,
+    PartialEq,
+    Clone,
+    Serialize,
+    Deserialize,
+    Event
+)]
+#[serde(rename_all = "snake_case")]
+#[event(version = 2)]
+enum RenamedEvent {
+    FirstHappened,
+    #[event(version = 5)]
+    SecondHappened(u32),
+}
+
+/// Declares an event enum with acronyms in its variant names,
+/// renamed with the `rename_all` rule
+macro_rules! acronym_event {
+    ($name:ident, $rule:literal) => {
+        #[derive(
+            Debug,
+            PartialEq,
+            Clone,
+            Serialize,
+            Deserialize,
+            Event
+        )]
+        #[serde(rename_all = $rule)]
+        enum $name {
+            HTTPRequestSent,
+            UserIDChanged { id: u32 },
+        }
+
+        impl $name {
+            fn all() -> Vec<Self> {
+                vec![
+                    Self::HTTPRequestSent,
+                    Self::UserIDChanged { id: 1 },
+                ]
+            }
+        }
+    };
+}
+
+acronym_event!(LowerAcronymEvent, "lowercase");
+acronym_event!(UpperAcronymEvent, "UPPERCASE");
+acronym_event!(PascalAcronymEvent, "PascalCase");
+acronym_event!(CamelAcronymEvent, "camelCase");
+acronym_event!(SnakeAcronymEvent, "snake_case");
+acronym_event!(
+    ScreamingSnakeAcronymEvent,
+    "SCREAMING_SNAKE_CASE"
+);
+acronym_event!(KebabAcronymEvent, "kebab-case");
+acronym_event!(
+    ScreamingKebabAcronymEvent,
+    "SCREAMING-KEBAB-CASE"
+);
+
+/// The serialized name of a unit or struct variant
+fn serde_name<E: Serialize>(event: &E) -> String {
+    match serde_json::to_value(event).unwrap() {
+        serde_json::Value::String(x) => x,
+        serde_json::Value::Object(x) => {
+            x.keys().next().unwrap().clone()
+        },
+        x => panic!("unexpected serialized event {}", x),
+    }
+}
+
+fn assert_serde_names<E: IEvent + Serialize>(events: &[E]) {
+    for event in events {
+        assert_eq!(event.event_type(), serde_name(event));
+    }
+}
+
+#[derive(
+    Debug,
+    PartialEq,
+    Clone,
+    Serialize,
+    Deserialize,
+    Event
+)]
+struct StandaloneEvent {
+    value: String,
+}
+
+#[derive(
+    Debug,
+    PartialEq,
+    Default,
+    Clone,
+    Serialize,
+    Deserialize,
+    Aggregate
+)]
+#[aggregate(type = "account")]
+struct Account {
+    owner: String,
+    balance: u64,
+    closed: bool,
+}
+
+impl ICommandHandler<AccountCommand, AccountEvent> for Account {
+    fn handle(
+        &self,
+        command: AccountCommand,
+    ) -> Result<Vec<AccountEvent>, Error> {
+        if self.closed {
+            return Err(Error::new("the account is closed"));
+        }
+
+        match command {
+            AccountCommand::Open { owner } => {
+                Ok(vec![AccountEvent::Opened { owner }])
+            },
+            AccountCommand::Deposit { amount } => {
+                Ok(vec![AccountEvent::Deposited { amount }])
+            },
+            AccountCommand::Close => Ok(vec![AccountEvent::Closed]),
+        }
+    }
+}
+
+impl IEventHandler<AccountEvent> for Account {
+    fn apply(
+        &mut self,
+        event: &AccountEvent,
+    ) {
+        match event {
+            AccountEvent::Opened { owner } => {
+                self.owner.clone_from(owner);
+            },
+            AccountEvent::Deposited { amount } => {
+                self.balance += amount;
+            },
+            AccountEvent::Closed => self.closed = true,
+        }
+    }
+}
+
+#[derive(
+    Debug,
+    PartialEq,
+    Default,
+    Clone,
+    Serialize,
+    Deserialize,
+    Aggregate
+)]
+struct SavingsAccount {}
+
+impl ICommandHandler<AccountCommand, AccountEvent>
+    for SavingsAccount
+{
+    fn handle(
+        &self,
+        _command: AccountCommand,
+    ) -> Result<Vec<AccountEvent>, Error> {
+        Ok(Vec::new())
+    }
+}
+
+impl IEventHandler<AccountEvent> for SavingsAccount {
+    fn apply(
+        &mut self,
+        _event: &AccountEvent,
+    ) {
+    }
+}
+
+#[derive(
+    Debug,
+    PartialEq,
+    Default,
+    Clone,
+    Serialize,
+    Deserialize,
+    Query
+)]
+struct AccountBalanceQuery {
+    balance: u64,
+}
+
+impl IEventConsumer<AccountCommand, AccountEvent>
+    for AccountBalanceQuery
+{
+    fn update(
+        &mut self,
+        event: &EventContext<AccountCommand, AccountEvent>,
+    ) {
+        if let AccountEvent::Deposited { amount } = &event.payload {
+            self.balance += amount;
+        }
+    }
+}
+
+#[derive(
+    Debug,
+    PartialEq,
+    Default,
+    Clone,
+    Serialize,
+    Deserialize,
+    Query
+)]
+#[query(type = "balances")]
+struct RenamedQuery {}
+
+impl IEventConsumer<AccountCommand, AccountEvent> for RenamedQuery {
+    fn update(
+        &mut self,
+        _event: &EventContext<AccountCommand, AccountEvent>,
+    ) {
+    }
+}
+
+fn query_type<Q: IQuery<AccountCommand, AccountEvent>>(
+) -> &'static str {
+    Q::query_type()
+}
+
+fn aggregate_type<A: IAggregate<AccountCommand, AccountEvent>>(
+) -> &'static str {
+    A::aggregate_type()
+}
+
+#[test]
+fn test_command_names() {
+    let command = AccountCommand::Open {
+        owner: "John Doe".to_string(),
+    };
+
+    assert_eq!(command.command_name(), "Open");
+    assert_eq!(
+        AccountCommand::Deposit { amount: 10 }.command_name(),
+        "Deposit"
+    );
+    assert_eq!(
+        AccountCommand::Close.command_name(),
+        "Close"
+    );
+}
+
+#[test]
+fn test_event_types_and_versions() {
+    let events = [
+        AccountEvent::Opened {
+            owner: "John Doe".to_string(),
+        },
+        AccountEvent::Deposited { amount: 10 },
+        AccountEvent::Closed,
+    ];
+
+    let result: Vec<_> = events
+        .iter()
+        .map(|x| (x.event_type(), x.event_version()))
+        .collect();
+
+    assert_eq!(
+        result,
+        vec![
+            ("Opened", 1),
+            ("Deposited", 3),
+            ("account_closed", 1)
+        ]
+    );
+
+    // the event type is the serialized variant name
+    assert_eq!(
+        serde_json::to_value(&AccountEvent::Closed).unwrap(),
+        serde_json::json!("account_closed")
+    );
+
+    assert_eq!(
+        (
+            RenamedEvent::FirstHappened.event_type(),
+            RenamedEvent::FirstHappened.event_version()
+        ),
+        ("first_happened", 2)
+    );
+    assert_eq!(
+        (
+            RenamedEvent::SecondHappened(1).event_type(),
+            RenamedEvent::SecondHappened(1).event_version()
+        ),
+        ("second_happened", 5)
+    );
+
+    let event = StandaloneEvent {
+        value: String::new(),
+    };
+    assert_eq!(
+        (
+            event.event_type(),
+            event.event_version()
+        ),
+        ("StandaloneEvent", 1)
+    );
+}
+
+#[test]
+fn test_rename_all_matches_serde() {
+    assert_serde_names(&LowerAcronymEvent::all());
+    assert_serde_names(&UpperAcronymEvent::all());
+    assert_serde_names(&PascalAcronymEvent::all());
+    assert_serde_names(&CamelAcronymEvent::all());
+    assert_serde_names(&SnakeAcronymEvent::all());
+    assert_serde_names(&ScreamingSnakeAcronymEvent::all());
+    assert_serde_names(&KebabAcronymEvent::all());
+    assert_serde_names(&ScreamingKebabAcronymEvent::all());
+
+    assert_eq!(
+        SnakeAcronymEvent::HTTPRequestSent.event_type(),
+        "h_t_t_p_request_sent"
+    );
+    assert_eq!(
+        ScreamingKebabAcronymEvent::UserIDChanged { id: 1 }
+            .event_type(),
+        "USER-I-D-CHANGED"
+    );
+
+    // events with acronyms can be persisted
+    let context =
+        EventContext::<AccountCommand, SnakeAcronymEvent>::new(
+            "account-1".to_string(),
+            1,
+            SnakeAcronymEvent::HTTPRequestSent,
+            Default::default(),
+        );
+
+    let serialized =
+        SerializedEvent::from_context("account", &context).unwrap();
+
+    assert_eq!(
+        serialized.event_type,
+        "h_t_t_p_request_sent"
+    );
+    assert_eq!(
+        serialized
+            .into_context::<AccountCommand, SnakeAcronymEvent>()
+            .unwrap(),
+        context
+    );
+}
+
+#[test]
+fn test_aggregate_and_query_types() {
+    assert_eq!(Account::aggregate_type(), "account");
+    assert_eq!(aggregate_type::<Account>(), "account");
+    assert_eq!(
+        aggregate_type::<SavingsAccount>(),
+        "savings_account"
+    );
+
+    assert_eq!(
+        AccountBalanceQuery::query_type(),
+        "account_balance_query"
+    );
+    assert_eq!(
+        query_type::<AccountBalanceQuery>(),
+        "account_balance_query"
+    );
+    assert_eq!(query_type::<RenamedQuery>(), "balances");
+}
+
+#[test]
+fn test_derived_aggregate() {
+    HandlerTester::<AccountCommand, AccountEvent, Account>::default()
+        .given(vec![AccountEvent::Opened {
+            owner: "John Doe".to_string(),
+        }])
+        .when(AccountCommand::Deposit { amount: 10 })
+        .then_expect(vec![AccountEvent::Deposited {
+            amount: 10,
+        }]);
+
+    HandlerTester::<AccountCommand, AccountEvent, Account>::default()
+        .given_no_previous_events()
+        .when(AccountCommand::Close)
+        .then_expect(vec![AccountEvent::Closed]);
+
+    HandlerTester::<AccountCommand, AccountEvent, Account>::default()
+        .given(vec![AccountEvent::Closed])
+        .when(AccountCommand::Deposit { amount: 10 })
+        .then_expect_error("the account is closed");
+}
+
+#[test]
+fn test_derived_types_in_framework() {
+    let mut cqrs = CqrsFramework::new(
+        InMemoryEventStore::<AccountCommand, AccountEvent, Account>::default(),
+        Vec::new(),
+    );
+
+    cqrs.execute(
+        "account-1",
+        AccountCommand::Open {
+            owner: "John Doe".to_string(),
+        },
+    )
+    .unwrap();
+
+    let events = cqrs
+        .execute(
+            "account-1",
+            AccountCommand::Deposit { amount: 10 },
+        )
+        .unwrap();
+
+    let mut query = AccountBalanceQuery::default();
+
+    for event in &events {
+        query.update(event);
+    }
+
+    assert_eq!(query.balance, 10);
+}
+
+#[test]
+fn test_serialized_event_of_derived_event() {
+    let context = EventContext::<AccountCommand, AccountEvent>::new(
+        "account-1".to_string(),
+        2,
+        AccountEvent::Deposited { amount: 10 },
+        Default::default(),
+    );
+
+    let serialized = SerializedEvent::from_context(
+        Account::aggregate_type(),
+        &context,
+    )
+    .unwrap();
+
+    assert_eq!(serialized.event_type, "Deposited");
+    assert_eq!(serialized.event_version, 3);
+    assert_eq!(serialized.aggregate_type, "account");
+}
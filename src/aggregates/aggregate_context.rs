@@ -9,16 +9,12 @@ use crate::{
     events::IEvent,
 };
 
-use super::i_aggregate::IAggregate;
-
 /// Returns the aggregate and context around it that is needed when
-/// committing events in an event store implementation.
+/// committing events in an event store implementation. The aggregate
+/// is usually an `IAggregate`, or with the `async` feature, an
+/// `IAsyncAggregate`.
 #[derive(Debug, PartialEq, Clone)]
-pub struct AggregateContext<
-    C: ICommand,
-    E: IEvent,
-    A: IAggregate<C, E>,
-> {
+pub struct AggregateContext<C: ICommand, E: IEvent, A> {
     /// The aggregate ID of the aggregate instance that has been
     /// loaded.
     pub aggregate_id: String,
@@ -32,9 +28,7 @@ pub struct AggregateContext<
     _phantom: PhantomData<(C, E)>,
 }
 
-impl<C: ICommand, E: IEvent, A: IAggregate<C, E>>
-    AggregateContext<C, E, A>
-{
+impl<C: ICommand, E: IEvent, A: Debug> AggregateContext<C, E, A> {
     /// Constructor
     pub fn new(
         aggregate_id: String,
@@ -48,7 +42,7 @@ impl<C: ICommand, E: IEvent, A: IAggregate<C, E>>
             _phantom: PhantomData,
         };
 
-        trace!("Created new {:?}", x,);
+        trace!("Created new {x:?}");
 
         x
     }
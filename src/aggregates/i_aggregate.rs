@@ -145,6 +145,6 @@ pub trait IAggregate<C: ICommand, E: IEvent>:
     + IEventHandler<E>
     + Sync
     + Send {
-    /// aggregate_type is a unique identifier for this aggregate
+    /// `aggregate_type` is a unique identifier for this aggregate
     fn aggregate_type() -> &'static str;
 }
@@ -0,0 +1,427 @@
+use std::{
+    collections::HashMap,
+    marker::PhantomData,
+    time,
+};
+
+use crate::{
+    aggregates::AggregateContext,
+    commands::{
+        ICommand,
+        IValidateCommand,
+    },
+    cqrs::{
+        DispatchPipeline,
+        IDispatchBackend,
+        RetryPolicy,
+        SnapshotPolicy,
+    },
+    errors::Error,
+    events::{
+        EventContext,
+        IEvent,
+    },
+    metadata::{
+        IMetadataEnricher,
+        MetadataEnrichers,
+        COMMAND_ID_KEY,
+    },
+    middleware::{
+        ICommandMiddleware,
+        IEventMiddleware,
+    },
+    stores::ICommandIdStore,
+};
+
+use super::{
+    i_async_aggregate::IAsyncAggregate,
+    i_async_command_handler::IAsyncCommandHandler,
+    i_async_event_consumer::IAsyncEventConsumer,
+    i_async_event_store::IAsyncEventStore,
+    i_async_snapshot_store::IAsyncSnapshotStore,
+};
+
+/// Async counterpart of `CqrsFramework`. Executing a command goes
+/// through the same steps, awaiting the event and snapshot stores,
+/// the command handler and the consumers along the way. The shipped
+/// stores are used through a `SyncStoreAdapter`.
+///
+/// When a command id store is configured, a command executed with a
+/// command id, see `execute_with_command_id`, is recorded per
+/// aggregate instance. Executing it again within the retention
+/// window returns the events it originally produced without handling
+/// it again.
+///
+/// # Examples
+/// ```rust
+/// use cqrs_es2::{
+///     example_impl::{
+///         AddCustomerName,
+///         Customer,
+///         CustomerCommand,
+///         CustomerEvent,
+///     },
+///     AsyncCqrsFramework,
+///     InMemoryEventStore,
+///     SyncStoreAdapter,
+/// };
+///
+/// # tokio::runtime::Builder::new_current_thread()
+/// #     .build()
+/// #     .unwrap()
+/// #     .block_on(async {
+/// let mut cqrs = AsyncCqrsFramework::new(
+///     SyncStoreAdapter::new(InMemoryEventStore::<
+///         CustomerCommand,
+///         CustomerEvent,
+///         Customer,
+///     >::default()),
+///     Vec::new(),
+/// );
+///
+/// let events = cqrs
+///     .execute(
+///         "customer-1",
+///         CustomerCommand::AddCustomerName(AddCustomerName {
+///             changed_name: "John Doe".to_string(),
+///         }),
+///     )
+///     .await
+///     .unwrap();
+///
+/// assert_eq!(events[0].sequence, 1);
+/// # });
+/// ```
+pub struct AsyncCqrsFramework<C, E, A, ES>
+where
+    C: ICommand + 'static,
+    E: IEvent + 'static,
+    A: IAsyncAggregate<C, E> + 'static,
+    ES: IAsyncEventStore<C, E, A>, {
+    backend: AsyncBackend<C, E, A, ES>,
+    pipeline: DispatchPipeline<C, E, dyn ICommandIdStore + Send>,
+}
+
+impl<C, E, A, ES> AsyncCqrsFramework<C, E, A, ES>
+where
+    C: ICommand + 'static,
+    E: IEvent + 'static,
+    A: IAsyncAggregate<C, E> + 'static,
+    ES: IAsyncEventStore<C, E, A>,
+{
+    /// Constructor
+    pub fn new(
+        store: ES,
+        consumers: Vec<Box<dyn IAsyncEventConsumer<C, E>>>,
+    ) -> Self {
+        Self {
+            backend: AsyncBackend {
+                store,
+                consumers,
+                snapshot_store: None,
+                _phantom: PhantomData,
+            },
+            pipeline: DispatchPipeline::new(),
+        }
+    }
+
+    /// Appends a consumer to be notified of every committed event
+    #[must_use]
+    pub fn with_consumer(
+        mut self,
+        consumer: Box<dyn IAsyncEventConsumer<C, E>>,
+    ) -> Self {
+        self.backend.consumers.push(consumer);
+        self
+    }
+
+    /// Sets the snapshot store used to speed up loading aggregates
+    /// and the policy deciding when new snapshots are saved
+    #[must_use]
+    pub fn with_snapshot_store(
+        mut self,
+        snapshot_store: Box<dyn IAsyncSnapshotStore<C, E, A>>,
+        snapshot_policy: SnapshotPolicy,
+    ) -> Self {
+        self.backend.snapshot_store = Some(snapshot_store);
+        self.pipeline.snapshot_policy = snapshot_policy;
+        self
+    }
+
+    /// Sets the store recording the processed command ids and how
+    /// long they are kept to detect repeated commands
+    #[must_use]
+    pub fn with_command_id_store(
+        mut self,
+        command_id_store: Box<dyn ICommandIdStore + Send>,
+        retention: time::Duration,
+    ) -> Self {
+        self.pipeline.command_id_store = Some(command_id_store);
+        self.pipeline.command_id_retention = retention;
+        self
+    }
+
+    /// Appends an enricher adding information to the metadata of
+    /// every resulting event
+    #[must_use]
+    pub fn with_metadata_enricher<
+        M: IMetadataEnricher<C> + 'static,
+    >(
+        mut self,
+        enricher: M,
+    ) -> Self {
+        self.pipeline
+            .metadata_enrichers
+            .register(Box::new(enricher));
+        self
+    }
+
+    /// Replaces the chain of metadata enrichers, e.g., with
+    /// `MetadataEnrichers::standard()`
+    #[must_use]
+    pub fn with_metadata_enrichers(
+        mut self,
+        metadata_enrichers: MetadataEnrichers<C>,
+    ) -> Self {
+        self.pipeline.metadata_enrichers = metadata_enrichers;
+        self
+    }
+
+    /// Appends a middleware to the pipeline wrapping the dispatch of
+    /// every command
+    #[must_use]
+    pub fn with_command_middleware<
+        M: ICommandMiddleware<C, E> + 'static,
+    >(
+        mut self,
+        middleware: M,
+    ) -> Self {
+        self.pipeline
+            .command_middlewares
+            .register(Box::new(middleware));
+        self
+    }
+
+    /// Appends a middleware to the pipeline run on every event
+    /// before it is committed
+    #[must_use]
+    pub fn with_event_middleware<
+        M: IEventMiddleware<C, E> + 'static,
+    >(
+        mut self,
+        middleware: M,
+    ) -> Self {
+        self.pipeline
+            .event_middlewares
+            .register(Box::new(middleware));
+        self
+    }
+
+    /// Validates every command through `IValidateCommand` before
+    /// the aggregate is loaded
+    #[must_use]
+    pub fn with_command_validation(mut self) -> Self
+    where
+        C: IValidateCommand, {
+        self.pipeline.command_validator = Some(C::validate_command);
+        self
+    }
+
+    /// Sets the policy applied when committing events fails with an
+    /// `Error::ConcurrencyConflict`
+    #[must_use]
+    pub fn with_retry_policy(
+        mut self,
+        retry_policy: RetryPolicy,
+    ) -> Self {
+        self.pipeline.retry_policy = retry_policy;
+        self
+    }
+
+    /// Executes a command on the aggregate instance identified by
+    /// `aggregate_id` and returns the committed events.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` when the command is rejected by the
+    /// aggregate or when the event store fails.
+    pub async fn execute(
+        &mut self,
+        aggregate_id: &str,
+        command: C,
+    ) -> Result<Vec<EventContext<C, E>>, Error> {
+        self.execute_with_metadata(
+            aggregate_id,
+            command,
+            HashMap::new(),
+        )
+        .await
+    }
+
+    /// Executes a command identified by `command_id` on the
+    /// aggregate instance identified by `aggregate_id`. A command id
+    /// that has already been processed for the aggregate instance
+    /// returns the originally committed events instead.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` when the command is rejected by the
+    /// aggregate or when a store fails.
+    pub async fn execute_with_command_id(
+        &mut self,
+        aggregate_id: &str,
+        command_id: &str,
+        command: C,
+    ) -> Result<Vec<EventContext<C, E>>, Error> {
+        let mut metadata = HashMap::new();
+
+        metadata.insert(
+            COMMAND_ID_KEY.to_string(),
+            command_id.to_string(),
+        );
+
+        self.execute_with_metadata(aggregate_id, command, metadata)
+            .await
+    }
+
+    /// Executes a command on the aggregate instance identified by
+    /// `aggregate_id` and attaches `metadata` to every resulting
+    /// event. The metadata enrichers run once beforehand and keep the
+    /// entries supplied by the caller. A command id supplied under
+    /// `COMMAND_ID_KEY` makes the execution idempotent, see
+    /// `execute_with_command_id`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` when the command is rejected by the
+    /// aggregate or when a store fails.
+    pub async fn execute_with_metadata(
+        &mut self,
+        aggregate_id: &str,
+        command: C,
+        metadata: HashMap<String, String>,
+    ) -> Result<Vec<EventContext<C, E>>, Error> {
+        self.pipeline
+            .execute(
+                &mut self.backend,
+                aggregate_id,
+                command,
+                metadata,
+            )
+            .await
+    }
+
+    /// Loads an aggregate and saves a snapshot of its current state
+    /// regardless of the snapshot policy.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` when no snapshot store is configured or
+    /// when loading the aggregate or saving the snapshot fails.
+    pub async fn take_snapshot(
+        &mut self,
+        aggregate_id: &str,
+    ) -> Result<AggregateContext<C, E, A>, Error> {
+        self.pipeline
+            .take_snapshot(&mut self.backend, aggregate_id)
+            .await
+    }
+}
+
+/// The stores and consumers `AsyncCqrsFramework` runs its
+/// `DispatchPipeline` with
+struct AsyncBackend<C, E, A, ES>
+where
+    C: ICommand + 'static,
+    E: IEvent + 'static,
+    A: IAsyncAggregate<C, E> + 'static,
+    ES: IAsyncEventStore<C, E, A>, {
+    store: ES,
+    consumers: Vec<Box<dyn IAsyncEventConsumer<C, E>>>,
+    snapshot_store: Option<Box<dyn IAsyncSnapshotStore<C, E, A>>>,
+    _phantom: PhantomData<A>,
+}
+
+impl<C, E, A, ES> IDispatchBackend<C, E, A>
+    for AsyncBackend<C, E, A, ES>
+where
+    C: ICommand + 'static,
+    E: IEvent + 'static,
+    A: IAsyncAggregate<C, E> + 'static,
+    ES: IAsyncEventStore<C, E, A>,
+{
+    fn aggregate_type() -> &'static str {
+        A::aggregate_type()
+    }
+
+    async fn load_aggregate(
+        &mut self,
+        aggregate_id: &str,
+    ) -> Result<AggregateContext<C, E, A>, Error> {
+        let snapshot = match &mut self.snapshot_store {
+            None => None,
+            Some(x) => x.load_snapshot(aggregate_id).await?,
+        };
+
+        match snapshot {
+            None => {
+                self.store
+                    .load_aggregate(aggregate_id)
+                    .await
+            },
+            Some(x) => self.store.load_aggregate_from(x).await,
+        }
+    }
+
+    async fn load_events_after(
+        &mut self,
+        aggregate_id: &str,
+        version: i64,
+    ) -> Result<Vec<EventContext<C, E>>, Error> {
+        self.store
+            .load_events_after(aggregate_id, version)
+            .await
+    }
+
+    async fn handle(
+        aggregate: &A,
+        command: C,
+    ) -> Result<Vec<E>, Error> {
+        IAsyncCommandHandler::handle(aggregate, command).await
+    }
+
+    async fn commit(
+        &mut self,
+        events: Vec<EventContext<C, E>>,
+        expected_version: i64,
+    ) -> Result<(), Error> {
+        self.store
+            .commit(events, expected_version)
+            .await
+    }
+
+    async fn save_snapshot(
+        &mut self,
+        context: &AggregateContext<C, E, A>,
+    ) -> Result<(), Error> {
+        match &mut self.snapshot_store {
+            None => {
+                Err(Error::TechnicalError(
+                    "no snapshot store is configured".to_string(),
+                ))
+            },
+            Some(x) => x.save_snapshot(context).await,
+        }
+    }
+
+    async fn notify(
+        &mut self,
+        events: &[EventContext<C, E>],
+    ) {
+        for consumer in &mut self.consumers {
+            for event in events {
+                consumer.update(event).await;
+            }
+        }
+    }
+}
@@ -0,0 +1,42 @@
+use serde::{
+    de::DeserializeOwned,
+    Serialize,
+};
+use std::fmt::Debug;
+
+use crate::{
+    aggregates::IAggregate,
+    commands::ICommand,
+    events::{
+        IEvent,
+        IEventHandler,
+    },
+};
+
+use super::i_async_command_handler::IAsyncCommandHandler;
+
+/// Async counterpart of `IAggregate` where commands are handled
+/// through `IAsyncCommandHandler`. Every `IAggregate` is also an
+/// `IAsyncAggregate`.
+pub trait IAsyncAggregate<C: ICommand, E: IEvent>:
+    Debug
+    + PartialEq
+    + Default
+    + Clone
+    + Serialize
+    + DeserializeOwned
+    + IAsyncCommandHandler<C, E>
+    + IEventHandler<E>
+    + Sync
+    + Send {
+    /// `aggregate_type` is a unique identifier for this aggregate
+    fn aggregate_type() -> &'static str;
+}
+
+impl<C: ICommand + 'static, E: IEvent, A: IAggregate<C, E>>
+    IAsyncAggregate<C, E> for A
+{
+    fn aggregate_type() -> &'static str {
+        <A as IAggregate<C, E>>::aggregate_type()
+    }
+}
@@ -0,0 +1,88 @@
+use async_trait::async_trait;
+
+use crate::{
+    commands::{
+        ICommand,
+        ICommandHandler,
+    },
+    errors::Error,
+    events::IEvent,
+};
+
+/// Async counterpart of `ICommandHandler`. It allows awaiting other
+/// services or databases while validating a command.
+///
+/// Every `ICommandHandler` is also an `IAsyncCommandHandler`, so
+/// only handlers that actually need to await anything implement
+/// this trait directly.
+///
+/// # Example
+///
+/// For illustration only:
+///
+/// ```rust
+/// use async_trait::async_trait;
+///
+/// use cqrs_es2::{
+///     example_impl::{
+///         CustomerCommand,
+///         CustomerEvent,
+///         EmailUpdated,
+///     },
+///     Error,
+///     IAsyncCommandHandler,
+/// };
+///
+/// pub struct CustomerCommandHandler {};
+///
+/// #[async_trait]
+/// impl IAsyncCommandHandler<CustomerCommand, CustomerEvent>
+///     for CustomerCommandHandler
+/// {
+///     async fn handle(
+///         &self,
+///         command: CustomerCommand,
+///     ) -> Result<Vec<CustomerEvent>, Error> {
+///         match command {
+///             CustomerCommand::UpdateEmail(payload) => {
+///                 // e.g., await an email verification service
+///                 Ok(vec![CustomerEvent::EmailUpdated(
+///                     EmailUpdated {
+///                         new_email: payload.new_email,
+///                     },
+///                 )])
+///             },
+///             _ => Err(Error::new("unsupported command")),
+///         }
+///     }
+/// }
+/// ```
+#[async_trait]
+pub trait IAsyncCommandHandler<C: ICommand, E: IEvent> {
+    /// handle inbound command and return a vector of events or an
+    /// error
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` when the command is rejected by the
+    /// business rules of the handler.
+    async fn handle(
+        &self,
+        command: C,
+    ) -> Result<Vec<E>, Error>;
+}
+
+#[async_trait]
+impl<C, E, T> IAsyncCommandHandler<C, E> for T
+where
+    C: ICommand + 'static,
+    E: IEvent,
+    T: ICommandHandler<C, E> + Sync,
+{
+    async fn handle(
+        &self,
+        command: C,
+    ) -> Result<Vec<E>, Error> {
+        ICommandHandler::handle(self, command)
+    }
+}
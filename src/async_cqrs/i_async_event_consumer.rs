@@ -0,0 +1,37 @@
+use async_trait::async_trait;
+
+use crate::{
+    commands::ICommand,
+    events::{
+        EventContext,
+        IEvent,
+        IEventConsumer,
+    },
+};
+
+/// Async counterpart of `IEventConsumer`. Every `IEventConsumer`
+/// that is `Send` is also an `IAsyncEventConsumer`.
+#[async_trait]
+pub trait IAsyncEventConsumer<C: ICommand, E: IEvent>: Send {
+    /// Each implemented query is responsible for updating its stated
+    /// based on events passed via this method.
+    async fn update(
+        &mut self,
+        event: &EventContext<C, E>,
+    );
+}
+
+#[async_trait]
+impl<C, E, T> IAsyncEventConsumer<C, E> for T
+where
+    C: ICommand,
+    E: IEvent,
+    T: IEventConsumer<C, E> + Send,
+{
+    async fn update(
+        &mut self,
+        event: &EventContext<C, E>,
+    ) {
+        IEventConsumer::update(self, event);
+    }
+}
@@ -0,0 +1,115 @@
+use async_trait::async_trait;
+
+use crate::{
+    aggregates::AggregateContext,
+    commands::ICommand,
+    errors::Error,
+    events::{
+        EventContext,
+        IEvent,
+    },
+};
+
+use super::i_async_aggregate::IAsyncAggregate;
+
+/// Async counterpart of `IEventStore`. The shipped event stores are
+/// used through a `SyncStoreAdapter`, aggregates that only implement
+/// `IAsyncAggregate` need an event store implementing this trait
+/// directly.
+#[async_trait]
+pub trait IAsyncEventStore<C, E, A>: Send
+where
+    C: ICommand + 'static,
+    E: IEvent + 'static,
+    A: IAsyncAggregate<C, E> + 'static, {
+    /// Load all events for a particular `aggregate_id` ordered by
+    /// their `sequence`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` when the underlying storage can not be
+    /// read.
+    async fn load_events(
+        &mut self,
+        aggregate_id: &str,
+    ) -> Result<Vec<EventContext<C, E>>, Error>;
+
+    /// Load the events of a particular `aggregate_id` whose
+    /// `sequence` is strictly greater than `version`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` when the underlying storage can not be
+    /// read.
+    async fn load_events_after(
+        &mut self,
+        aggregate_id: &str,
+        version: i64,
+    ) -> Result<Vec<EventContext<C, E>>, Error> {
+        Ok(self
+            .load_events(aggregate_id)
+            .await?
+            .into_iter()
+            .filter(|x| x.sequence > version)
+            .collect())
+    }
+
+    /// Load the aggregate at its current state by replaying all of
+    /// its events on top of `A::default()`, see
+    /// `IEventStore::load_aggregate`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` when the events can not be loaded.
+    async fn load_aggregate(
+        &mut self,
+        aggregate_id: &str,
+    ) -> Result<AggregateContext<C, E, A>, Error> {
+        self.load_aggregate_from(AggregateContext::new(
+            aggregate_id.to_string(),
+            0,
+            A::default(),
+        ))
+        .await
+    }
+
+    /// Bring an aggregate up to its current state starting from a
+    /// previous state, usually a snapshot, by replaying only the
+    /// events committed after its `version`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` when the events can not be loaded.
+    async fn load_aggregate_from(
+        &mut self,
+        context: AggregateContext<C, E, A>,
+    ) -> Result<AggregateContext<C, E, A>, Error> {
+        let mut context = context;
+
+        let events = self
+            .load_events_after(&context.aggregate_id, context.version)
+            .await?;
+
+        for event in events {
+            context.payload.apply(&event.payload);
+            context.version = event.sequence;
+        }
+
+        Ok(context)
+    }
+
+    /// Commit new events of a single aggregate instance produced
+    /// from `expected_version`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error::ConcurrencyConflict` when the stored
+    /// version of the aggregate does not match `expected_version`,
+    /// and an `Error` when the events are not consistent or the
+    /// underlying storage can not be written.
+    async fn commit(
+        &mut self,
+        events: Vec<EventContext<C, E>>,
+        expected_version: i64,
+    ) -> Result<(), Error>;
+}
@@ -0,0 +1,43 @@
+use async_trait::async_trait;
+
+use crate::{
+    aggregates::AggregateContext,
+    commands::ICommand,
+    errors::Error,
+    events::IEvent,
+};
+
+use super::i_async_aggregate::IAsyncAggregate;
+
+/// Async counterpart of `ISnapshotStore`. The shipped snapshot stores
+/// are used through a `SyncStoreAdapter`.
+#[async_trait]
+pub trait IAsyncSnapshotStore<C, E, A>: Send
+where
+    C: ICommand,
+    E: IEvent,
+    A: IAsyncAggregate<C, E>, {
+    /// Load the latest snapshot of a particular `aggregate_id` if
+    /// one has been saved.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` when the underlying storage can not be
+    /// read or the snapshot can not be deserialized.
+    async fn load_snapshot(
+        &mut self,
+        aggregate_id: &str,
+    ) -> Result<Option<AggregateContext<C, E, A>>, Error>;
+
+    /// Save a snapshot replacing any previous one of the same
+    /// aggregate instance.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` when the snapshot can not be serialized or
+    /// the underlying storage can not be written.
+    async fn save_snapshot(
+        &mut self,
+        context: &AggregateContext<C, E, A>,
+    ) -> Result<(), Error>;
+}
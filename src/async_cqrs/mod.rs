@@ -0,0 +1,23 @@
+//! # async_cqrs
+//!
+//! Async counterparts of the command, event, store and dispatch
+//! interfaces, available with the `async` feature
+
+pub use async_cqrs_framework::AsyncCqrsFramework;
+pub use i_async_aggregate::IAsyncAggregate;
+pub use i_async_command_handler::IAsyncCommandHandler;
+pub use i_async_event_consumer::IAsyncEventConsumer;
+pub use i_async_event_store::IAsyncEventStore;
+pub use i_async_snapshot_store::IAsyncSnapshotStore;
+pub use sync_store_adapter::SyncStoreAdapter;
+
+mod async_cqrs_framework;
+mod i_async_aggregate;
+mod i_async_command_handler;
+mod i_async_event_consumer;
+mod i_async_event_store;
+mod i_async_snapshot_store;
+mod sync_store_adapter;
+
+#[cfg(test)]
+mod test;
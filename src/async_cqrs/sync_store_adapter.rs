@@ -0,0 +1,145 @@
+use async_trait::async_trait;
+
+use crate::{
+    aggregates::{
+        AggregateContext,
+        IAggregate,
+    },
+    commands::ICommand,
+    errors::Error,
+    events::{
+        EventContext,
+        IEvent,
+    },
+    stores::{
+        IEventStore,
+        ISnapshotStore,
+    },
+};
+
+use super::{
+    i_async_event_store::IAsyncEventStore,
+    i_async_snapshot_store::IAsyncSnapshotStore,
+};
+
+/// Makes any `IEventStore` an `IAsyncEventStore` and any
+/// `ISnapshotStore` an `IAsyncSnapshotStore`, e.g., to use the
+/// shipped stores with `AsyncCqrsFramework`. The calls of the wrapped
+/// store do not wait on anything and complete right away.
+///
+/// # Examples
+/// ```rust
+/// use cqrs_es2::{
+///     example_impl::{
+///         Customer,
+///         CustomerCommand,
+///         CustomerEvent,
+///     },
+///     IAsyncEventStore,
+///     InMemoryEventStore,
+///     SyncStoreAdapter,
+/// };
+///
+/// # tokio::runtime::Builder::new_current_thread()
+/// #     .build()
+/// #     .unwrap()
+/// #     .block_on(async {
+/// let mut store = SyncStoreAdapter::new(InMemoryEventStore::<
+///     CustomerCommand,
+///     CustomerEvent,
+///     Customer,
+/// >::default());
+///
+/// let context = store
+///     .load_aggregate("customer-1")
+///     .await
+///     .unwrap();
+///
+/// assert_eq!(context.version, 0);
+/// # });
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct SyncStoreAdapter<T> {
+    store: T,
+}
+
+impl<T> SyncStoreAdapter<T> {
+    /// Constructor
+    pub fn new(store: T) -> Self {
+        Self { store }
+    }
+
+    /// The wrapped store
+    pub fn store(&mut self) -> &mut T {
+        &mut self.store
+    }
+
+    /// Unwraps the wrapped store
+    pub fn into_inner(self) -> T {
+        self.store
+    }
+}
+
+#[async_trait]
+impl<C, E, A, T> IAsyncEventStore<C, E, A> for SyncStoreAdapter<T>
+where
+    C: ICommand + 'static,
+    E: IEvent + 'static,
+    A: IAggregate<C, E> + 'static,
+    T: IEventStore<C, E, A> + Send,
+{
+    async fn load_events(
+        &mut self,
+        aggregate_id: &str,
+    ) -> Result<Vec<EventContext<C, E>>, Error> {
+        self.store.load_events(aggregate_id)
+    }
+
+    async fn load_events_after(
+        &mut self,
+        aggregate_id: &str,
+        version: i64,
+    ) -> Result<Vec<EventContext<C, E>>, Error> {
+        self.store
+            .load_events_after(aggregate_id, version)
+    }
+
+    async fn load_aggregate_from(
+        &mut self,
+        context: AggregateContext<C, E, A>,
+    ) -> Result<AggregateContext<C, E, A>, Error> {
+        self.store.load_aggregate_from(context)
+    }
+
+    async fn commit(
+        &mut self,
+        events: Vec<EventContext<C, E>>,
+        expected_version: i64,
+    ) -> Result<(), Error> {
+        self.store
+            .commit(events, expected_version)
+    }
+}
+
+#[async_trait]
+impl<C, E, A, T> IAsyncSnapshotStore<C, E, A> for SyncStoreAdapter<T>
+where
+    C: ICommand + 'static,
+    E: IEvent,
+    A: IAggregate<C, E>,
+    T: ISnapshotStore<C, E, A> + Send,
+{
+    async fn load_snapshot(
+        &mut self,
+        aggregate_id: &str,
+    ) -> Result<Option<AggregateContext<C, E, A>>, Error> {
+        self.store.load_snapshot(aggregate_id)
+    }
+
+    async fn save_snapshot(
+        &mut self,
+        context: &AggregateContext<C, E, A>,
+    ) -> Result<(), Error> {
+        self.store.save_snapshot(context)
+    }
+}
@@ -0,0 +1,629 @@
+use async_trait::async_trait;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use std::{
+    collections::HashMap,
+    sync::{
+        Arc,
+        Mutex,
+    },
+    time,
+};
+
+use crate::{
+    example_impl::*,
+    Error,
+    EventContext,
+    ICommandIdStore,
+    ICommandMiddleware,
+    IEventConsumer,
+    IEventHandler,
+    IEventMiddleware,
+    IEventStore,
+    ISnapshotStore,
+    InMemoryCommandIdStore,
+    InMemoryEventStore,
+    InMemorySnapshotStore,
+    RetryPolicy,
+    SnapshotPolicy,
+};
+
+use super::{
+    async_cqrs_framework::AsyncCqrsFramework,
+    i_async_aggregate::IAsyncAggregate,
+    i_async_command_handler::IAsyncCommandHandler,
+    i_async_event_consumer::IAsyncEventConsumer,
+    i_async_event_store::IAsyncEventStore,
+    i_async_snapshot_store::IAsyncSnapshotStore,
+    sync_store_adapter::SyncStoreAdapter,
+};
+
+type ThisEventStore =
+    InMemoryEventStore<CustomerCommand, CustomerEvent, Customer>;
+
+type ThisSnapshotStore =
+    InMemorySnapshotStore<CustomerCommand, CustomerEvent, Customer>;
+
+type Events =
+    Arc<Mutex<Vec<EventContext<CustomerCommand, CustomerEvent>>>>;
+
+#[derive(Default, Clone)]
+struct SyncConsumer {
+    events: Events,
+}
+
+impl IEventConsumer<CustomerCommand, CustomerEvent> for SyncConsumer {
+    fn update(
+        &mut self,
+        event: &EventContext<CustomerCommand, CustomerEvent>,
+    ) {
+        self.events
+            .lock()
+            .unwrap()
+            .push(event.clone());
+    }
+}
+
+#[derive(Default, Clone)]
+struct AsyncConsumer {
+    events: Events,
+}
+
+#[async_trait]
+impl IAsyncEventConsumer<CustomerCommand, CustomerEvent>
+    for AsyncConsumer
+{
+    async fn update(
+        &mut self,
+        event: &EventContext<CustomerCommand, CustomerEvent>,
+    ) {
+        tokio::task::yield_now().await;
+
+        self.events
+            .lock()
+            .unwrap()
+            .push(event.clone());
+    }
+}
+
+fn add_address(address: &str) -> CustomerCommand {
+    CustomerCommand::AddAddress(AddAddress {
+        new_address: address.to_string(),
+    })
+}
+
+#[tokio::test]
+async fn test_sync_adapters() {
+    let customer = Customer::default();
+
+    assert_eq!(
+        IAsyncCommandHandler::handle(&customer, add_address("home"))
+            .await
+            .unwrap(),
+        vec![CustomerEvent::AddressUpdated(
+            AddressUpdated {
+                new_address: "home".to_string(),
+            }
+        )]
+    );
+
+    assert_eq!(
+        <Customer as IAsyncAggregate<
+            CustomerCommand,
+            CustomerEvent,
+        >>::aggregate_type(),
+        "customer"
+    );
+
+    let mut store = SyncStoreAdapter::new(ThisEventStore::default());
+
+    IAsyncEventStore::commit(
+        &mut store,
+        vec![EventContext::new(
+            "test_id_A".to_string(),
+            1,
+            CustomerEvent::NameAdded(NameAdded {
+                changed_name: "John Doe".to_string(),
+            }),
+            HashMap::default(),
+        )],
+        0,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(
+        IAsyncEventStore::load_events(&mut store, "test_id_A")
+            .await
+            .unwrap(),
+        IEventStore::load_events(store.store(), "test_id_A").unwrap()
+    );
+
+    let mut snapshots =
+        SyncStoreAdapter::new(ThisSnapshotStore::default());
+
+    let context =
+        IAsyncEventStore::load_aggregate(&mut store, "test_id_A")
+            .await
+            .unwrap();
+
+    IAsyncSnapshotStore::save_snapshot(&mut snapshots, &context)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        IAsyncSnapshotStore::load_snapshot(
+            &mut snapshots,
+            "test_id_A"
+        )
+        .await
+        .unwrap(),
+        snapshots
+            .into_inner()
+            .load_snapshot("test_id_A")
+            .unwrap()
+    );
+}
+
+#[tokio::test]
+async fn test_execute() {
+    let store = ThisEventStore::default();
+    let sync_consumer = SyncConsumer::default();
+    let async_consumer = AsyncConsumer::default();
+
+    let mut cqrs = AsyncCqrsFramework::new(
+        SyncStoreAdapter::new(store.clone()),
+        vec![Box::new(sync_consumer.clone())],
+    )
+    .with_consumer(Box::new(async_consumer.clone()));
+
+    cqrs.execute("test_id_A", add_address("home"))
+        .await
+        .unwrap();
+
+    let events = cqrs
+        .execute("test_id_A", add_address("work"))
+        .await
+        .unwrap();
+
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].sequence, 2);
+
+    assert!(cqrs
+        .execute("test_id_A", add_address("work"))
+        .await
+        .is_err());
+
+    assert_eq!(
+        store
+            .clone()
+            .load_aggregate("test_id_A")
+            .unwrap()
+            .payload
+            .addresses,
+        vec!["home".to_string(), "work".to_string()]
+    );
+
+    assert_eq!(
+        sync_consumer
+            .events
+            .lock()
+            .unwrap()
+            .len(),
+        2
+    );
+    assert_eq!(
+        *async_consumer.events.lock().unwrap(),
+        *sync_consumer.events.lock().unwrap()
+    );
+}
+
+/// An aggregate that only implements the async interfaces
+#[derive(
+    Debug,
+    PartialEq,
+    Default,
+    Clone,
+    Serialize,
+    Deserialize
+)]
+struct VerifiedCustomer {
+    email: String,
+}
+
+#[async_trait]
+impl IAsyncCommandHandler<CustomerCommand, CustomerEvent>
+    for VerifiedCustomer
+{
+    async fn handle(
+        &self,
+        command: CustomerCommand,
+    ) -> Result<Vec<CustomerEvent>, Error> {
+        match command {
+            CustomerCommand::UpdateEmail(payload) => {
+                // stands in for a call to a verification service
+                tokio::task::yield_now().await;
+
+                if !payload.new_email.contains('@') {
+                    return Err(Error::new("invalid email"));
+                }
+
+                Ok(vec![CustomerEvent::EmailUpdated(
+                    EmailUpdated {
+                        new_email: payload.new_email,
+                    },
+                )])
+            },
+            _ => Err(Error::new("unsupported command")),
+        }
+    }
+}
+
+impl IEventHandler<CustomerEvent> for VerifiedCustomer {
+    fn apply(
+        &mut self,
+        event: &CustomerEvent,
+    ) {
+        if let CustomerEvent::EmailUpdated(payload) = event {
+            self.email
+                .clone_from(&payload.new_email);
+        }
+    }
+}
+
+impl IAsyncAggregate<CustomerCommand, CustomerEvent>
+    for VerifiedCustomer
+{
+    fn aggregate_type() -> &'static str {
+        "verified_customer"
+    }
+}
+
+#[derive(Default)]
+struct VerifiedCustomerStore {
+    events: Vec<EventContext<CustomerCommand, CustomerEvent>>,
+}
+
+#[async_trait]
+impl
+    IAsyncEventStore<CustomerCommand, CustomerEvent, VerifiedCustomer>
+    for VerifiedCustomerStore
+{
+    async fn load_events(
+        &mut self,
+        aggregate_id: &str,
+    ) -> Result<
+        Vec<EventContext<CustomerCommand, CustomerEvent>>,
+        Error,
+    > {
+        Ok(self
+            .events
+            .iter()
+            .filter(|x| x.aggregate_id == aggregate_id)
+            .cloned()
+            .collect())
+    }
+
+    async fn commit(
+        &mut self,
+        events: Vec<EventContext<CustomerCommand, CustomerEvent>>,
+        expected_version: i64,
+    ) -> Result<(), Error> {
+        let actual = self
+            .events
+            .iter()
+            .filter(|x| x.aggregate_id == events[0].aggregate_id)
+            .map(|x| x.sequence)
+            .max()
+            .unwrap_or(0);
+
+        if actual != expected_version {
+            return Err(Error::ConcurrencyConflict {
+                aggregate_id: events[0].aggregate_id.clone(),
+                expected: expected_version,
+                actual,
+            });
+        }
+
+        self.events.extend(events);
+
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn test_execute_async_aggregate() {
+    let mut cqrs = AsyncCqrsFramework::new(
+        VerifiedCustomerStore::default(),
+        Vec::new(),
+    )
+    .with_retry_policy(RetryPolicy::Bounded(1));
+
+    assert_eq!(
+        cqrs.execute(
+            "test_id_A",
+            CustomerCommand::UpdateEmail(UpdateEmail {
+                new_email: "invalid".to_string(),
+            }),
+        )
+        .await,
+        Err(Error::new("invalid email"))
+    );
+
+    let events = cqrs
+        .execute(
+            "test_id_A",
+            CustomerCommand::UpdateEmail(UpdateEmail {
+                new_email: "j@d.com".to_string(),
+            }),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].sequence, 1);
+
+    // without a snapshot store, snapshots can not be taken
+    assert!(cqrs
+        .take_snapshot("test_id_A")
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_snapshots() {
+    let store = ThisEventStore::default();
+    let snapshots = ThisSnapshotStore::default();
+
+    let mut cqrs = AsyncCqrsFramework::new(
+        SyncStoreAdapter::new(store.clone()),
+        Vec::new(),
+    )
+    .with_snapshot_store(
+        Box::new(SyncStoreAdapter::new(snapshots.clone())),
+        SnapshotPolicy::EveryNEvents(2),
+    );
+
+    cqrs.execute("test_id_A", add_address("home"))
+        .await
+        .unwrap();
+
+    assert_eq!(
+        snapshots
+            .clone()
+            .load_snapshot("test_id_A")
+            .unwrap(),
+        None
+    );
+
+    cqrs.execute("test_id_A", add_address("work"))
+        .await
+        .unwrap();
+
+    let snapshot = snapshots
+        .clone()
+        .load_snapshot("test_id_A")
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(snapshot.version, 2);
+
+    // a snapshot that disagrees with the event history proves that
+    // the aggregate is rehydrated from it
+    let mut snapshot = snapshot;
+    snapshot.payload.name = "Snapshot Doe".to_string();
+
+    snapshots
+        .clone()
+        .save_snapshot(&snapshot)
+        .unwrap();
+
+    let context = cqrs
+        .take_snapshot("test_id_A")
+        .await
+        .unwrap();
+
+    assert_eq!(context.version, 2);
+    assert_eq!(context.payload.name, "Snapshot Doe");
+
+    cqrs.execute("test_id_A", add_address("gym"))
+        .await
+        .unwrap();
+
+    assert_eq!(
+        store
+            .clone()
+            .load_aggregate("test_id_A")
+            .unwrap()
+            .version,
+        3
+    );
+}
+
+#[test]
+fn test_execute_is_send() {
+    fn assert_send<T: Send>(_: T) {}
+
+    let mut cqrs = AsyncCqrsFramework::new(
+        SyncStoreAdapter::new(ThisEventStore::default()),
+        Vec::new(),
+    )
+    .with_command_id_store(
+        Box::new(InMemoryCommandIdStore::default()),
+        time::Duration::from_secs(60),
+    );
+
+    assert_send(cqrs.execute("test_id_A", add_address("home")));
+}
+
+#[tokio::test]
+async fn test_command_validation() {
+    let mut cqrs = AsyncCqrsFramework::new(
+        SyncStoreAdapter::new(ThisEventStore::default()),
+        Vec::new(),
+    )
+    .with_command_validation();
+
+    match cqrs
+        .execute("test_id_A", add_address(" "))
+        .await
+    {
+        Err(Error::Validation(x)) => {
+            assert_eq!(
+                x.params.unwrap()["new_address"],
+                "is required"
+            );
+        },
+        x => {
+            panic!(
+                "expected a validation error, found {:?}",
+                x
+            )
+        },
+    }
+
+    cqrs.execute("test_id_A", add_address("home"))
+        .await
+        .unwrap();
+}
+
+struct TenantAnnotator;
+
+impl IEventMiddleware<CustomerCommand, CustomerEvent>
+    for TenantAnnotator
+{
+    fn process(
+        &mut self,
+        event: &mut EventContext<CustomerCommand, CustomerEvent>,
+    ) -> Result<(), Error> {
+        event
+            .metadata
+            .insert("tenant".to_string(), "acme".to_string());
+
+        Ok(())
+    }
+}
+
+struct AdminOnly;
+
+impl ICommandMiddleware<CustomerCommand, CustomerEvent>
+    for AdminOnly
+{
+    fn before(
+        &mut self,
+        _aggregate_id: &str,
+        _command: &CustomerCommand,
+        metadata: &HashMap<String, String>,
+    ) -> Result<(), Error> {
+        match metadata.get("user").map(String::as_str) {
+            Some("admin") => Ok(()),
+            _ => Err(Error::unauthorized("admins only")),
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_middleware() {
+    let mut cqrs = AsyncCqrsFramework::new(
+        SyncStoreAdapter::new(ThisEventStore::default()),
+        Vec::new(),
+    )
+    .with_command_middleware(AdminOnly)
+    .with_event_middleware(TenantAnnotator);
+
+    assert_eq!(
+        cqrs.execute("test_id_A", add_address("home"))
+            .await,
+        Err(Error::unauthorized("admins only"))
+    );
+
+    let mut metadata = HashMap::new();
+    metadata.insert("user".to_string(), "admin".to_string());
+
+    let events = cqrs
+        .execute_with_metadata(
+            "test_id_A",
+            add_address("home"),
+            metadata,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(events[0].metadata["tenant"], "acme");
+}
+
+#[tokio::test]
+async fn test_repeated_command_id() {
+    let store = ThisEventStore::default();
+    let consumer = SyncConsumer::default();
+    let mut command_ids = InMemoryCommandIdStore::default();
+
+    let mut cqrs = AsyncCqrsFramework::new(
+        SyncStoreAdapter::new(store.clone()),
+        vec![Box::new(consumer.clone())],
+    )
+    .with_command_id_store(
+        Box::new(command_ids.clone()),
+        time::Duration::from_secs(60 * 60),
+    );
+
+    cqrs.execute("test_id_A", add_address("home"))
+        .await
+        .unwrap();
+
+    let events = cqrs
+        .execute_with_command_id(
+            "test_id_A",
+            "command-1",
+            add_address("work"),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].sequence, 2);
+
+    let processed = command_ids
+        .load("test_id_A", "command-1")
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(processed.from_version, 1);
+    assert_eq!(processed.to_version, 2);
+
+    // handling the command again would reject the known address
+    let repeated = cqrs
+        .execute_with_command_id(
+            "test_id_A",
+            "command-1",
+            add_address("work"),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(repeated, events);
+    assert_eq!(
+        IEventStore::load_events(&mut store.clone(), "test_id_A")
+            .unwrap()
+            .len(),
+        2
+    );
+    assert_eq!(consumer.events.lock().unwrap().len(), 2);
+
+    // without a store, command ids do not deduplicate
+    let mut cqrs = AsyncCqrsFramework::new(
+        SyncStoreAdapter::new(store),
+        Vec::new(),
+    );
+
+    assert!(cqrs
+        .execute_with_command_id(
+            "test_id_A",
+            "command-1",
+            add_address("work"),
+        )
+        .await
+        .is_err());
+}
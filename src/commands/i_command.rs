@@ -1,4 +1,7 @@
-use std::fmt::Debug;
+use std::{
+    any::type_name,
+    fmt::Debug,
+};
 
 /// An `ICommand` represents business API call.
 ///
@@ -12,7 +15,8 @@ use std::fmt::Debug;
 /// same name as the element, and elements that do not require
 /// additional information use an empty payload.
 ///
-/// Though the `ICommand` trait only has a single function, the
+/// The `ICommand` trait only names the command, an enum overrides
+/// `command_name` to name its elements rather than itself. The
 /// commands must also derive a number of standard traits.
 /// - `Clone` - events may be cloned throughout the framework
 /// - `Debug` and `PartialEq` - needed for effective testing
@@ -39,6 +43,29 @@ use std::fmt::Debug;
 ///     new_email: String,
 /// }
 ///
-/// impl ICommand for CustomerCommand {};
+/// impl ICommand for CustomerCommand {
+///     fn command_name(&self) -> &'static str {
+///         match self {
+///             CustomerCommand::AddCustomerName(_) => {
+///                 "AddCustomerName"
+///             },
+///             CustomerCommand::UpdateEmail(_) => "UpdateEmail",
+///         }
+///     }
+/// };
+///
+/// let command = CustomerCommand::UpdateEmail(UpdateEmail {
+///     new_email: "john@example.com".to_string(),
+/// });
+///
+/// assert_eq!(command.command_name(), "UpdateEmail");
 /// ```
-pub trait ICommand: Debug + PartialEq + Clone + Sync + Send {}
+pub trait ICommand: Debug + PartialEq + Clone + Sync + Send {
+    /// The name of the command, the name of its type by default
+    fn command_name(&self) -> &'static str {
+        let name = type_name::<Self>();
+        let name = name.split('<').next().unwrap_or(name);
+
+        name.rsplit("::").next().unwrap_or(name)
+    }
+}
@@ -67,6 +67,11 @@ use super::i_command::ICommand;
 pub trait ICommandHandler<C: ICommand, E: IEvent> {
     /// handle inbound command and return a vector of events or an
     /// error
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` when the command is rejected by the
+    /// business rules of the handler.
     fn handle(
         &self,
         command: C,
@@ -0,0 +1,62 @@
+use crate::errors::{
+    Error,
+    ValidationErrors,
+};
+
+/// The validation run by the frameworks before handling a command
+pub(crate) type CommandValidator<C> = fn(&C) -> Result<(), Error>;
+
+/// Stateless validation of a command, e.g., required fields, formats
+/// and length limits, which does not depend on the state of the
+/// aggregate. A `CqrsFramework` configured with
+/// `with_command_validation` validates every command before the
+/// aggregate is loaded and rejects invalid commands with an
+/// `Error::Validation`, whose `params` map each invalid field to a
+/// message. Business rules stay in `ICommandHandler::handle`.
+///
+/// # Examples
+/// ```rust
+/// use cqrs_es2::{
+///     example_impl::{
+///         CustomerCommand,
+///         UpdateEmail,
+///     },
+///     Error,
+///     IValidateCommand,
+/// };
+///
+/// let command = CustomerCommand::UpdateEmail(UpdateEmail {
+///     new_email: "john.doe".to_string(),
+/// });
+///
+/// match command.validate_command() {
+///     Err(Error::Validation(x)) => {
+///         assert_eq!(
+///             x.params.unwrap()["new_email"],
+///             "is not a valid email address"
+///         );
+///     },
+///     _ => panic!("expected a validation error"),
+/// }
+/// ```
+pub trait IValidateCommand {
+    /// Records the validation failures of the command in `errors`
+    fn validate(
+        &self,
+        errors: &mut ValidationErrors,
+    );
+
+    /// Validates the command and converts the failures into an
+    /// `Error::Validation`
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error::Validation` when the command is invalid.
+    fn validate_command(&self) -> Result<(), Error> {
+        let mut errors = ValidationErrors::default();
+
+        self.validate(&mut errors);
+
+        errors.into_result()
+    }
+}
@@ -4,6 +4,11 @@
 
 pub use i_command::ICommand;
 pub use i_command_handler::ICommandHandler;
+pub(crate) use i_validate_command::CommandValidator;
+pub use i_validate_command::IValidateCommand;
+pub use processed_command::ProcessedCommand;
 
 mod i_command;
 mod i_command_handler;
+mod i_validate_command;
+mod processed_command;
@@ -0,0 +1,45 @@
+use chrono::{
+    DateTime,
+    Utc,
+};
+
+/// The record of a command that has been executed with a command id.
+/// The events it produced are the events of the aggregate instance
+/// whose sequence is greater than `from_version` and up to
+/// `to_version`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ProcessedCommand {
+    /// The id of the aggregate instance.
+    pub aggregate_id: String,
+
+    /// The id supplied by the caller to identify the command.
+    pub command_id: String,
+
+    /// The version of the aggregate the command was handled on.
+    pub from_version: i64,
+
+    /// The version of the aggregate after its events were committed.
+    pub to_version: i64,
+
+    /// The time the command was processed at.
+    pub processed_at: DateTime<Utc>,
+}
+
+impl ProcessedCommand {
+    /// Constructor of a command processed right now
+    #[must_use]
+    pub fn new(
+        aggregate_id: &str,
+        command_id: &str,
+        from_version: i64,
+        to_version: i64,
+    ) -> Self {
+        Self {
+            aggregate_id: aggregate_id.to_string(),
+            command_id: command_id.to_string(),
+            from_version,
+            to_version,
+            processed_at: Utc::now(),
+        }
+    }
+}
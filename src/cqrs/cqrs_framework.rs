@@ -0,0 +1,454 @@
+use std::{
+    collections::HashMap,
+    marker::PhantomData,
+    time,
+};
+
+use crate::{
+    aggregates::{
+        AggregateContext,
+        IAggregate,
+    },
+    commands::{
+        ICommand,
+        ICommandHandler,
+        IValidateCommand,
+    },
+    errors::Error,
+    events::{
+        EventContext,
+        IEvent,
+        IEventConsumer,
+    },
+    metadata::{
+        IMetadataEnricher,
+        MetadataEnrichers,
+        COMMAND_ID_KEY,
+    },
+    middleware::{
+        ICommandMiddleware,
+        IEventMiddleware,
+    },
+    stores::{
+        ICommandIdStore,
+        IEventStore,
+        ISnapshotStore,
+    },
+};
+
+use super::{
+    dispatch_pipeline::{
+        run_sync,
+        DispatchPipeline,
+        IDispatchBackend,
+    },
+    i_command_dispatcher::ICommandDispatcher,
+    retry_policy::RetryPolicy,
+    snapshot_policy::SnapshotPolicy,
+};
+
+/// `CqrsFramework` is the command dispatcher that ties all
+/// components together. Executing a command goes through the
+/// following steps:
+///
+/// 1. the aggregate is loaded from the event store, starting from its
+///    latest snapshot when a snapshot store is configured
+/// 2. the command is handled by the aggregate
+/// 3. the resulting events are wrapped into `EventContext`s with the
+///    next sequence numbers and the caller metadata, enriched by the
+///    metadata enrichers, passed through the event middlewares and
+///    applied to the aggregate
+/// 4. the events are committed to the event store
+/// 5. the committed events are passed to all registered consumers
+///
+/// The whole dispatch is wrapped by the `before`, `after` and
+/// `on_error` hooks of the command middlewares, see
+/// `ICommandMiddleware`.
+///
+/// A framework configured with `with_command_validation` rejects
+/// invalid commands before the aggregate is loaded, see
+/// `IValidateCommand`.
+///
+/// When a command id store is configured, a command executed with a
+/// command id, see `execute_with_command_id`, is recorded per
+/// aggregate instance. Executing it again within the retention
+/// window returns the events it originally produced without handling
+/// it again.
+///
+/// # Examples
+/// ```rust
+/// use cqrs_es2::{
+///     example_impl::{
+///         AddCustomerName,
+///         Customer,
+///         CustomerCommand,
+///         CustomerEvent,
+///     },
+///     CqrsFramework,
+///     InMemoryEventStore,
+/// };
+///
+/// let mut cqrs =
+///     CqrsFramework::new(
+///         InMemoryEventStore::<
+///             CustomerCommand,
+///             CustomerEvent,
+///             Customer,
+///         >::default(),
+///         Vec::new(),
+///     );
+///
+/// let events = cqrs
+///     .execute(
+///         "customer-1",
+///         CustomerCommand::AddCustomerName(AddCustomerName {
+///             changed_name: "John Doe".to_string(),
+///         }),
+///     )
+///     .unwrap();
+///
+/// assert_eq!(events.len(), 1);
+/// assert_eq!(events[0].sequence, 1);
+/// ```
+pub struct CqrsFramework<
+    C: ICommand,
+    E: IEvent,
+    A: IAggregate<C, E>,
+    ES: IEventStore<C, E, A>,
+> {
+    backend: SyncBackend<C, E, A, ES>,
+    pipeline: DispatchPipeline<C, E, dyn ICommandIdStore>,
+}
+
+impl<
+        C: ICommand,
+        E: IEvent,
+        A: IAggregate<C, E>,
+        ES: IEventStore<C, E, A>,
+    > CqrsFramework<C, E, A, ES>
+{
+    /// Constructor
+    pub fn new(
+        store: ES,
+        consumers: Vec<Box<dyn IEventConsumer<C, E>>>,
+    ) -> Self {
+        Self {
+            backend: SyncBackend {
+                store,
+                consumers,
+                snapshot_store: None,
+                _phantom: PhantomData,
+            },
+            pipeline: DispatchPipeline::new(),
+        }
+    }
+
+    /// Sets the snapshot store used to speed up loading aggregates
+    /// and the policy deciding when new snapshots are saved
+    #[must_use]
+    pub fn with_snapshot_store(
+        mut self,
+        snapshot_store: Box<dyn ISnapshotStore<C, E, A>>,
+        snapshot_policy: SnapshotPolicy,
+    ) -> Self {
+        self.backend.snapshot_store = Some(snapshot_store);
+        self.pipeline.snapshot_policy = snapshot_policy;
+        self
+    }
+
+    /// Sets the store recording the processed command ids and how
+    /// long they are kept to detect repeated commands
+    #[must_use]
+    pub fn with_command_id_store(
+        mut self,
+        command_id_store: Box<dyn ICommandIdStore>,
+        retention: time::Duration,
+    ) -> Self {
+        self.pipeline.command_id_store = Some(command_id_store);
+        self.pipeline.command_id_retention = retention;
+        self
+    }
+
+    /// Appends an enricher adding information to the metadata of
+    /// every resulting event
+    #[must_use]
+    pub fn with_metadata_enricher<
+        M: IMetadataEnricher<C> + 'static,
+    >(
+        mut self,
+        enricher: M,
+    ) -> Self {
+        self.pipeline
+            .metadata_enrichers
+            .register(Box::new(enricher));
+        self
+    }
+
+    /// Replaces the chain of metadata enrichers, e.g., with
+    /// `MetadataEnrichers::standard()`
+    #[must_use]
+    pub fn with_metadata_enrichers(
+        mut self,
+        metadata_enrichers: MetadataEnrichers<C>,
+    ) -> Self {
+        self.pipeline.metadata_enrichers = metadata_enrichers;
+        self
+    }
+
+    /// Appends a middleware to the pipeline wrapping the dispatch of
+    /// every command
+    #[must_use]
+    pub fn with_command_middleware<
+        M: ICommandMiddleware<C, E> + 'static,
+    >(
+        mut self,
+        middleware: M,
+    ) -> Self {
+        self.pipeline
+            .command_middlewares
+            .register(Box::new(middleware));
+        self
+    }
+
+    /// Appends a middleware to the pipeline run on every event
+    /// before it is committed
+    #[must_use]
+    pub fn with_event_middleware<
+        M: IEventMiddleware<C, E> + 'static,
+    >(
+        mut self,
+        middleware: M,
+    ) -> Self {
+        self.pipeline
+            .event_middlewares
+            .register(Box::new(middleware));
+        self
+    }
+
+    /// Validates every command through `IValidateCommand` before
+    /// the aggregate is loaded
+    #[must_use]
+    pub fn with_command_validation(mut self) -> Self
+    where
+        C: IValidateCommand, {
+        self.pipeline.command_validator = Some(C::validate_command);
+        self
+    }
+
+    /// Sets the policy applied when committing events fails with an
+    /// `Error::ConcurrencyConflict`
+    #[must_use]
+    pub fn with_retry_policy(
+        mut self,
+        retry_policy: RetryPolicy,
+    ) -> Self {
+        self.pipeline.retry_policy = retry_policy;
+        self
+    }
+
+    /// Appends a consumer to be notified of every committed event
+    #[must_use]
+    pub fn with_consumer(
+        mut self,
+        consumer: Box<dyn IEventConsumer<C, E>>,
+    ) -> Self {
+        self.backend.consumers.push(consumer);
+        self
+    }
+
+    /// Executes a command on the aggregate instance identified by
+    /// `aggregate_id` and returns the committed events.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` when the command is rejected by the
+    /// aggregate or when the event store fails.
+    pub fn execute(
+        &mut self,
+        aggregate_id: &str,
+        command: C,
+    ) -> Result<Vec<EventContext<C, E>>, Error> {
+        self.execute_with_metadata(
+            aggregate_id,
+            command,
+            HashMap::new(),
+        )
+    }
+
+    /// Executes a command identified by `command_id` on the
+    /// aggregate instance identified by `aggregate_id`. A command id
+    /// that has already been processed for the aggregate instance
+    /// returns the originally committed events instead.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` when the command is rejected by the
+    /// aggregate or when a store fails.
+    pub fn execute_with_command_id(
+        &mut self,
+        aggregate_id: &str,
+        command_id: &str,
+        command: C,
+    ) -> Result<Vec<EventContext<C, E>>, Error> {
+        let mut metadata = HashMap::new();
+
+        metadata.insert(
+            COMMAND_ID_KEY.to_string(),
+            command_id.to_string(),
+        );
+
+        self.execute_with_metadata(aggregate_id, command, metadata)
+    }
+
+    /// Executes a command on the aggregate instance identified by
+    /// `aggregate_id` and attaches `metadata` to every resulting
+    /// event. The metadata enrichers run once beforehand and keep the
+    /// entries supplied by the caller. A command id supplied under
+    /// `COMMAND_ID_KEY` makes the execution idempotent, see
+    /// `execute_with_command_id`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` when the command is rejected by the
+    /// aggregate or when a store fails.
+    pub fn execute_with_metadata(
+        &mut self,
+        aggregate_id: &str,
+        command: C,
+        metadata: HashMap<String, String>,
+    ) -> Result<Vec<EventContext<C, E>>, Error> {
+        run_sync(self.pipeline.execute(
+            &mut self.backend,
+            aggregate_id,
+            command,
+            metadata,
+        ))
+    }
+
+    /// Loads an aggregate and saves a snapshot of its current state
+    /// regardless of the snapshot policy.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` when no snapshot store is configured or
+    /// when loading the aggregate or saving the snapshot fails.
+    pub fn take_snapshot(
+        &mut self,
+        aggregate_id: &str,
+    ) -> Result<AggregateContext<C, E, A>, Error> {
+        run_sync(
+            self.pipeline
+                .take_snapshot(&mut self.backend, aggregate_id),
+        )
+    }
+}
+
+/// The stores and consumers `CqrsFramework` runs its
+/// `DispatchPipeline` with
+struct SyncBackend<
+    C: ICommand,
+    E: IEvent,
+    A: IAggregate<C, E>,
+    ES: IEventStore<C, E, A>,
+> {
+    store: ES,
+    consumers: Vec<Box<dyn IEventConsumer<C, E>>>,
+    snapshot_store: Option<Box<dyn ISnapshotStore<C, E, A>>>,
+    _phantom: PhantomData<A>,
+}
+
+impl<
+        C: ICommand,
+        E: IEvent,
+        A: IAggregate<C, E>,
+        ES: IEventStore<C, E, A>,
+    > IDispatchBackend<C, E, A> for SyncBackend<C, E, A, ES>
+{
+    fn aggregate_type() -> &'static str {
+        A::aggregate_type()
+    }
+
+    async fn load_aggregate(
+        &mut self,
+        aggregate_id: &str,
+    ) -> Result<AggregateContext<C, E, A>, Error> {
+        let snapshot = match &mut self.snapshot_store {
+            None => None,
+            Some(x) => x.load_snapshot(aggregate_id)?,
+        };
+
+        match snapshot {
+            None => self.store.load_aggregate(aggregate_id),
+            Some(x) => self.store.load_aggregate_from(x),
+        }
+    }
+
+    async fn load_events_after(
+        &mut self,
+        aggregate_id: &str,
+        version: i64,
+    ) -> Result<Vec<EventContext<C, E>>, Error> {
+        self.store
+            .load_events_after(aggregate_id, version)
+    }
+
+    async fn handle(
+        aggregate: &A,
+        command: C,
+    ) -> Result<Vec<E>, Error> {
+        ICommandHandler::handle(aggregate, command)
+    }
+
+    async fn commit(
+        &mut self,
+        events: Vec<EventContext<C, E>>,
+        expected_version: i64,
+    ) -> Result<(), Error> {
+        self.store
+            .commit(events, expected_version)
+    }
+
+    async fn save_snapshot(
+        &mut self,
+        context: &AggregateContext<C, E, A>,
+    ) -> Result<(), Error> {
+        match &mut self.snapshot_store {
+            None => {
+                Err(Error::TechnicalError(
+                    "no snapshot store is configured".to_string(),
+                ))
+            },
+            Some(x) => x.save_snapshot(context),
+        }
+    }
+
+    async fn notify(
+        &mut self,
+        events: &[EventContext<C, E>],
+    ) {
+        for consumer in &mut self.consumers {
+            for event in events {
+                consumer.update(event);
+            }
+        }
+    }
+}
+
+impl<
+        C: ICommand,
+        E: IEvent,
+        A: IAggregate<C, E>,
+        ES: IEventStore<C, E, A>,
+    > ICommandDispatcher<C> for CqrsFramework<C, E, A, ES>
+{
+    fn dispatch(
+        &mut self,
+        aggregate_id: &str,
+        command: C,
+        metadata: HashMap<String, String>,
+    ) -> Result<(), Error> {
+        self.execute_with_metadata(aggregate_id, command, metadata)?;
+
+        Ok(())
+    }
+}
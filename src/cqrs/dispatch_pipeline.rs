@@ -0,0 +1,496 @@
+use chrono::{
+    DateTime,
+    Duration,
+    Utc,
+};
+use log::{
+    debug,
+    warn,
+};
+use std::{
+    collections::HashMap,
+    future::Future,
+    pin::pin,
+    task::{
+        Context,
+        Poll,
+        Waker,
+    },
+    time,
+};
+
+use crate::{
+    aggregates::AggregateContext,
+    commands::{
+        CommandValidator,
+        ICommand,
+        ProcessedCommand,
+    },
+    errors::Error,
+    events::{
+        EventContext,
+        IEvent,
+        IEventHandler,
+    },
+    metadata::{
+        MetadataEnrichers,
+        COMMAND_ID_KEY,
+    },
+    middleware::{
+        CommandMiddlewares,
+        EventMiddlewares,
+    },
+    stores::ICommandIdStore,
+};
+
+use super::{
+    retry_policy::RetryPolicy,
+    snapshot_policy::SnapshotPolicy,
+};
+
+/// The calls of a `DispatchPipeline` to the event and snapshot
+/// stores, the aggregate and the consumers, which are the only steps
+/// of executing a command that differ between `CqrsFramework` and
+/// `AsyncCqrsFramework`
+pub(crate) trait IDispatchBackend<C: ICommand, E: IEvent, A> {
+    /// The type of the aggregates, see `IAggregate::aggregate_type`
+    fn aggregate_type() -> &'static str;
+
+    /// Loads an aggregate, starting from its latest snapshot when a
+    /// snapshot store is configured
+    async fn load_aggregate(
+        &mut self,
+        aggregate_id: &str,
+    ) -> Result<AggregateContext<C, E, A>, Error>;
+
+    /// Loads the events of an aggregate committed after `version`
+    async fn load_events_after(
+        &mut self,
+        aggregate_id: &str,
+        version: i64,
+    ) -> Result<Vec<EventContext<C, E>>, Error>;
+
+    /// Handles `command` with the current state of the aggregate
+    async fn handle(
+        aggregate: &A,
+        command: C,
+    ) -> Result<Vec<E>, Error>;
+
+    /// Commits the events produced from `expected_version`
+    async fn commit(
+        &mut self,
+        events: Vec<EventContext<C, E>>,
+        expected_version: i64,
+    ) -> Result<(), Error>;
+
+    /// Saves a snapshot of the aggregate, an `Error` when no
+    /// snapshot store is configured
+    async fn save_snapshot(
+        &mut self,
+        context: &AggregateContext<C, E, A>,
+    ) -> Result<(), Error>;
+
+    /// Passes the committed events to the consumers
+    async fn notify(
+        &mut self,
+        events: &[EventContext<C, E>],
+    );
+}
+
+/// The steps of executing a command shared by `CqrsFramework` and
+/// `AsyncCqrsFramework`, which only differ in their
+/// `IDispatchBackend`. The command id store is either `Send` or not,
+/// depending on the framework.
+pub(crate) struct DispatchPipeline<
+    C: ICommand,
+    E: IEvent,
+    S: ICommandIdStore + ?Sized,
+> {
+    pub(crate) retry_policy: RetryPolicy,
+    pub(crate) metadata_enrichers: MetadataEnrichers<C>,
+    pub(crate) command_validator: Option<CommandValidator<C>>,
+    pub(crate) command_middlewares: CommandMiddlewares<C, E>,
+    pub(crate) event_middlewares: EventMiddlewares<C, E>,
+    pub(crate) snapshot_policy: SnapshotPolicy,
+    pub(crate) command_id_store: Option<Box<S>>,
+    pub(crate) command_id_retention: time::Duration,
+}
+
+impl<C: ICommand, E: IEvent, S: ICommandIdStore + ?Sized>
+    DispatchPipeline<C, E, S>
+{
+    /// Constructor
+    pub(crate) fn new() -> Self {
+        Self {
+            retry_policy: RetryPolicy::default(),
+            metadata_enrichers: MetadataEnrichers::default(),
+            command_validator: None,
+            command_middlewares: CommandMiddlewares::default(),
+            event_middlewares: EventMiddlewares::default(),
+            snapshot_policy: SnapshotPolicy::default(),
+            command_id_store: None,
+            command_id_retention: time::Duration::ZERO,
+        }
+    }
+
+    /// Executes a command on the aggregate instance identified by
+    /// `aggregate_id`, wrapped by the hooks of the command
+    /// middlewares, see `CqrsFramework::execute_with_metadata`
+    pub(crate) async fn execute<A, B>(
+        &mut self,
+        backend: &mut B,
+        aggregate_id: &str,
+        command: C,
+        metadata: HashMap<String, String>,
+    ) -> Result<Vec<EventContext<C, E>>, Error>
+    where
+        A: IEventHandler<E>,
+        B: IDispatchBackend<C, E, A>, {
+        self.command_middlewares.before(
+            aggregate_id,
+            &command,
+            &metadata,
+        )?;
+
+        let for_hooks = command.clone();
+
+        let result = self
+            .execute_validated(
+                backend,
+                aggregate_id,
+                command,
+                metadata,
+            )
+            .await;
+
+        match &result {
+            Ok(events) => {
+                self.command_middlewares.after(
+                    aggregate_id,
+                    &for_hooks,
+                    events,
+                );
+            },
+            Err(e) => {
+                self.command_middlewares.on_error(
+                    aggregate_id,
+                    &for_hooks,
+                    e,
+                );
+            },
+        }
+
+        result
+    }
+
+    /// Loads an aggregate and saves a snapshot of its current state
+    /// regardless of the snapshot policy
+    pub(crate) async fn take_snapshot<A, B>(
+        &mut self,
+        backend: &mut B,
+        aggregate_id: &str,
+    ) -> Result<AggregateContext<C, E, A>, Error>
+    where
+        B: IDispatchBackend<C, E, A>, {
+        let context = backend
+            .load_aggregate(aggregate_id)
+            .await?;
+
+        backend.save_snapshot(&context).await?;
+
+        Ok(context)
+    }
+
+    async fn execute_validated<A, B>(
+        &mut self,
+        backend: &mut B,
+        aggregate_id: &str,
+        command: C,
+        metadata: HashMap<String, String>,
+    ) -> Result<Vec<EventContext<C, E>>, Error>
+    where
+        A: IEventHandler<E>,
+        B: IDispatchBackend<C, E, A>, {
+        if let Some(validate) = self.command_validator {
+            validate(&command)?;
+        }
+
+        let command_id = match metadata.get(COMMAND_ID_KEY) {
+            Some(x) if self.command_id_store.is_some() => x.clone(),
+            _ => {
+                return self
+                    .execute_with_retries(
+                        backend,
+                        aggregate_id,
+                        command,
+                        metadata,
+                    )
+                    .await;
+            },
+        };
+
+        if let Some(events) = self
+            .load_processed(backend, aggregate_id, &command_id)
+            .await?
+        {
+            return Ok(events);
+        }
+
+        let events = self
+            .execute_with_retries(
+                backend,
+                aggregate_id,
+                command,
+                metadata,
+            )
+            .await?;
+
+        self.save_processed::<A, B>(
+            aggregate_id,
+            &command_id,
+            &events,
+        );
+
+        Ok(events)
+    }
+
+    async fn execute_with_retries<A, B>(
+        &mut self,
+        backend: &mut B,
+        aggregate_id: &str,
+        command: C,
+        metadata: HashMap<String, String>,
+    ) -> Result<Vec<EventContext<C, E>>, Error>
+    where
+        A: IEventHandler<E>,
+        B: IDispatchBackend<C, E, A>, {
+        let mut metadata = metadata;
+
+        self.metadata_enrichers
+            .enrich(&command, &mut metadata);
+
+        let mut retries = 0;
+
+        loop {
+            match self
+                .try_execute(
+                    backend,
+                    aggregate_id,
+                    command.clone(),
+                    &metadata,
+                )
+                .await
+            {
+                Err(Error::ConcurrencyConflict {
+                    expected,
+                    actual,
+                    ..
+                }) if retries < self.retry_policy.max_retries() => {
+                    retries += 1;
+
+                    warn!(
+                        "retrying command on aggregate '{}' of type \
+                         '{}' after a concurrency conflict \
+                         (expected version {}, found {}), retry {} \
+                         of {}",
+                        aggregate_id,
+                        B::aggregate_type(),
+                        expected,
+                        actual,
+                        retries,
+                        self.retry_policy.max_retries()
+                    );
+                },
+                result => return result,
+            }
+        }
+    }
+
+    async fn load_processed<A, B>(
+        &mut self,
+        backend: &mut B,
+        aggregate_id: &str,
+        command_id: &str,
+    ) -> Result<Option<Vec<EventContext<C, E>>>, Error>
+    where
+        B: IDispatchBackend<C, E, A>, {
+        let processed = match &mut self.command_id_store {
+            None => None,
+            Some(x) => x.load(aggregate_id, command_id)?,
+        };
+
+        let processed = match processed {
+            Some(x) if !self.is_expired(&x) => x,
+            _ => return Ok(None),
+        };
+
+        debug!(
+            "command '{}' has already been processed on aggregate \
+             '{}' of type '{}', returning its events",
+            command_id,
+            aggregate_id,
+            B::aggregate_type()
+        );
+
+        let events = backend
+            .load_events_after(aggregate_id, processed.from_version)
+            .await?
+            .into_iter()
+            .take_while(|x| x.sequence <= processed.to_version)
+            .collect();
+
+        Ok(Some(events))
+    }
+
+    fn save_processed<A, B>(
+        &mut self,
+        aggregate_id: &str,
+        command_id: &str,
+        events: &[EventContext<C, E>],
+    ) where
+        B: IDispatchBackend<C, E, A>, {
+        let (from_version, to_version) =
+            match (events.first(), events.last()) {
+                (Some(first), Some(last)) => {
+                    (first.sequence - 1, last.sequence)
+                },
+                _ => (0, 0),
+            };
+
+        let expired_before = self.expired_before();
+
+        if let Some(x) = &mut self.command_id_store {
+            // the events are already committed, a missing record
+            // only lets a repeated command through
+            let result = x
+                .save(ProcessedCommand::new(
+                    aggregate_id,
+                    command_id,
+                    from_version,
+                    to_version,
+                ))
+                .and_then(|()| {
+                    match expired_before {
+                        None => Ok(0),
+                        Some(before) => x.purge(before),
+                    }
+                });
+
+            if let Err(e) = result {
+                warn!(
+                    "unable to record command '{}' of aggregate \
+                     '{}' of type '{}': {}",
+                    command_id,
+                    aggregate_id,
+                    B::aggregate_type(),
+                    e
+                );
+            }
+        }
+    }
+
+    fn is_expired(
+        &self,
+        command: &ProcessedCommand,
+    ) -> bool {
+        self.expired_before()
+            .is_some_and(|x| command.processed_at < x)
+    }
+
+    /// The time before which processed commands are expired, `None`
+    /// when the retention window reaches back further than chrono
+    /// can represent
+    fn expired_before(&self) -> Option<DateTime<Utc>> {
+        Duration::from_std(self.command_id_retention)
+            .ok()
+            .and_then(|x| Utc::now().checked_sub_signed(x))
+    }
+
+    async fn try_execute<A, B>(
+        &mut self,
+        backend: &mut B,
+        aggregate_id: &str,
+        command: C,
+        metadata: &HashMap<String, String>,
+    ) -> Result<Vec<EventContext<C, E>>, Error>
+    where
+        A: IEventHandler<E>,
+        B: IDispatchBackend<C, E, A>, {
+        let mut context = backend
+            .load_aggregate(aggregate_id)
+            .await?;
+
+        let events = B::handle(&context.payload, command).await?;
+
+        let expected_version = context.version;
+
+        let mut event_contexts = Vec::with_capacity(events.len());
+
+        for event in events {
+            context.version += 1;
+
+            let mut event_context = EventContext::new(
+                aggregate_id.to_string(),
+                context.version,
+                event,
+                metadata.clone(),
+            );
+
+            self.event_middlewares
+                .process(&mut event_context)?;
+
+            context
+                .payload
+                .apply(&event_context.payload);
+
+            event_contexts.push(event_context);
+        }
+
+        debug!(
+            "committing {} events for aggregate '{}' of type '{}'",
+            event_contexts.len(),
+            aggregate_id,
+            B::aggregate_type()
+        );
+
+        backend
+            .commit(event_contexts.clone(), expected_version)
+            .await?;
+
+        if self
+            .snapshot_policy
+            .is_due(expected_version, context.version)
+        {
+            // the events are already committed, a missing snapshot
+            // only costs a longer replay on the next load
+            if let Err(e) = backend.save_snapshot(&context).await {
+                warn!(
+                    "unable to save snapshot at version {} for \
+                     aggregate '{}' of type '{}': {}",
+                    context.version,
+                    context.aggregate_id,
+                    B::aggregate_type(),
+                    e
+                );
+            }
+        }
+
+        backend.notify(&event_contexts).await;
+
+        Ok(event_contexts)
+    }
+}
+
+/// Runs a future of the pipeline driven by the sync backend of
+/// `CqrsFramework` to completion. None of its calls ever waits, so
+/// the future completes the first time it is polled.
+pub(crate) fn run_sync<F: Future>(future: F) -> F::Output {
+    let future = pin!(future);
+
+    match future.poll(&mut Context::from_waker(Waker::noop())) {
+        Poll::Ready(x) => x,
+        Poll::Pending => {
+            unreachable!("the sync dispatch pipeline never waits")
+        },
+    }
+}
@@ -0,0 +1,25 @@
+use std::collections::HashMap;
+
+use crate::{
+    commands::ICommand,
+    errors::Error,
+};
+
+/// An `ICommandDispatcher` executes commands on behalf of another
+/// component, e.g., a `SagaManager` coordinating several aggregates.
+/// It is implemented by `CqrsFramework`.
+pub trait ICommandDispatcher<C: ICommand> {
+    /// Executes `command` on the aggregate instance identified by
+    /// `aggregate_id`, attaching `metadata` to the resulting events.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` when the command is rejected or can not be
+    /// executed.
+    fn dispatch(
+        &mut self,
+        aggregate_id: &str,
+        command: C,
+        metadata: HashMap<String, String>,
+    ) -> Result<(), Error>;
+}
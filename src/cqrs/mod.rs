@@ -0,0 +1,23 @@
+//! # cqrs
+//!
+//! A central location for the command dispatch framework
+
+pub use cqrs_framework::CqrsFramework;
+pub use i_command_dispatcher::ICommandDispatcher;
+pub use retry_policy::RetryPolicy;
+pub use snapshot_policy::SnapshotPolicy;
+
+#[cfg(feature = "async")]
+pub(crate) use dispatch_pipeline::{
+    DispatchPipeline,
+    IDispatchBackend,
+};
+
+mod cqrs_framework;
+mod dispatch_pipeline;
+mod i_command_dispatcher;
+mod retry_policy;
+mod snapshot_policy;
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,29 @@
+/// Decides how `CqrsFramework` reacts to an
+/// `Error::ConcurrencyConflict` raised while committing events.
+///
+/// Retrying reloads the aggregate and handles the command again on
+/// top of the newer state, so the business rules are always checked
+/// against the latest committed events.
+#[derive(
+    Debug, PartialEq, Eq, Clone, Copy, Default
+)]
+pub enum RetryPolicy {
+    /// Return the conflict to the caller right away.
+    #[default]
+    Never,
+
+    /// Retry up to the given number of times before returning the
+    /// conflict to the caller.
+    Bounded(u32),
+}
+
+impl RetryPolicy {
+    /// The maximum number of retries allowed by this policy
+    #[must_use]
+    pub fn max_retries(&self) -> u32 {
+        match self {
+            RetryPolicy::Never => 0,
+            RetryPolicy::Bounded(x) => *x,
+        }
+    }
+}
@@ -0,0 +1,36 @@
+/// Decides when `CqrsFramework` saves a snapshot of an aggregate
+/// instance to its snapshot store.
+#[derive(
+    Debug, PartialEq, Eq, Clone, Copy, Default
+)]
+pub enum SnapshotPolicy {
+    /// Never save snapshots.
+    #[default]
+    Never,
+
+    /// Save a snapshot every time the version of the aggregate
+    /// crosses a multiple of the given number of events.
+    EveryNEvents(i64),
+
+    /// Only save snapshots when explicitly requested through
+    /// `CqrsFramework::take_snapshot`.
+    OnDemand,
+}
+
+impl SnapshotPolicy {
+    /// Whether a snapshot is due after committing the events that
+    /// moved an aggregate from `previous_version` to `version`
+    #[must_use]
+    pub fn is_due(
+        &self,
+        previous_version: i64,
+        version: i64,
+    ) -> bool {
+        match self {
+            SnapshotPolicy::EveryNEvents(n) if *n > 0 => {
+                previous_version / n != version / n
+            },
+            _ => false,
+        }
+    }
+}
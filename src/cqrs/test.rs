@@ -0,0 +1,680 @@
+use chrono::{
+    Duration,
+    Utc,
+};
+use std::{
+    collections::HashMap,
+    sync::{
+        Arc,
+        Mutex,
+    },
+    time,
+};
+
+use crate::{
+    example_impl::*,
+    AggregateContext,
+    Error,
+    EventContext,
+    ICommandIdStore,
+    IEventConsumer,
+    IEventStore,
+    ISnapshotStore,
+    InMemoryCommandIdStore,
+    InMemoryEventStore,
+    InMemorySnapshotStore,
+    MetadataEnrichers,
+    PrincipalEnricher,
+    ProcessedCommand,
+    UserError,
+    COMMAND_ID_KEY,
+    CORRELATION_ID_KEY,
+    VALIDATION_MESSAGE,
+};
+
+use super::{
+    cqrs_framework::CqrsFramework,
+    retry_policy::RetryPolicy,
+    snapshot_policy::SnapshotPolicy,
+};
+
+type ThisEventStore =
+    InMemoryEventStore<CustomerCommand, CustomerEvent, Customer>;
+
+type ThisSnapshotStore =
+    InMemorySnapshotStore<CustomerCommand, CustomerEvent, Customer>;
+
+type ThisCqrsFramework = CqrsFramework<
+    CustomerCommand,
+    CustomerEvent,
+    Customer,
+    ThisEventStore,
+>;
+
+#[derive(Default, Clone)]
+struct TestConsumer {
+    events:
+        Arc<Mutex<Vec<EventContext<CustomerCommand, CustomerEvent>>>>,
+}
+
+impl IEventConsumer<CustomerCommand, CustomerEvent> for TestConsumer {
+    fn update(
+        &mut self,
+        event: &EventContext<CustomerCommand, CustomerEvent>,
+    ) {
+        self.events
+            .lock()
+            .unwrap()
+            .push(event.clone());
+    }
+}
+
+fn add_address(address: &str) -> CustomerCommand {
+    CustomerCommand::AddAddress(AddAddress {
+        new_address: address.to_string(),
+    })
+}
+
+#[test]
+fn test_execute() {
+    let store = ThisEventStore::default();
+    let consumer = TestConsumer::default();
+
+    let mut cqrs = ThisCqrsFramework::new(
+        store.clone(),
+        vec![Box::new(consumer.clone())],
+    );
+
+    let mut metadata = HashMap::new();
+    metadata.insert("user".to_string(), "tester".to_string());
+
+    let events = cqrs
+        .execute_with_metadata(
+            "test_id_A",
+            CustomerCommand::AddCustomerName(AddCustomerName {
+                changed_name: "John Doe".to_string(),
+            }),
+            metadata.clone(),
+        )
+        .unwrap();
+
+    assert_eq!(
+        events,
+        vec![EventContext::new(
+            "test_id_A".to_string(),
+            1,
+            CustomerEvent::NameAdded(NameAdded {
+                changed_name: "John Doe".to_string(),
+            }),
+            metadata,
+        )]
+    );
+
+    cqrs.execute("test_id_A", add_address("home"))
+        .unwrap();
+    cqrs.execute("test_id_A", add_address("work"))
+        .unwrap();
+
+    let context = store
+        .clone()
+        .load_aggregate("test_id_A")
+        .unwrap();
+
+    assert_eq!(context.version, 3);
+    assert_eq!(
+        context.payload.addresses,
+        vec!["home".to_string(), "work".to_string()]
+    );
+
+    let received = consumer.events.lock().unwrap();
+
+    assert_eq!(received.len(), 3);
+    assert_eq!(
+        received
+            .iter()
+            .map(|x| x.sequence)
+            .collect::<Vec<_>>(),
+        vec![1, 2, 3]
+    );
+}
+
+#[test]
+fn test_execute_rejected_command() {
+    let store = ThisEventStore::default();
+    let consumer = TestConsumer::default();
+
+    let mut cqrs = ThisCqrsFramework::new(store.clone(), Vec::new())
+        .with_consumer(Box::new(consumer.clone()));
+
+    cqrs.execute("test_id_A", add_address("home"))
+        .unwrap();
+
+    assert!(cqrs
+        .execute("test_id_A", add_address("home"))
+        .is_err());
+
+    assert_eq!(
+        store
+            .clone()
+            .load_events("test_id_A")
+            .unwrap()
+            .len(),
+        1
+    );
+    assert_eq!(consumer.events.lock().unwrap().len(), 1);
+}
+
+/// Simulates a concurrent writer by committing a competing event
+/// right before each of the first `conflicts` commits.
+struct ConflictingEventStore {
+    inner: ThisEventStore,
+    conflicts: usize,
+}
+
+impl IEventStore<CustomerCommand, CustomerEvent, Customer>
+    for ConflictingEventStore
+{
+    fn load_events(
+        &mut self,
+        aggregate_id: &str,
+    ) -> Result<
+        Vec<EventContext<CustomerCommand, CustomerEvent>>,
+        Error,
+    > {
+        self.inner.load_events(aggregate_id)
+    }
+
+    fn commit(
+        &mut self,
+        events: Vec<EventContext<CustomerCommand, CustomerEvent>>,
+        expected_version: i64,
+    ) -> Result<(), Error> {
+        if self.conflicts > 0 {
+            self.conflicts -= 1;
+
+            let aggregate_id = events[0].aggregate_id.clone();
+            let version = self
+                .inner
+                .load_aggregate(&aggregate_id)?
+                .version;
+
+            self.inner.commit(
+                vec![EventContext::new(
+                    aggregate_id,
+                    version + 1,
+                    CustomerEvent::EmailUpdated(EmailUpdated {
+                        new_email: "concurrent@d.com".to_string(),
+                    }),
+                    HashMap::default(),
+                )],
+                version,
+            )?;
+        }
+
+        self.inner
+            .commit(events, expected_version)
+    }
+}
+
+type ConflictingCqrsFramework = CqrsFramework<
+    CustomerCommand,
+    CustomerEvent,
+    Customer,
+    ConflictingEventStore,
+>;
+
+#[test]
+fn test_concurrency_conflict_without_retry() {
+    let store = ThisEventStore::default();
+
+    let mut cqrs = ConflictingCqrsFramework::new(
+        ConflictingEventStore {
+            inner: store.clone(),
+            conflicts: 1,
+        },
+        Vec::new(),
+    );
+
+    assert_eq!(
+        cqrs.execute("test_id_A", add_address("home")),
+        Err(Error::ConcurrencyConflict {
+            aggregate_id: "test_id_A".to_string(),
+            expected: 0,
+            actual: 1,
+        })
+    );
+}
+
+#[test]
+fn test_concurrency_conflict_with_retry() {
+    let store = ThisEventStore::default();
+
+    let mut cqrs = ConflictingCqrsFramework::new(
+        ConflictingEventStore {
+            inner: store.clone(),
+            conflicts: 2,
+        },
+        Vec::new(),
+    )
+    .with_retry_policy(RetryPolicy::Bounded(2));
+
+    let events = cqrs
+        .execute("test_id_A", add_address("home"))
+        .unwrap();
+
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].sequence, 3);
+
+    let context = store
+        .clone()
+        .load_aggregate("test_id_A")
+        .unwrap();
+
+    assert_eq!(context.version, 3);
+    assert_eq!(
+        context.payload.addresses,
+        vec!["home".to_string()]
+    );
+}
+
+#[test]
+fn test_concurrency_conflict_retries_exhausted() {
+    let mut cqrs = ConflictingCqrsFramework::new(
+        ConflictingEventStore {
+            inner: ThisEventStore::default(),
+            conflicts: 3,
+        },
+        Vec::new(),
+    )
+    .with_retry_policy(RetryPolicy::Bounded(2));
+
+    assert_eq!(
+        cqrs.execute("test_id_A", add_address("home")),
+        Err(Error::ConcurrencyConflict {
+            aggregate_id: "test_id_A".to_string(),
+            expected: 2,
+            actual: 3,
+        })
+    );
+}
+
+#[test]
+fn test_snapshot_policy() {
+    assert!(!SnapshotPolicy::Never.is_due(0, 100));
+    assert!(!SnapshotPolicy::OnDemand.is_due(0, 100));
+    assert!(!SnapshotPolicy::EveryNEvents(0).is_due(0, 100));
+    assert!(!SnapshotPolicy::EveryNEvents(3).is_due(0, 2));
+    assert!(SnapshotPolicy::EveryNEvents(3).is_due(2, 3));
+    assert!(SnapshotPolicy::EveryNEvents(3).is_due(2, 7));
+    assert!(!SnapshotPolicy::EveryNEvents(3).is_due(3, 5));
+}
+
+#[test]
+fn test_snapshot_every_n_events() {
+    let snapshots = ThisSnapshotStore::default();
+
+    let mut cqrs =
+        ThisCqrsFramework::new(ThisEventStore::default(), Vec::new())
+            .with_snapshot_store(
+                Box::new(snapshots.clone()),
+                SnapshotPolicy::EveryNEvents(2),
+            );
+
+    cqrs.execute("test_id_A", add_address("home"))
+        .unwrap();
+
+    assert_eq!(
+        snapshots
+            .clone()
+            .load_snapshot("test_id_A")
+            .unwrap(),
+        None
+    );
+
+    cqrs.execute("test_id_A", add_address("work"))
+        .unwrap();
+    cqrs.execute("test_id_A", add_address("gym"))
+        .unwrap();
+
+    let snapshot = snapshots
+        .clone()
+        .load_snapshot("test_id_A")
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(snapshot.version, 2);
+    assert_eq!(
+        snapshot.payload.addresses,
+        vec!["home".to_string(), "work".to_string()]
+    );
+}
+
+#[test]
+fn test_snapshot_on_demand() {
+    let store = ThisEventStore::default();
+    let snapshots = ThisSnapshotStore::default();
+
+    let mut cqrs = ThisCqrsFramework::new(store.clone(), Vec::new());
+
+    cqrs.execute("test_id_A", add_address("home"))
+        .unwrap();
+
+    assert!(cqrs.take_snapshot("test_id_A").is_err());
+
+    let mut cqrs = ThisCqrsFramework::new(store, Vec::new())
+        .with_snapshot_store(
+            Box::new(snapshots.clone()),
+            SnapshotPolicy::OnDemand,
+        );
+
+    cqrs.execute("test_id_A", add_address("work"))
+        .unwrap();
+
+    assert_eq!(
+        snapshots
+            .clone()
+            .load_snapshot("test_id_A")
+            .unwrap(),
+        None
+    );
+
+    let context = cqrs.take_snapshot("test_id_A").unwrap();
+
+    assert_eq!(context.version, 2);
+    assert_eq!(
+        snapshots
+            .clone()
+            .load_snapshot("test_id_A")
+            .unwrap(),
+        Some(context)
+    );
+}
+
+#[test]
+fn test_execute_from_snapshot() {
+    let store = ThisEventStore::default();
+    let mut snapshots = ThisSnapshotStore::default();
+
+    let mut cqrs = ThisCqrsFramework::new(store.clone(), Vec::new());
+
+    cqrs.execute("test_id_A", add_address("home"))
+        .unwrap();
+    cqrs.execute("test_id_A", add_address("work"))
+        .unwrap();
+
+    // a snapshot that disagrees with the event history proves that
+    // the aggregate is rehydrated from it
+    snapshots
+        .save_snapshot(&AggregateContext::new(
+            "test_id_A".to_string(),
+            1,
+            Customer {
+                customer_id: String::new(),
+                name: "Snapshot Doe".to_string(),
+                email: String::new(),
+                addresses: vec!["home".to_string()],
+            },
+        ))
+        .unwrap();
+
+    let mut cqrs = ThisCqrsFramework::new(store, Vec::new())
+        .with_snapshot_store(
+            Box::new(snapshots),
+            SnapshotPolicy::Never,
+        );
+
+    assert_eq!(
+        cqrs.execute(
+            "test_id_A",
+            CustomerCommand::AddCustomerName(AddCustomerName {
+                changed_name: "John Doe".to_string(),
+            }),
+        ),
+        Err(Error::new(
+            "a name has already been added for this customer"
+        ))
+    );
+
+    let events = cqrs
+        .execute("test_id_A", add_address("gym"))
+        .unwrap();
+
+    assert_eq!(events[0].sequence, 3);
+}
+
+#[test]
+fn test_metadata_enrichers() {
+    let mut cqrs =
+        ThisCqrsFramework::new(ThisEventStore::default(), Vec::new())
+            .with_metadata_enrichers(MetadataEnrichers::standard())
+            .with_metadata_enricher(PrincipalEnricher::new("admin"));
+
+    let mut metadata = HashMap::new();
+    metadata.insert(
+        CORRELATION_ID_KEY.to_string(),
+        "flow-1".to_string(),
+    );
+
+    let events = cqrs
+        .execute_with_metadata(
+            "test_id_A",
+            CustomerCommand::AddCustomerName(AddCustomerName {
+                changed_name: "John Doe".to_string(),
+            }),
+            metadata,
+        )
+        .unwrap();
+
+    let event = &events[0];
+
+    assert_eq!(event.correlation_id(), Some("flow-1"));
+    assert!(event.causation_id().is_some());
+    assert!(event.timestamp().is_some());
+    assert_eq!(
+        event.command_name(),
+        Some("AddCustomerName")
+    );
+    assert_eq!(event.principal(), Some("admin"));
+}
+
+#[test]
+fn test_repeated_command_id() {
+    let mut store = ThisEventStore::default();
+    let consumer = TestConsumer::default();
+    let mut command_ids = InMemoryCommandIdStore::default();
+
+    let mut cqrs = ThisCqrsFramework::new(
+        store.clone(),
+        vec![Box::new(consumer.clone())],
+    )
+    .with_command_id_store(
+        Box::new(command_ids.clone()),
+        time::Duration::from_secs(60 * 60),
+    );
+
+    cqrs.execute("test_id_A", add_address("Street 1"))
+        .unwrap();
+
+    let events = cqrs
+        .execute_with_command_id(
+            "test_id_A",
+            "command-1",
+            add_address("Street 2"),
+        )
+        .unwrap();
+
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].sequence, 2);
+    assert_eq!(
+        events[0]
+            .metadata
+            .get(COMMAND_ID_KEY)
+            .map(String::as_str),
+        Some("command-1")
+    );
+
+    let processed = command_ids
+        .load("test_id_A", "command-1")
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(processed.from_version, 1);
+    assert_eq!(processed.to_version, 2);
+
+    // handling the command again would reject the known address
+    let repeated = cqrs
+        .execute_with_command_id(
+            "test_id_A",
+            "command-1",
+            add_address("Street 2"),
+        )
+        .unwrap();
+
+    assert_eq!(repeated, events);
+    assert_eq!(
+        store
+            .load_events("test_id_A")
+            .unwrap()
+            .len(),
+        2
+    );
+    assert_eq!(consumer.events.lock().unwrap().len(), 2);
+
+    // command ids are recorded per aggregate instance
+    assert_eq!(
+        cqrs.execute_with_command_id(
+            "test_id_B",
+            "command-1",
+            add_address("Street 2"),
+        )
+        .unwrap()[0]
+            .aggregate_id,
+        "test_id_B"
+    );
+}
+
+#[test]
+fn test_command_id_retention() {
+    let mut command_ids = InMemoryCommandIdStore::default();
+
+    let mut expired =
+        ProcessedCommand::new("test_id_A", "command-1", 0, 1);
+    expired.processed_at = Utc::now() - Duration::hours(2);
+
+    command_ids.save(expired).unwrap();
+
+    let mut cqrs =
+        ThisCqrsFramework::new(ThisEventStore::default(), Vec::new())
+            .with_command_id_store(
+                Box::new(command_ids.clone()),
+                time::Duration::from_secs(60 * 60),
+            );
+
+    // the expired record is ignored and purged
+    let events = cqrs
+        .execute_with_command_id(
+            "test_id_A",
+            "command-1",
+            add_address("Street 1"),
+        )
+        .unwrap();
+
+    assert_eq!(events[0].sequence, 1);
+
+    let processed = command_ids
+        .load("test_id_A", "command-1")
+        .unwrap()
+        .unwrap();
+
+    assert!(processed.processed_at > Utc::now() - Duration::hours(1));
+
+    assert_eq!(
+        command_ids
+            .purge(Utc::now() + Duration::seconds(1))
+            .unwrap(),
+        1
+    );
+    assert_eq!(
+        command_ids
+            .load("test_id_A", "command-1")
+            .unwrap(),
+        None
+    );
+}
+
+#[test]
+fn test_command_id_without_store() {
+    let mut cqrs =
+        ThisCqrsFramework::new(ThisEventStore::default(), Vec::new());
+
+    cqrs.execute_with_command_id(
+        "test_id_A",
+        "command-1",
+        add_address("Street 1"),
+    )
+    .unwrap();
+
+    assert_eq!(
+        cqrs.execute_with_command_id(
+            "test_id_A",
+            "command-1",
+            add_address("Street 1"),
+        ),
+        Err(Error::new(
+            "this address has already been added for this customer"
+        ))
+    );
+}
+
+#[test]
+fn test_command_validation() {
+    let mut store = ThisEventStore::default();
+
+    let mut cqrs = ThisCqrsFramework::new(store.clone(), Vec::new())
+        .with_command_validation();
+
+    let mut params = HashMap::new();
+    params.insert(
+        "new_email".to_string(),
+        "is not a valid email address".to_string(),
+    );
+
+    assert_eq!(
+        cqrs.execute(
+            "test_id_A",
+            CustomerCommand::UpdateEmail(UpdateEmail {
+                new_email: "john.doe".to_string(),
+            }),
+        ),
+        Err(Error::Validation(UserError {
+            code: None,
+            message: Some(VALIDATION_MESSAGE.to_string()),
+            params: Some(params),
+        }))
+    );
+    assert!(store
+        .load_events("test_id_A")
+        .unwrap()
+        .is_empty());
+
+    cqrs.execute(
+        "test_id_A",
+        CustomerCommand::UpdateEmail(UpdateEmail {
+            new_email: "john@doe.com".to_string(),
+        }),
+    )
+    .unwrap();
+
+    // validation is opt-in
+    ThisCqrsFramework::new(store.clone(), Vec::new())
+        .execute("test_id_A", add_address(""))
+        .unwrap();
+
+    assert_eq!(
+        store
+            .load_events("test_id_A")
+            .unwrap()
+            .len(),
+        2
+    );
+}
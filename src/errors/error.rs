@@ -13,23 +13,125 @@ use serde::{
     Serialize,
 };
 
-use super::user_error::UserError;
+use super::{
+    error_category::ErrorCategory,
+    user_error::UserError,
+};
+
+/// The underlying cause of an `Error`. It is not serialized along
+/// with the error, and any two sources are equal, so errors only
+/// differ in whether they have a source.
+#[derive(Debug)]
+pub struct ErrorSource(Box<dyn error::Error + Send + Sync + 'static>);
+
+impl ErrorSource {
+    /// The underlying cause
+    #[must_use]
+    pub fn get(&self) -> &(dyn error::Error + Send + Sync + 'static) {
+        self.0.as_ref()
+    }
+}
+
+impl<E: error::Error + Send + Sync + 'static> From<E>
+    for ErrorSource
+{
+    fn from(e: E) -> Self {
+        Self(Box::new(e))
+    }
+}
+
+impl PartialEq for ErrorSource {
+    fn eq(
+        &self,
+        _other: &Self,
+    ) -> bool {
+        true
+    }
+}
 
 /// The base error for the framework.
+///
+/// Every variant belongs to an `ErrorCategory` and has a stable
+/// machine-readable `code`. Technical variants keep their underlying
+/// cause, which is available through `std::error::Error::source`,
+/// but is not serialized.
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
 pub enum Error {
     /// The user has made an error, a String value contains a message
     /// to be delivered to the user.
     UserError(UserError),
 
+    /// The request failed validation before reaching the business
+    /// rules. The `params` of the payload map each invalid field to
+    /// a message.
+    Validation(UserError),
+
+    /// The caller is not allowed to perform the request.
+    Unauthorized(String),
+
+    /// The requested entity does not exist.
+    NotFound {
+        /// The kind of entity, e.g., the aggregate or query type
+        entity: String,
+
+        /// The id of the entity
+        id: String,
+    },
+
     /// A technical error was encountered that prevented the command
     /// from being applied to the aggregate. In general the
     /// accompanying message should be logged for investigation
     /// rather than returned to the user.
     TechnicalError(String),
+
+    /// Another writer committed events to the same aggregate
+    /// instance after it was loaded, so the new events were
+    /// rejected to preserve the consistency of the aggregate.
+    ConcurrencyConflict {
+        /// The id of the aggregate instance
+        aggregate_id: String,
+
+        /// The version the events were produced from
+        expected: i64,
+
+        /// The version that was found in the store
+        actual: i64,
+    },
+
+    /// Data could not be serialized or deserialized.
+    Serialization {
+        /// A description of the failure
+        message: String,
+
+        /// The underlying cause
+        #[serde(skip)]
+        source: Option<ErrorSource>,
+    },
+
+    /// A storage backend failed.
+    Store {
+        /// A description of the failure
+        message: String,
+
+        /// The underlying cause
+        #[serde(skip)]
+        source: Option<ErrorSource>,
+    },
 }
 
-impl error::Error for Error {}
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Error::Serialization {
+                source: Some(x), ..
+            } |
+            Error::Store {
+                source: Some(x), ..
+            } => Some(x.get()),
+            _ => None,
+        }
+    }
+}
 
 impl Display for Error {
     fn fmt(
@@ -37,12 +139,31 @@ impl Display for Error {
         f: &mut Formatter<'_>,
     ) -> fmtResult {
         match self {
-            Error::TechnicalError(message) => {
+            Error::TechnicalError(message) |
+            Error::Unauthorized(message) |
+            Error::Serialization { message, .. } |
+            Error::Store { message, .. } => {
                 write!(f, "{}", message)
             },
-            Error::UserError(message) => {
+            Error::UserError(message) |
+            Error::Validation(message) => {
                 write!(f, "{}", message)
             },
+            Error::NotFound { entity, id } => {
+                write!(f, "{entity} '{id}' not found")
+            },
+            Error::ConcurrencyConflict {
+                aggregate_id,
+                expected,
+                actual,
+            } => {
+                write!(
+                    f,
+                    "concurrency conflict on aggregate \
+                     '{aggregate_id}': expected version {expected} \
+                     but found version {actual}"
+                )
+            },
         }
     }
 }
@@ -50,6 +171,7 @@ impl Display for Error {
 impl Error {
     /// Convenience function to construct a simple `UserError` from a
     /// `&str`.
+    #[must_use]
     pub fn new(msg: &str) -> Self {
         Error::UserError(UserError {
             code: None,
@@ -57,17 +179,94 @@ impl Error {
             params: None,
         })
     }
+
+    /// Convenience function to construct an `Error::Unauthorized`
+    #[must_use]
+    pub fn unauthorized(msg: &str) -> Self {
+        Error::Unauthorized(msg.to_string())
+    }
+
+    /// Convenience function to construct an `Error::NotFound`
+    #[must_use]
+    pub fn not_found(
+        entity: &str,
+        id: &str,
+    ) -> Self {
+        Error::NotFound {
+            entity: entity.to_string(),
+            id: id.to_string(),
+        }
+    }
+
+    /// Convenience function to construct an `Error::Serialization`
+    /// with an optional underlying cause
+    #[must_use]
+    pub fn serialization(
+        message: &str,
+        source: Option<ErrorSource>,
+    ) -> Self {
+        Error::Serialization {
+            message: message.to_string(),
+            source,
+        }
+    }
+
+    /// Convenience function to construct an `Error::Store` with an
+    /// optional underlying cause
+    #[must_use]
+    pub fn store(
+        message: &str,
+        source: Option<ErrorSource>,
+    ) -> Self {
+        Error::Store {
+            message: message.to_string(),
+            source,
+        }
+    }
+
+    /// The category of this error
+    #[must_use]
+    pub fn category(&self) -> ErrorCategory {
+        match self {
+            Error::UserError(_) => ErrorCategory::User,
+            Error::Validation(_) => ErrorCategory::Validation,
+            Error::Unauthorized(_) => ErrorCategory::Unauthorized,
+            Error::NotFound { .. } => ErrorCategory::NotFound,
+            Error::ConcurrencyConflict { .. } => {
+                ErrorCategory::Conflict
+            },
+            Error::Serialization { .. } => {
+                ErrorCategory::Serialization
+            },
+            Error::Store { .. } => ErrorCategory::Store,
+            Error::TechnicalError(_) => ErrorCategory::Technical,
+        }
+    }
+
+    /// A stable machine-readable code identifying the kind of this
+    /// error
+    #[must_use]
+    pub fn code(&self) -> &'static str {
+        match self {
+            Error::UserError(_) => "user_error",
+            Error::Validation(_) => "validation_error",
+            Error::Unauthorized(_) => "unauthorized",
+            Error::NotFound { .. } => "not_found",
+            Error::ConcurrencyConflict { .. } => {
+                "concurrency_conflict"
+            },
+            Error::Serialization { .. } => "serialization_error",
+            Error::Store { .. } => "store_error",
+            Error::TechnicalError(_) => "technical_error",
+        }
+    }
 }
 
 impl From<serde_json::error::Error> for Error {
     fn from(e: serde_json::error::Error) -> Self {
-        match e.classify() {
-            serde_json::error::Category::Syntax => {
-                Error::new("invalid json")
-            },
-            serde_json::error::Category::Io |
-            serde_json::error::Category::Data |
-            serde_json::error::Category::Eof => Error::new("fail"),
+        Error::Serialization {
+            message: format!("{:?} error: {}", e.classify(), e),
+            source: Some(e.into()),
         }
     }
 }
@@ -0,0 +1,84 @@
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use std::fmt::{
+    Debug,
+    Display,
+    Formatter,
+    Result as fmtResult,
+};
+
+/// The category of an `Error`, meant to let callers react to whole
+/// families of errors, e.g., to map them to HTTP status codes:
+///
+/// | Category        | HTTP status                   |
+/// |-----------------|-------------------------------|
+/// | `User`          | `400 Bad Request`             |
+/// | `Validation`    | `422 Unprocessable Entity`    |
+/// | `Unauthorized`  | `403 Forbidden`               |
+/// | `NotFound`      | `404 Not Found`               |
+/// | `Conflict`      | `409 Conflict`                |
+/// | `Serialization` | `500 Internal Server Error`   |
+/// | `Store`         | `503 Service Unavailable`     |
+/// | `Technical`     | `500 Internal Server Error`   |
+#[derive(
+    Debug,
+    PartialEq,
+    Eq,
+    Hash,
+    Clone,
+    Copy,
+    Serialize,
+    Deserialize
+)]
+pub enum ErrorCategory {
+    /// A business rule rejected the request
+    User,
+
+    /// The request is malformed
+    Validation,
+
+    /// The caller is not allowed to perform the request
+    Unauthorized,
+
+    /// The requested entity does not exist
+    NotFound,
+
+    /// The request conflicts with a concurrent change
+    Conflict,
+
+    /// Data could not be serialized or deserialized
+    Serialization,
+
+    /// A storage backend failed
+    Store,
+
+    /// Any other technical failure
+    Technical,
+}
+
+impl ErrorCategory {
+    /// Whether errors of this category are caused by the caller and
+    /// their details can safely be returned to them
+    #[must_use]
+    pub fn is_client_error(&self) -> bool {
+        matches!(
+            self,
+            ErrorCategory::User |
+                ErrorCategory::Validation |
+                ErrorCategory::Unauthorized |
+                ErrorCategory::NotFound |
+                ErrorCategory::Conflict
+        )
+    }
+}
+
+impl Display for ErrorCategory {
+    fn fmt(
+        &self,
+        f: &mut Formatter<'_>,
+    ) -> fmtResult {
+        Debug::fmt(self, f)
+    }
+}
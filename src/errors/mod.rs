@@ -2,8 +2,21 @@
 //!
 //! A central location for errors and error handling
 
-pub use error::Error;
+pub use error::{
+    Error,
+    ErrorSource,
+};
+pub use error_category::ErrorCategory;
 pub use user_error::UserError;
+pub use validation_errors::{
+    ValidationErrors,
+    VALIDATION_MESSAGE,
+};
 
 mod error;
+mod error_category;
 mod user_error;
+mod validation_errors;
+
+#[cfg(test)]
+mod test;
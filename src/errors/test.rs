@@ -0,0 +1,252 @@
+use std::{
+    collections::HashMap,
+    error::Error as StdError,
+};
+
+use super::{
+    error::Error,
+    error_category::ErrorCategory,
+    user_error::UserError,
+    validation_errors::{
+        ValidationErrors,
+        VALIDATION_MESSAGE,
+    },
+};
+
+fn all_errors() -> Vec<Error> {
+    let mut params = HashMap::new();
+    params.insert(
+        "email".to_string(),
+        "is not a valid address".to_string(),
+    );
+
+    vec![
+        Error::new("user error"),
+        Error::Validation(UserError {
+            code: None,
+            message: Some("invalid command".to_string()),
+            params: Some(params),
+        }),
+        Error::unauthorized("not allowed"),
+        Error::not_found("customer", "test_id_A"),
+        Error::ConcurrencyConflict {
+            aggregate_id: "test_id_A".to_string(),
+            expected: 1,
+            actual: 2,
+        },
+        Error::serialization("bad payload", None),
+        Error::store("store is down", None),
+        Error::TechnicalError("technical error".to_string()),
+    ]
+}
+
+#[test]
+fn test_codes_and_categories() {
+    let result: Vec<_> = all_errors()
+        .iter()
+        .map(|x| (x.code(), x.category()))
+        .collect();
+
+    assert_eq!(
+        result,
+        vec![
+            ("user_error", ErrorCategory::User),
+            (
+                "validation_error",
+                ErrorCategory::Validation
+            ),
+            (
+                "unauthorized",
+                ErrorCategory::Unauthorized
+            ),
+            ("not_found", ErrorCategory::NotFound),
+            (
+                "concurrency_conflict",
+                ErrorCategory::Conflict
+            ),
+            (
+                "serialization_error",
+                ErrorCategory::Serialization
+            ),
+            ("store_error", ErrorCategory::Store),
+            (
+                "technical_error",
+                ErrorCategory::Technical
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_client_errors() {
+    let result: Vec<_> = all_errors()
+        .iter()
+        .map(|x| x.category().is_client_error())
+        .collect();
+
+    assert_eq!(
+        result,
+        vec![true, true, true, true, true, false, false, false]
+    );
+}
+
+#[test]
+fn test_display() {
+    assert_eq!(
+        Error::not_found("customer", "test_id_A").to_string(),
+        "customer 'test_id_A' not found"
+    );
+
+    assert_eq!(
+        Error::ConcurrencyConflict {
+            aggregate_id: "test_id_A".to_string(),
+            expected: 1,
+            actual: 2,
+        }
+        .to_string(),
+        "concurrency conflict on aggregate 'test_id_A': expected \
+         version 1 but found version 2"
+    );
+
+    assert_eq!(
+        Error::store("store is down", None).to_string(),
+        "store is down"
+    );
+}
+
+#[test]
+fn test_source_chain() {
+    let json_error =
+        serde_json::from_str::<HashMap<String, String>>("{\"a\":")
+            .unwrap_err();
+
+    let error: Error = json_error.into();
+
+    assert_eq!(
+        error.category(),
+        ErrorCategory::Serialization
+    );
+
+    let source = error
+        .source()
+        .and_then(|x| x.downcast_ref::<serde_json::Error>())
+        .unwrap();
+
+    assert_eq!(source.line(), 1);
+    assert!(source.is_eof());
+
+    assert!(Error::new("user error")
+        .source()
+        .is_none());
+    assert!(Error::store("store is down", None)
+        .source()
+        .is_none());
+}
+
+#[test]
+fn test_equality_ignores_source() {
+    let json_error =
+        serde_json::from_str::<HashMap<String, String>>("[")
+            .unwrap_err();
+
+    let io_error = std::io::Error::other("disk is full");
+
+    assert_eq!(
+        Error::serialization("bad payload", Some(json_error.into())),
+        Error::serialization("bad payload", Some(io_error.into()))
+    );
+
+    assert_ne!(
+        Error::serialization("bad payload", None),
+        Error::store("bad payload", None)
+    );
+}
+
+#[test]
+fn test_serde_round_trip_drops_source() {
+    let json_error =
+        serde_json::from_str::<HashMap<String, String>>("[")
+            .unwrap_err();
+
+    let error =
+        Error::store("store is down", Some(json_error.into()));
+
+    let serialized = serde_json::to_value(&error).unwrap();
+
+    assert_eq!(
+        serialized,
+        serde_json::json!({
+            "Store": { "message": "store is down" }
+        })
+    );
+
+    let deserialized: Error =
+        serde_json::from_value(serialized).unwrap();
+
+    assert_eq!(
+        deserialized,
+        Error::store("store is down", None)
+    );
+}
+
+#[test]
+fn test_validation_errors() {
+    let mut errors = ValidationErrors::default();
+
+    errors.require_non_empty("name", "John Doe");
+    errors.require_length("name", "John Doe", 1, 20);
+    errors.require_email("email", "john@doe.com");
+    errors.require_email("email", "john@mail.doe.com");
+    errors.require("age", true, "must be positive");
+
+    assert!(errors.is_empty());
+    assert_eq!(errors.into_result(), Ok(()));
+
+    let mut errors = ValidationErrors::default();
+
+    errors.require_non_empty("name", "  ");
+    errors.require_length("name", "  ", 3, 20);
+    errors.require("age", false, "must be positive");
+
+    for email in &[
+        "john.doe",
+        "@doe.com",
+        "john@doe",
+        "john@.com",
+        "john@doe.",
+        "john@doe..com",
+        "a@b..c",
+        "john@do@e.com",
+        "john @doe.com",
+    ] {
+        let mut errors = ValidationErrors::default();
+
+        errors.require_email("email", email);
+
+        assert_eq!(
+            errors.params()["email"],
+            "is not a valid email address",
+            "{email}"
+        );
+    }
+
+    let mut params = HashMap::new();
+    params.insert(
+        "name".to_string(),
+        "is required; must be between 3 and 20 characters long"
+            .to_string(),
+    );
+    params.insert(
+        "age".to_string(),
+        "must be positive".to_string(),
+    );
+
+    assert_eq!(
+        errors.into_result(),
+        Err(Error::Validation(UserError {
+            code: None,
+            message: Some(VALIDATION_MESSAGE.to_string()),
+            params: Some(params),
+        }))
+    );
+}
@@ -0,0 +1,161 @@
+use std::collections::HashMap;
+
+use super::{
+    error::Error,
+    user_error::UserError,
+};
+
+/// The message of the `Error::Validation` built from
+/// `ValidationErrors`
+pub const VALIDATION_MESSAGE: &str = "the command is invalid";
+
+/// Collects the validation failures of a command as field to message
+/// pairs, which become the `params` of an `Error::Validation`.
+/// Several failures of the same field are joined with `"; "`.
+///
+/// # Examples
+/// ```rust
+/// use cqrs_es2::{
+///     Error,
+///     ValidationErrors,
+/// };
+///
+/// let mut errors = ValidationErrors::default();
+///
+/// errors.require_non_empty("name", "");
+/// errors.require_email("email", "john.doe");
+///
+/// match errors.into_result() {
+///     Err(Error::Validation(x)) => {
+///         let params = x.params.unwrap();
+///
+///         assert_eq!(params["name"], "is required");
+///         assert_eq!(
+///             params["email"],
+///             "is not a valid email address"
+///         );
+///     },
+///     _ => panic!("expected a validation error"),
+/// }
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct ValidationErrors {
+    params: HashMap<String, String>,
+}
+
+impl ValidationErrors {
+    /// Records a failure of `field`
+    pub fn add(
+        &mut self,
+        field: &str,
+        message: &str,
+    ) {
+        self.params
+            .entry(field.to_string())
+            .and_modify(|x| {
+                x.push_str("; ");
+                x.push_str(message);
+            })
+            .or_insert_with(|| message.to_string());
+    }
+
+    /// Records a failure of `field` unless `condition` holds
+    pub fn require(
+        &mut self,
+        field: &str,
+        condition: bool,
+        message: &str,
+    ) {
+        if !condition {
+            self.add(field, message);
+        }
+    }
+
+    /// Requires `value` to contain more than whitespace
+    pub fn require_non_empty(
+        &mut self,
+        field: &str,
+        value: &str,
+    ) {
+        self.require(
+            field,
+            !value.trim().is_empty(),
+            "is required",
+        );
+    }
+
+    /// Requires `value` to be between `min` and `max` characters long
+    pub fn require_length(
+        &mut self,
+        field: &str,
+        value: &str,
+        min: usize,
+        max: usize,
+    ) {
+        let length = value.chars().count();
+
+        self.require(
+            field,
+            (min..=max).contains(&length),
+            &format!(
+                "must be between {min} and {max} characters long"
+            ),
+        );
+    }
+
+    /// Requires `value` to look like an email address, i.e., a local
+    /// part and a dotted domain without empty labels around a single
+    /// `@` without whitespace
+    pub fn require_email(
+        &mut self,
+        field: &str,
+        value: &str,
+    ) {
+        let valid = match value.split_once('@') {
+            Some((local, domain)) => {
+                !local.is_empty() &&
+                    !domain.contains('@') &&
+                    domain.split('.').count() >= 2 &&
+                    domain.split('.').all(|x| !x.is_empty()) &&
+                    !value.chars().any(char::is_whitespace)
+            },
+            None => false,
+        };
+
+        self.require(
+            field,
+            valid,
+            "is not a valid email address",
+        );
+    }
+
+    /// Whether no failure has been recorded
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.params.is_empty()
+    }
+
+    /// The recorded failures by field
+    #[must_use]
+    pub fn params(&self) -> &HashMap<String, String> {
+        &self.params
+    }
+
+    /// Converts the recorded failures into an `Error::Validation`,
+    /// or `Ok` when there are none
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error::Validation` when a failure was recorded.
+    pub fn into_result(self) -> Result<(), Error> {
+        if self.is_empty() {
+            return Ok(());
+        }
+
+        Err(Error::Validation(UserError {
+            code: None,
+            message: Some(VALIDATION_MESSAGE.to_string()),
+            params: Some(self.params),
+        }))
+    }
+}
@@ -1,3 +1,7 @@
+use chrono::{
+    DateTime,
+    Utc,
+};
 use log::trace;
 use std::{
     collections::HashMap,
@@ -5,7 +9,16 @@ use std::{
     marker::PhantomData,
 };
 
-use crate::commands::ICommand;
+use crate::{
+    commands::ICommand,
+    metadata::{
+        CAUSATION_ID_KEY,
+        COMMAND_NAME_KEY,
+        CORRELATION_ID_KEY,
+        PRINCIPAL_KEY,
+        TIMESTAMP_KEY,
+    },
+};
 
 use super::i_event::IEvent;
 
@@ -50,8 +63,51 @@ impl<C: ICommand, E: IEvent> EventContext<C, E> {
             _phantom: PhantomData,
         };
 
-        trace!("Created new {:?}", x,);
+        trace!("Created new {x:?}");
 
         x
     }
+
+    /// The UTC time at which the event was produced, if recorded and
+    /// valid
+    #[must_use]
+    pub fn timestamp(&self) -> Option<DateTime<Utc>> {
+        self.metadata
+            .get(TIMESTAMP_KEY)
+            .and_then(|x| DateTime::parse_from_rfc3339(x).ok())
+            .map(|x| x.with_timezone(&Utc))
+    }
+
+    /// The id of the business flow the event belongs to, if recorded
+    #[must_use]
+    pub fn correlation_id(&self) -> Option<&str> {
+        self.metadata_value(CORRELATION_ID_KEY)
+    }
+
+    /// The id of the message that caused the event, if recorded
+    #[must_use]
+    pub fn causation_id(&self) -> Option<&str> {
+        self.metadata_value(CAUSATION_ID_KEY)
+    }
+
+    /// The name of the command that produced the event, if recorded
+    #[must_use]
+    pub fn command_name(&self) -> Option<&str> {
+        self.metadata_value(COMMAND_NAME_KEY)
+    }
+
+    /// The user or service that executed the command, if recorded
+    #[must_use]
+    pub fn principal(&self) -> Option<&str> {
+        self.metadata_value(PRINCIPAL_KEY)
+    }
+
+    fn metadata_value(
+        &self,
+        key: &str,
+    ) -> Option<&str> {
+        self.metadata
+            .get(key)
+            .map(String::as_str)
+    }
 }
@@ -0,0 +1,180 @@
+use serde::{
+    de::{
+        self,
+        DeserializeOwned,
+        DeserializeSeed,
+        IntoDeserializer,
+        MapAccess,
+        Visitor,
+    },
+    forward_to_deserialize_any,
+    Deserializer,
+};
+use std::fmt::{
+    self,
+    Display,
+    Formatter,
+};
+
+use super::event_type_cache::cached_representation;
+
+/// A tag value no enum variant is expected to be named after
+const UNKNOWN_TAG: &str = "\u{0}";
+
+/// How serde represents an event type, found out by probing its
+/// `Deserialize` implementation without any data
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub(crate) enum EventRepresentation {
+    /// An externally tagged enum with the names of its variants
+    External(&'static [&'static str]),
+
+    /// An internally tagged enum with the names of its variants
+    Internal(&'static [&'static str]),
+
+    /// A struct or an adjacently tagged enum, both keeping the whole
+    /// serialized value as their payload
+    Struct,
+
+    /// Any other representation, e.g., an untagged enum
+    Unsupported,
+}
+
+impl EventRepresentation {
+    /// Probes the representation of `E`. An internally tagged enum
+    /// is only recognized when `tag` is its tag field.
+    pub(crate) fn of<E: DeserializeOwned>(tag: Option<&str>) -> Self {
+        match E::deserialize(Probe { tag }) {
+            Ok(_) => Self::Unsupported,
+            Err(Found(x)) => x,
+        }
+    }
+
+    /// Probes the representation of `E` without a tag once, and
+    /// answers from the cache afterwards
+    pub(crate) fn cached<E: DeserializeOwned>() -> Self {
+        cached_representation::<E, _>(|| Self::of::<E>(None))
+    }
+}
+
+/// Carries the representation found by the probe
+#[derive(Debug)]
+struct Found(EventRepresentation);
+
+impl Display for Found {
+    fn fmt(
+        &self,
+        f: &mut Formatter<'_>,
+    ) -> fmt::Result {
+        write!(f, "event representation {:?}", self.0)
+    }
+}
+
+impl std::error::Error for Found {}
+
+impl de::Error for Found {
+    fn custom<T: Display>(_msg: T) -> Self {
+        Found(EventRepresentation::Unsupported)
+    }
+
+    fn unknown_variant(
+        _variant: &str,
+        expected: &'static [&'static str],
+    ) -> Self {
+        Found(EventRepresentation::Internal(expected))
+    }
+}
+
+/// A deserializer answering the first call of a `Deserialize`
+/// implementation, which reveals its representation
+struct Probe<'a> {
+    tag: Option<&'a str>,
+}
+
+impl<'de> Deserializer<'de> for Probe<'_> {
+    type Error = Found;
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str
+        string bytes byte_buf option unit seq tuple tuple_struct map
+        identifier ignored_any
+    }
+
+    fn deserialize_any<V: Visitor<'de>>(
+        self,
+        visitor: V,
+    ) -> Result<V::Value, Found> {
+        // an internally tagged enum reads the tag through a map and
+        // reports its variants when the tag is unknown
+        match self.tag {
+            Some(tag) => {
+                visitor.visit_map(TagAccess { tag: Some(tag) })
+            },
+            None => Err(Found(EventRepresentation::Unsupported)),
+        }
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        variants: &'static [&'static str],
+        _visitor: V,
+    ) -> Result<V::Value, Found> {
+        Err(Found(EventRepresentation::External(
+            variants,
+        )))
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _fields: &'static [&'static str],
+        _visitor: V,
+    ) -> Result<V::Value, Found> {
+        Err(Found(EventRepresentation::Struct))
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _visitor: V,
+    ) -> Result<V::Value, Found> {
+        Err(Found(EventRepresentation::Struct))
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _visitor: V,
+    ) -> Result<V::Value, Found> {
+        Err(Found(EventRepresentation::Struct))
+    }
+}
+
+/// A map holding the tag field with an unknown value
+struct TagAccess<'a> {
+    tag: Option<&'a str>,
+}
+
+impl<'de> MapAccess<'de> for TagAccess<'_> {
+    type Error = Found;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Found> {
+        match self.tag.take() {
+            Some(tag) => {
+                seed.deserialize(tag.into_deserializer())
+                    .map(Some)
+            },
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, Found> {
+        seed.deserialize(UNKNOWN_TAG.into_deserializer())
+    }
+}
@@ -0,0 +1,147 @@
+use std::{
+    any::type_name,
+    borrow::Borrow,
+    collections::{
+        hash_map::DefaultHasher,
+        HashMap,
+    },
+    hash::{
+        Hash,
+        Hasher,
+    },
+    mem::discriminant,
+    sync::{
+        OnceLock,
+        RwLock,
+    },
+};
+
+use super::event_representation::EventRepresentation;
+
+/// A process wide cache keyed by the name of a Rust type, which
+/// stands for the type itself as event types can not be told apart
+/// by lifetimes only
+type TypeCache<K, V> =
+    OnceLock<RwLock<HashMap<&'static str, HashMap<K, V>>>>;
+
+/// The event type of every variant of the event types, keyed by a
+/// hash of the discriminant of the variant
+static EVENT_TYPES: TypeCache<u64, &'static str> = OnceLock::new();
+
+/// The representation of the event types
+static REPRESENTATIONS: TypeCache<(), EventRepresentation> =
+    OnceLock::new();
+
+/// The externally tagged variant named after an overridden event type
+static VARIANTS: TypeCache<String, &'static str> = OnceLock::new();
+
+/// The event type of the variant of `event`, resolved by `resolve`
+/// the first time the variant is seen
+pub(crate) fn cached_event_type<E, F>(
+    event: &E,
+    resolve: F,
+) -> &'static str
+where
+    F: FnOnce() -> &'static str, {
+    let mut hasher = DefaultHasher::new();
+    discriminant(event).hash(&mut hasher);
+
+    cached(
+        &EVENT_TYPES,
+        type_name::<E>(),
+        hasher.finish(),
+        resolve,
+    )
+}
+
+/// The representation of `E` as revealed by probing it without a
+/// tag, see `EventRepresentation::of`
+pub(crate) fn cached_representation<E, F>(
+    resolve: F
+) -> EventRepresentation
+where
+    F: FnOnce() -> EventRepresentation, {
+    cached(
+        &REPRESENTATIONS,
+        type_name::<E>(),
+        (),
+        resolve,
+    )
+}
+
+/// The externally tagged variant of `E` whose event type is
+/// `event_type`, found by `resolve` the first time it is requested
+pub(crate) fn cached_variant<E, F>(
+    event_type: &str,
+    resolve: F,
+) -> Option<&'static str>
+where
+    F: FnOnce() -> Option<&'static str>, {
+    let variant = read(&VARIANTS, type_name::<E>(), event_type);
+
+    if variant.is_some() {
+        return variant;
+    }
+
+    let variant = resolve()?;
+    write(
+        &VARIANTS,
+        type_name::<E>(),
+        event_type.to_string(),
+        variant,
+    );
+
+    Some(variant)
+}
+
+fn cached<K: Hash + Eq, V: Copy, F: FnOnce() -> V>(
+    cache: &TypeCache<K, V>,
+    type_name: &'static str,
+    key: K,
+    resolve: F,
+) -> V {
+    if let Some(x) = read(cache, type_name, &key) {
+        return x;
+    }
+
+    let value = resolve();
+    write(cache, type_name, key, value);
+
+    value
+}
+
+/// Reads an entry of `cache`, a poisoned cache is bypassed
+fn read<K, Q, V>(
+    cache: &TypeCache<K, V>,
+    type_name: &'static str,
+    key: &Q,
+) -> Option<V>
+where
+    K: Hash + Eq + Borrow<Q>,
+    Q: Hash + Eq + ?Sized,
+    V: Copy, {
+    cache
+        .get()?
+        .read()
+        .ok()?
+        .get(type_name)?
+        .get(key)
+        .copied()
+}
+
+/// Writes an entry of `cache`, a poisoned cache is bypassed
+fn write<K: Hash + Eq, V>(
+    cache: &TypeCache<K, V>,
+    type_name: &'static str,
+    key: K,
+    value: V,
+) {
+    if let Ok(mut x) = cache
+        .get_or_init(RwLock::default)
+        .write()
+    {
+        x.entry(type_name)
+            .or_default()
+            .insert(key, value);
+    }
+}
@@ -0,0 +1,564 @@
+use log::warn;
+use serde::{
+    ser::{
+        self,
+        Impossible,
+        SerializeMap,
+        SerializeStruct,
+        SerializeStructVariant,
+        SerializeTupleVariant,
+    },
+    Serialize,
+    Serializer,
+};
+use std::{
+    any::type_name,
+    fmt::{
+        self,
+        Display,
+        Formatter,
+    },
+};
+
+use super::{
+    event_representation::EventRepresentation,
+    event_type_cache::cached_event_type,
+    i_event::IEvent,
+};
+
+/// Returns the serialized name of the enum variant of `event`, or the
+/// name of its struct, without serializing its fields. Externally,
+/// internally and adjacently tagged enums are supported, any other
+/// representation falls back to the name of the Rust type. The name
+/// is resolved once per variant and cached afterwards.
+pub(crate) fn event_type_of<E: IEvent>(event: &E) -> &'static str {
+    cached_event_type(event, || {
+        resolve_event_type(event).unwrap_or_else(|| {
+            let name = type_name::<E>();
+            let name = name.split('<').next().unwrap_or(name);
+            let name = name.rsplit("::").next().unwrap_or(name);
+
+            warn!(
+                "unable to resolve the event type of '{}', only \
+                 structs and externally, internally or adjacently \
+                 tagged enums are supported, falling back to '{}'",
+                type_name::<E>(),
+                name
+            );
+
+            name
+        })
+    })
+}
+
+/// Probes the serialized name of the enum variant of `event`, or the
+/// name of its struct
+fn resolve_event_type<E: IEvent>(event: &E) -> Option<&'static str> {
+    match event.serialize(EventTypeSerializer) {
+        Ok(SerializedName::Variant(variant)) => Some(variant),
+        Ok(SerializedName::Struct { name, first_field }) => {
+            let tag = first_field
+                .as_ref()
+                .map(|(key, _)| key.as_str());
+
+            match EventRepresentation::of::<E>(tag) {
+                EventRepresentation::Struct => name,
+                EventRepresentation::Internal(variants) => {
+                    first_field.and_then(|(_, value)| {
+                        variants
+                            .iter()
+                            .find(|&&x| x == value)
+                            .copied()
+                    })
+                },
+                _ => None,
+            }
+        },
+        Err(_) => None,
+    }
+}
+
+/// What the serializer learned about the name of a value
+enum SerializedName {
+    /// The name of an externally or adjacently tagged enum variant
+    Variant(&'static str),
+
+    /// A struct, or a map, along with its first field when it holds
+    /// a string, i.e., the tag of an internally tagged enum variant
+    Struct {
+        name: Option<&'static str>,
+        first_field: Option<(String, String)>,
+    },
+}
+
+/// The value of the first field of a struct or a map
+enum FieldValue {
+    /// A unit variant of the enum named after the enclosing struct,
+    /// i.e., the tag of an adjacently tagged enum variant
+    Variant(&'static str),
+
+    /// A string, possibly the tag of an internally tagged enum
+    /// variant
+    Str(String),
+}
+
+/// Raised for every value that does not name an event type
+#[derive(Debug)]
+struct NotAnEventType;
+
+impl Display for NotAnEventType {
+    fn fmt(
+        &self,
+        f: &mut Formatter<'_>,
+    ) -> fmt::Result {
+        write!(
+            f,
+            "the value is not an enum variant or a struct"
+        )
+    }
+}
+
+impl std::error::Error for NotAnEventType {}
+
+impl ser::Error for NotAnEventType {
+    fn custom<T: Display>(_msg: T) -> Self {
+        NotAnEventType
+    }
+}
+
+/// A serializer capturing the `&'static str` name serde hands over
+/// for enum variants and structs
+struct EventTypeSerializer;
+
+/// Skips the fields of a variant and yields its name
+struct EventTypeName(&'static str);
+
+/// Skips the fields of a struct or a map, except for the first one
+struct EventTypeFields {
+    name: Option<&'static str>,
+    first_key: Option<String>,
+    first_field: Option<FieldValue>,
+    fields: usize,
+}
+
+/// A serializer capturing the first field of a struct or a map
+struct FieldSerializer {
+    name: Option<&'static str>,
+}
+
+macro_rules! not_an_event_type {
+    ($($method:ident($($arg:ty),*)),* $(,)?) => {
+        $(
+            fn $method(
+                self,
+                $(_: $arg),*
+            ) -> Result<Self::Ok, NotAnEventType> {
+                Err(NotAnEventType)
+            }
+        )*
+    };
+}
+
+impl Serializer for EventTypeSerializer {
+    type Error = NotAnEventType;
+    type Ok = SerializedName;
+    type SerializeMap = EventTypeFields;
+    type SerializeSeq = Impossible<SerializedName, NotAnEventType>;
+    type SerializeStruct = EventTypeFields;
+    type SerializeStructVariant = EventTypeName;
+    type SerializeTuple = Impossible<SerializedName, NotAnEventType>;
+    type SerializeTupleStruct =
+        Impossible<SerializedName, NotAnEventType>;
+    type SerializeTupleVariant = EventTypeName;
+
+    not_an_event_type!(
+        serialize_bool(bool),
+        serialize_i8(i8),
+        serialize_i16(i16),
+        serialize_i32(i32),
+        serialize_i64(i64),
+        serialize_u8(u8),
+        serialize_u16(u16),
+        serialize_u32(u32),
+        serialize_u64(u64),
+        serialize_f32(f32),
+        serialize_f64(f64),
+        serialize_char(char),
+        serialize_str(&str),
+        serialize_bytes(&[u8]),
+        serialize_none(),
+        serialize_unit(),
+    );
+
+    fn serialize_some<T: Serialize + ?Sized>(
+        self,
+        value: &T,
+    ) -> Result<SerializedName, NotAnEventType> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit_struct(
+        self,
+        name: &'static str,
+    ) -> Result<SerializedName, NotAnEventType> {
+        Ok(SerializedName::Struct {
+            name: Some(name),
+            first_field: None,
+        })
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<SerializedName, NotAnEventType> {
+        Ok(SerializedName::Variant(variant))
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        name: &'static str,
+        _value: &T,
+    ) -> Result<SerializedName, NotAnEventType> {
+        Ok(SerializedName::Struct {
+            name: Some(name),
+            first_field: None,
+        })
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _value: &T,
+    ) -> Result<SerializedName, NotAnEventType> {
+        Ok(SerializedName::Variant(variant))
+    }
+
+    fn serialize_seq(
+        self,
+        _len: Option<usize>,
+    ) -> Result<Self::SerializeSeq, NotAnEventType> {
+        Err(NotAnEventType)
+    }
+
+    fn serialize_tuple(
+        self,
+        _len: usize,
+    ) -> Result<Self::SerializeTuple, NotAnEventType> {
+        Err(NotAnEventType)
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct, NotAnEventType> {
+        Err(NotAnEventType)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, NotAnEventType> {
+        Ok(EventTypeName(variant))
+    }
+
+    fn serialize_map(
+        self,
+        _len: Option<usize>,
+    ) -> Result<Self::SerializeMap, NotAnEventType> {
+        Ok(EventTypeFields::new(None))
+    }
+
+    fn serialize_struct(
+        self,
+        name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStruct, NotAnEventType> {
+        Ok(EventTypeFields::new(Some(name)))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, NotAnEventType> {
+        Ok(EventTypeName(variant))
+    }
+}
+
+impl SerializeTupleVariant for EventTypeName {
+    type Error = NotAnEventType;
+    type Ok = SerializedName;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        _value: &T,
+    ) -> Result<(), NotAnEventType> {
+        Ok(())
+    }
+
+    fn end(self) -> Result<SerializedName, NotAnEventType> {
+        Ok(SerializedName::Variant(self.0))
+    }
+}
+
+impl SerializeStructVariant for EventTypeName {
+    type Error = NotAnEventType;
+    type Ok = SerializedName;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        _key: &'static str,
+        _value: &T,
+    ) -> Result<(), NotAnEventType> {
+        Ok(())
+    }
+
+    fn end(self) -> Result<SerializedName, NotAnEventType> {
+        Ok(SerializedName::Variant(self.0))
+    }
+}
+
+impl EventTypeFields {
+    fn new(name: Option<&'static str>) -> Self {
+        Self {
+            name,
+            first_key: None,
+            first_field: None,
+            fields: 0,
+        }
+    }
+
+    fn capture<T: Serialize + ?Sized>(
+        &mut self,
+        value: &T,
+    ) {
+        if self.fields == 0 {
+            self.first_field = value
+                .serialize(FieldSerializer { name: self.name })
+                .ok();
+        }
+
+        self.fields += 1;
+    }
+
+    fn finish(self) -> SerializedName {
+        match (self.first_key, self.first_field) {
+            (_, Some(FieldValue::Variant(variant))) => {
+                SerializedName::Variant(variant)
+            },
+            (Some(key), Some(FieldValue::Str(value))) => {
+                SerializedName::Struct {
+                    name: self.name,
+                    first_field: Some((key, value)),
+                }
+            },
+            _ => {
+                SerializedName::Struct {
+                    name: self.name,
+                    first_field: None,
+                }
+            },
+        }
+    }
+}
+
+impl SerializeStruct for EventTypeFields {
+    type Error = NotAnEventType;
+    type Ok = SerializedName;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), NotAnEventType> {
+        if self.fields == 0 {
+            self.first_key = Some(key.to_string());
+        }
+
+        self.capture(value);
+        Ok(())
+    }
+
+    fn end(self) -> Result<SerializedName, NotAnEventType> {
+        Ok(self.finish())
+    }
+}
+
+impl SerializeMap for EventTypeFields {
+    type Error = NotAnEventType;
+    type Ok = SerializedName;
+
+    fn serialize_key<T: Serialize + ?Sized>(
+        &mut self,
+        key: &T,
+    ) -> Result<(), NotAnEventType> {
+        if self.fields == 0 {
+            if let Ok(FieldValue::Str(key)) =
+                key.serialize(FieldSerializer { name: None })
+            {
+                self.first_key = Some(key);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(
+        &mut self,
+        value: &T,
+    ) -> Result<(), NotAnEventType> {
+        self.capture(value);
+        Ok(())
+    }
+
+    fn end(self) -> Result<SerializedName, NotAnEventType> {
+        Ok(self.finish())
+    }
+}
+
+impl Serializer for FieldSerializer {
+    type Error = NotAnEventType;
+    type Ok = FieldValue;
+    type SerializeMap = Impossible<FieldValue, NotAnEventType>;
+    type SerializeSeq = Impossible<FieldValue, NotAnEventType>;
+    type SerializeStruct = Impossible<FieldValue, NotAnEventType>;
+    type SerializeStructVariant =
+        Impossible<FieldValue, NotAnEventType>;
+    type SerializeTuple = Impossible<FieldValue, NotAnEventType>;
+    type SerializeTupleStruct =
+        Impossible<FieldValue, NotAnEventType>;
+    type SerializeTupleVariant =
+        Impossible<FieldValue, NotAnEventType>;
+
+    not_an_event_type!(
+        serialize_bool(bool),
+        serialize_i8(i8),
+        serialize_i16(i16),
+        serialize_i32(i32),
+        serialize_i64(i64),
+        serialize_u8(u8),
+        serialize_u16(u16),
+        serialize_u32(u32),
+        serialize_u64(u64),
+        serialize_f32(f32),
+        serialize_f64(f64),
+        serialize_char(char),
+        serialize_bytes(&[u8]),
+        serialize_none(),
+        serialize_unit(),
+        serialize_unit_struct(&'static str),
+    );
+
+    fn serialize_str(
+        self,
+        value: &str,
+    ) -> Result<FieldValue, NotAnEventType> {
+        Ok(FieldValue::Str(value.to_string()))
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(
+        self,
+        _value: &T,
+    ) -> Result<FieldValue, NotAnEventType> {
+        Err(NotAnEventType)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<FieldValue, NotAnEventType> {
+        if self.name == Some(name) {
+            Ok(FieldValue::Variant(variant))
+        }
+        else {
+            Err(NotAnEventType)
+        }
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _value: &T,
+    ) -> Result<FieldValue, NotAnEventType> {
+        Err(NotAnEventType)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<FieldValue, NotAnEventType> {
+        Err(NotAnEventType)
+    }
+
+    fn serialize_seq(
+        self,
+        _len: Option<usize>,
+    ) -> Result<Self::SerializeSeq, NotAnEventType> {
+        Err(NotAnEventType)
+    }
+
+    fn serialize_tuple(
+        self,
+        _len: usize,
+    ) -> Result<Self::SerializeTuple, NotAnEventType> {
+        Err(NotAnEventType)
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct, NotAnEventType> {
+        Err(NotAnEventType)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, NotAnEventType> {
+        Err(NotAnEventType)
+    }
+
+    fn serialize_map(
+        self,
+        _len: Option<usize>,
+    ) -> Result<Self::SerializeMap, NotAnEventType> {
+        Err(NotAnEventType)
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStruct, NotAnEventType> {
+        Err(NotAnEventType)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, NotAnEventType> {
+        Err(NotAnEventType)
+    }
+}
@@ -0,0 +1,208 @@
+use log::trace;
+use serde_json::Value;
+use std::{
+    collections::HashMap,
+    fmt::{
+        Debug,
+        Formatter,
+        Result as fmtResult,
+    },
+};
+
+use crate::errors::Error;
+
+use super::{
+    i_event::IEvent,
+    i_event_upcaster::IEventUpcaster,
+    serialized_event::deserialize_event,
+};
+
+/// A registry of `IEventUpcaster`s keyed by event type and source
+/// version. Upcasting a payload applies every matching upcaster in
+/// turn until no upcaster is registered for the reached version,
+/// which is the current schema of the event.
+///
+/// # Examples
+/// ```rust
+/// use serde_json::json;
+///
+/// use cqrs_es2::{
+///     example_impl::{
+///         CustomerEvent,
+///         NameAdded,
+///     },
+///     EventUpcaster,
+///     EventUpcasters,
+/// };
+///
+/// let upcasters = EventUpcasters::default()
+///     .with_upcaster(EventUpcaster::new("NameAdded", 1, |x| {
+///         Ok(json!({ "first": x["name"], "last": "" }))
+///     }))
+///     .with_upcaster(EventUpcaster::new("NameAdded", 2, |x| {
+///         Ok(json!({ "changed_name": x["first"] }))
+///     }));
+///
+/// let event: CustomerEvent = upcasters
+///     .deserialize("NameAdded", 1, json!({ "name": "John Doe" }))
+///     .unwrap();
+///
+/// assert_eq!(
+///     event,
+///     CustomerEvent::NameAdded(NameAdded {
+///         changed_name: "John Doe".to_string(),
+///     })
+/// );
+/// ```
+#[derive(Default)]
+pub struct EventUpcasters {
+    upcasters: HashMap<(String, u32), Box<dyn IEventUpcaster>>,
+}
+
+impl EventUpcasters {
+    /// Registers an upcaster replacing any previous one for the same
+    /// event type and source version
+    pub fn register(
+        &mut self,
+        upcaster: Box<dyn IEventUpcaster>,
+    ) {
+        let key = (
+            upcaster.event_type().to_string(),
+            upcaster.source_version(),
+        );
+
+        self.upcasters.insert(key, upcaster);
+    }
+
+    /// Builder variant of `register`
+    #[must_use]
+    pub fn with_upcaster<U: IEventUpcaster + 'static>(
+        mut self,
+        upcaster: U,
+    ) -> Self {
+        self.register(Box::new(upcaster));
+        self
+    }
+
+    /// Migrates a payload of `event_type` from `event_version` to the
+    /// latest known version and returns that version along with the
+    /// migrated payload.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` when one of the upcasters fails or when the
+    /// upcasters do not move the version forward.
+    pub fn upcast(
+        &self,
+        event_type: &str,
+        event_version: u32,
+        payload: Value,
+    ) -> Result<(u32, Value), Error> {
+        let mut version = event_version;
+        let mut payload = payload;
+
+        while let Some(upcaster) = self
+            .upcasters
+            .get(&(event_type.to_string(), version))
+        {
+            let target_version = upcaster.target_version();
+
+            if target_version <= version {
+                return Err(Error::TechnicalError(format!(
+                    "upcaster of event type '{event_type}' from \
+                     version {version} does not move the version \
+                     forward"
+                )));
+            }
+
+            trace!(
+                "upcasting event type '{event_type}' from version \
+                 {version} to {target_version}"
+            );
+
+            payload = upcaster.upcast(payload)?;
+            version = target_version;
+        }
+
+        Ok((version, payload))
+    }
+
+    /// Upcasts a payload and deserializes it into the event, using
+    /// the event type to find the variant of an externally tagged
+    /// event enum.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` when upcasting fails or when the upcasted
+    /// payload does not deserialize into `E`.
+    pub fn deserialize<E: IEvent>(
+        &self,
+        event_type: &str,
+        event_version: u32,
+        payload: Value,
+    ) -> Result<E, Error> {
+        let (_, payload) =
+            self.upcast(event_type, event_version, payload)?;
+
+        deserialize_event(event_type, payload)
+    }
+}
+
+impl Debug for EventUpcasters {
+    fn fmt(
+        &self,
+        f: &mut Formatter<'_>,
+    ) -> fmtResult {
+        let mut keys: Vec<_> = self.upcasters.keys().collect();
+        keys.sort();
+
+        f.debug_struct("EventUpcasters")
+            .field("upcasters", &keys)
+            .finish()
+    }
+}
+
+type UpcastFn = dyn Fn(Value) -> Result<Value, Error> + Send + Sync;
+
+/// A closure based `IEventUpcaster` migrating an event type from
+/// `source_version` to the following version.
+pub struct EventUpcaster {
+    event_type: String,
+    source_version: u32,
+    upcast: Box<UpcastFn>,
+}
+
+impl EventUpcaster {
+    /// Constructor
+    pub fn new<F>(
+        event_type: &str,
+        source_version: u32,
+        upcast: F,
+    ) -> Self
+    where
+        F: Fn(Value) -> Result<Value, Error> + Send + Sync + 'static,
+    {
+        Self {
+            event_type: event_type.to_string(),
+            source_version,
+            upcast: Box::new(upcast),
+        }
+    }
+}
+
+impl IEventUpcaster for EventUpcaster {
+    fn event_type(&self) -> &str {
+        &self.event_type
+    }
+
+    fn source_version(&self) -> u32 {
+        self.source_version
+    }
+
+    fn upcast(
+        &self,
+        payload: Value,
+    ) -> Result<Value, Error> {
+        (self.upcast)(payload)
+    }
+}
@@ -4,6 +4,11 @@ use serde::{
 };
 use std::fmt::Debug;
 
+use super::{
+    event_type_serializer::event_type_of,
+    serialized_event::DEFAULT_EVENT_VERSION,
+};
+
 /// An `IEvent` represents any business change in the state of an
 /// `Aggregate`. `IEvent`s are immutable and with
 /// [event sourcing](https://martinfowler.com/eaaDev/EventSourcing.html)
@@ -20,8 +25,21 @@ use std::fmt::Debug;
 /// same name as the element, and elements that do not require
 /// additional information use an empty payload.
 ///
-/// Though the `IEvent` trait only has a single function, the
-/// events must also derive a number of standard traits.
+/// Every event has a stable `event_type` and `event_version`, which
+/// are the canonical keys for persistence, upcasting, routing and
+/// filtering. By default, the event type is the serialized name of
+/// the enum variant, honoring `#[serde(rename = "...")]`, and the
+/// event version is `DEFAULT_EVENT_VERSION`. Both can be overridden,
+/// e.g., to bump the version of a variant after changing its schema.
+/// The default event type supports structs as well as externally
+/// tagged (the serde default), internally tagged
+/// (`#[serde(tag = "...")]`) and adjacently tagged
+/// (`#[serde(tag = "...", content = "...")]`) enums, is resolved once
+/// per variant and falls back to the name of the type otherwise,
+/// e.g., for untagged enums. `#[derive(Event)]` generates it at
+/// compile time instead.
+///
+/// The events must also derive a number of standard traits.
 /// - `Clone` - events may be cloned throughout the framework,
 ///   particularly when applied to queries
 /// - `Serialize` and `Deserialize` - required for persistence
@@ -71,9 +89,36 @@ use std::fmt::Debug;
 ///     new_email: String,
 /// }
 ///
-/// impl IEvent for CustomerEvent {};
+/// impl IEvent for CustomerEvent {
+///     fn event_version(&self) -> u32 {
+///         match self {
+///             CustomerEvent::NameAdded(_) => 2,
+///             CustomerEvent::EmailUpdated(_) => 1,
+///         }
+///     }
+/// }
+///
+/// let event = CustomerEvent::NameAdded(NameAdded {
+///     changed_name: "John Doe".to_string(),
+/// });
+///
+/// assert_eq!(event.event_type(), "NameAdded");
+/// assert_eq!(event.event_version(), 2);
 /// ```
 pub trait IEvent:
     Debug + PartialEq + Clone + Serialize + DeserializeOwned + Sync + Send
 {
+    /// The type of the event, i.e., the serialized name of the event
+    /// enum variant, or the name of the event struct. The name of the
+    /// event type is used for any other representation, e.g., an
+    /// untagged enum, which should override this method to tell its
+    /// variants apart.
+    fn event_type(&self) -> &'static str {
+        event_type_of(self)
+    }
+
+    /// The schema version of the event
+    fn event_version(&self) -> u32 {
+        DEFAULT_EVENT_VERSION
+    }
 }
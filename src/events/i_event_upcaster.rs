@@ -0,0 +1,68 @@
+use serde_json::Value;
+
+use crate::errors::Error;
+
+/// An `IEventUpcaster` migrates the serialized payload of a single
+/// event type from one version of its schema to the next one. It
+/// operates on the raw `serde_json::Value` so that persisted events
+/// written with an older schema can still be deserialized once the
+/// `IEvent` definition has evolved.
+///
+/// Upcasters are registered in an `EventUpcasters` registry which
+/// chains them, e.g. `v1 -> v2 -> v3`.
+///
+/// # Examples
+/// ```rust
+/// use serde_json::{
+///     json,
+///     Value,
+/// };
+///
+/// use cqrs_es2::{
+///     Error,
+///     IEventUpcaster,
+/// };
+///
+/// struct NameAddedV1;
+///
+/// impl IEventUpcaster for NameAddedV1 {
+///     fn event_type(&self) -> &str {
+///         "NameAdded"
+///     }
+///
+///     fn source_version(&self) -> u32 {
+///         1
+///     }
+///
+///     fn upcast(
+///         &self,
+///         payload: Value,
+///     ) -> Result<Value, Error> {
+///         Ok(json!({ "changed_name": payload["name"] }))
+///     }
+/// }
+/// ```
+pub trait IEventUpcaster: Send + Sync {
+    /// The event type whose payload this upcaster migrates
+    fn event_type(&self) -> &str;
+
+    /// The schema version of the payloads accepted by this upcaster
+    fn source_version(&self) -> u32;
+
+    /// The schema version of the payloads produced by this upcaster,
+    /// by default the version following `source_version`
+    fn target_version(&self) -> u32 {
+        self.source_version() + 1
+    }
+
+    /// Migrate a payload from `source_version` to `target_version`
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` when the payload does not match the
+    /// expected source schema.
+    fn upcast(
+        &self,
+        payload: Value,
+    ) -> Result<Value, Error>;
+}
@@ -3,11 +3,31 @@
 //! A central location for event interfaces
 
 pub use event_context::EventContext;
+pub use event_upcasters::{
+    EventUpcaster,
+    EventUpcasters,
+};
 pub use i_event::IEvent;
 pub use i_event_consumer::IEventConsumer;
 pub use i_event_handler::IEventHandler;
+pub use i_event_upcaster::IEventUpcaster;
+pub use positioned_event::PositionedEvent;
+pub use serialized_event::{
+    SerializedEvent,
+    DEFAULT_EVENT_VERSION,
+};
 
 mod event_context;
+mod event_representation;
+mod event_type_cache;
+mod event_type_serializer;
+mod event_upcasters;
 mod i_event;
 mod i_event_consumer;
 mod i_event_handler;
+mod i_event_upcaster;
+mod positioned_event;
+mod serialized_event;
+
+#[cfg(test)]
+mod test;
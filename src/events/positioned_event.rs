@@ -0,0 +1,30 @@
+use crate::commands::ICommand;
+
+use super::{
+    event_context::EventContext,
+    i_event::IEvent,
+};
+
+/// A persisted event along with its global position in the event
+/// store. Positions start at `1` and increase monotonically in the
+/// order the events were committed, across all aggregate instances
+/// of the store, so that consumers can resume reading the stream
+/// right after the last position they have seen.
+#[derive(Debug, PartialEq, Clone)]
+pub struct PositionedEvent<C: ICommand, E: IEvent> {
+    /// The global position of the event in the event store.
+    pub position: i64,
+
+    /// The persisted event.
+    pub event: EventContext<C, E>,
+}
+
+impl<C: ICommand, E: IEvent> PositionedEvent<C, E> {
+    /// Constructor
+    pub fn new(
+        position: i64,
+        event: EventContext<C, E>,
+    ) -> Self {
+        Self { position, event }
+    }
+}
@@ -0,0 +1,359 @@
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use serde_json::{
+    Map,
+    Value,
+};
+use std::{
+    collections::HashMap,
+    convert::TryFrom,
+};
+
+use crate::{
+    commands::ICommand,
+    errors::Error,
+};
+
+use super::{
+    event_context::EventContext,
+    event_representation::EventRepresentation,
+    event_type_cache::cached_variant,
+    event_upcasters::EventUpcasters,
+    i_event::IEvent,
+};
+
+/// The schema version assigned to events that do not carry one.
+pub const DEFAULT_EVENT_VERSION: u32 = 1;
+
+/// `SerializedEvent` is the standard persistence envelope of an
+/// `EventContext`. The event type and version are kept next to the
+/// payload instead of being the root node of the payload, which
+/// allows stores to index and filter events without understanding
+/// the payload.
+///
+/// The envelope serializes as
+///
+/// ```json
+/// {
+///   "event_type": "NameAdded",
+///   "event_version": 1,
+///   "aggregate_type": "customer",
+///   "aggregate_id": "customer-1",
+///   "sequence": 1,
+///   "payload": { "changed_name": "John Doe" },
+///   "metadata": {}
+/// }
+/// ```
+///
+/// The legacy layout, where `event_type` and `event_version` are
+/// missing and the payload is keyed by the event type, e.g.
+/// `"payload": { "NameAdded": { "changed_name": "John Doe" } }`, is
+/// still accepted when deserializing so that existing data can be
+/// migrated.
+///
+/// # Examples
+/// ```rust
+/// use cqrs_es2::{
+///     example_impl::{
+///         CustomerCommand,
+///         CustomerEvent,
+///         NameAdded,
+///     },
+///     EventContext,
+///     SerializedEvent,
+/// };
+///
+/// let context = EventContext::<CustomerCommand, CustomerEvent>::new(
+///     "customer-1".to_string(),
+///     1,
+///     CustomerEvent::NameAdded(NameAdded {
+///         changed_name: "John Doe".to_string(),
+///     }),
+///     Default::default(),
+/// );
+///
+/// let serialized =
+///     SerializedEvent::from_context("customer", &context).unwrap();
+///
+/// assert_eq!(serialized.event_type, "NameAdded");
+/// assert_eq!(
+///     serialized.payload["changed_name"],
+///     "John Doe"
+/// );
+///
+/// assert_eq!(
+///     serialized.into_context().unwrap(),
+///     context
+/// );
+/// ```
+#[derive(
+    Debug,
+    PartialEq,
+    Clone,
+    Serialize,
+    Deserialize
+)]
+#[serde(try_from = "RawSerializedEvent")]
+pub struct SerializedEvent {
+    /// The type of the event, i.e., `IEvent::event_type`.
+    pub event_type: String,
+
+    /// The schema version of the payload, i.e.,
+    /// `IEvent::event_version` at the time it was serialized.
+    pub event_version: u32,
+
+    /// The type of the aggregate instance.
+    pub aggregate_type: String,
+
+    /// The id of the aggregate instance.
+    pub aggregate_id: String,
+
+    /// The sequence number for an aggregate instance.
+    pub sequence: i64,
+
+    /// The event payload, without the event type root node of
+    /// externally tagged enums.
+    pub payload: Value,
+
+    /// Additional metadata for use in auditing, logging or debugging
+    /// purposes.
+    pub metadata: HashMap<String, String>,
+}
+
+impl SerializedEvent {
+    /// Serializes an `EventContext` of an aggregate of type
+    /// `aggregate_type`, keyed by the `event_type` of its event.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` when the event payload can not be
+    /// serialized.
+    pub fn from_context<C: ICommand, E: IEvent>(
+        aggregate_type: &str,
+        context: &EventContext<C, E>,
+    ) -> Result<Self, Error> {
+        let value =
+            serde_json::to_value(&context.payload).map_err(|e| {
+                Error::Serialization {
+                    message: format!(
+                        "unable to serialize event of aggregate \
+                         '{}': {}",
+                        context.aggregate_id, e
+                    ),
+                    source: Some(e.into()),
+                }
+            })?;
+
+        let payload = match EventRepresentation::cached::<E>() {
+            EventRepresentation::External(_) => {
+                split_event_type(value)?.1
+            },
+            _ => value,
+        };
+
+        Ok(Self {
+            event_type: context.payload.event_type().to_string(),
+            event_version: context.payload.event_version(),
+            aggregate_type: aggregate_type.to_string(),
+            aggregate_id: context.aggregate_id.clone(),
+            sequence: context.sequence,
+            payload,
+            metadata: context.metadata.clone(),
+        })
+    }
+
+    /// Builds an envelope from the legacy layout where the payload
+    /// is keyed by the event type.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` when the payload does not have a single
+    /// root node.
+    pub fn from_legacy(
+        aggregate_type: &str,
+        aggregate_id: &str,
+        sequence: i64,
+        payload: Value,
+        metadata: HashMap<String, String>,
+    ) -> Result<Self, Error> {
+        let (event_type, payload) = split_event_type(payload)?;
+
+        Ok(Self {
+            event_type,
+            event_version: DEFAULT_EVENT_VERSION,
+            aggregate_type: aggregate_type.to_string(),
+            aggregate_id: aggregate_id.to_string(),
+            sequence,
+            payload,
+            metadata,
+        })
+    }
+
+    /// Deserializes the envelope back into an `EventContext`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` when the payload does not deserialize into
+    /// `E`.
+    pub fn into_context<C: ICommand, E: IEvent>(
+        self
+    ) -> Result<EventContext<C, E>, Error> {
+        self.into_context_with(&EventUpcasters::default())
+    }
+
+    /// Upcasts the payload to the latest schema version and
+    /// deserializes the envelope back into an `EventContext`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` when upcasting fails or when the payload
+    /// does not deserialize into `E`.
+    pub fn into_context_with<C: ICommand, E: IEvent>(
+        self,
+        upcasters: &EventUpcasters,
+    ) -> Result<EventContext<C, E>, Error> {
+        let payload = upcasters.deserialize(
+            &self.event_type,
+            self.event_version,
+            self.payload,
+        )?;
+
+        Ok(EventContext::new(
+            self.aggregate_id,
+            self.sequence,
+            payload,
+            self.metadata,
+        ))
+    }
+}
+
+/// Accepts both the standard and the legacy layout
+#[derive(Deserialize)]
+struct RawSerializedEvent {
+    event_type: Option<String>,
+    event_version: Option<u32>,
+    aggregate_type: String,
+    aggregate_id: String,
+    sequence: i64,
+    payload: Value,
+    #[serde(default)]
+    metadata: HashMap<String, String>,
+}
+
+impl TryFrom<RawSerializedEvent> for SerializedEvent {
+    type Error = Error;
+
+    fn try_from(x: RawSerializedEvent) -> Result<Self, Self::Error> {
+        let (event_type, payload) = match x.event_type {
+            Some(event_type) => (event_type, x.payload),
+            None => split_event_type(x.payload)?,
+        };
+
+        Ok(Self {
+            event_type,
+            event_version: x
+                .event_version
+                .unwrap_or(DEFAULT_EVENT_VERSION),
+            aggregate_type: x.aggregate_type,
+            aggregate_id: x.aggregate_id,
+            sequence: x.sequence,
+            payload,
+            metadata: x.metadata,
+        })
+    }
+}
+
+/// Splits an externally tagged event into its event type and payload.
+/// Unit variants serialize as a plain string and get a `null`
+/// payload.
+pub(crate) fn split_event_type(
+    value: Value
+) -> Result<(String, Value), Error> {
+    match value {
+        Value::String(event_type) => Ok((event_type, Value::Null)),
+        Value::Object(root) if root.len() == 1 => {
+            Ok(root.into_iter().next().unwrap())
+        },
+        _ => {
+            Err(Error::serialization(
+                "expected an event keyed by its event type",
+                None,
+            ))
+        },
+    }
+}
+
+/// Inverse of `split_event_type`
+pub(crate) fn join_event_type(
+    event_type: &str,
+    payload: Value,
+) -> Value {
+    match payload {
+        Value::Null => Value::String(event_type.to_string()),
+        payload => {
+            let mut root = Map::new();
+            root.insert(event_type.to_string(), payload);
+            Value::Object(root)
+        },
+    }
+}
+
+/// Deserializes the payload of an event keyed by `event_type`. The
+/// variant of an externally tagged enum is the one named after the
+/// event type or, when `IEvent::event_type` is overridden, the one
+/// whose event type it is, which is looked up once per event type.
+/// Other representations keep their tag in the payload.
+pub(crate) fn deserialize_event<E: IEvent>(
+    event_type: &str,
+    payload: Value,
+) -> Result<E, Error> {
+    let result = match EventRepresentation::cached::<E>() {
+        EventRepresentation::External(variants)
+            if !variants.contains(&event_type) =>
+        {
+            let variant = cached_variant::<E, _>(event_type, || {
+                variants
+                    .iter()
+                    .copied()
+                    .find(|variant| {
+                        serde_json::from_value::<E>(join_event_type(
+                            variant,
+                            payload.clone(),
+                        ))
+                        .is_ok_and(|x| x.event_type() == event_type)
+                    })
+            });
+
+            let Some(variant) = variant
+            else {
+                return Err(Error::serialization(
+                    &format!(
+                        "unable to deserialize event type \
+                         '{event_type}': no matching variant"
+                    ),
+                    None,
+                ));
+            };
+
+            serde_json::from_value(join_event_type(variant, payload))
+        },
+        EventRepresentation::External(_) => {
+            serde_json::from_value(join_event_type(
+                event_type, payload,
+            ))
+        },
+        _ => serde_json::from_value(payload),
+    };
+
+    result.map_err(|e| {
+        Error::Serialization {
+            message: format!(
+                "unable to deserialize event type '{event_type}': \
+                 {e}"
+            ),
+            source: Some(e.into()),
+        }
+    })
+}
@@ -0,0 +1,658 @@
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use serde_json::{
+    json,
+    Value,
+};
+use std::collections::HashMap;
+
+use crate::{
+    example_impl::*,
+    Error,
+    EventContext,
+    IEvent,
+};
+
+use super::{
+    event_upcasters::{
+        EventUpcaster,
+        EventUpcasters,
+    },
+    i_event_upcaster::IEventUpcaster,
+    serialized_event::{
+        SerializedEvent,
+        DEFAULT_EVENT_VERSION,
+    },
+};
+
+/// Renames a field of the payload
+struct FieldRenamer {
+    event_type: String,
+    source_version: u32,
+    target_version: u32,
+    from: &'static str,
+    to: &'static str,
+}
+
+impl FieldRenamer {
+    fn new(
+        event_type: &str,
+        source_version: u32,
+        from: &'static str,
+        to: &'static str,
+    ) -> Self {
+        Self {
+            event_type: event_type.to_string(),
+            source_version,
+            target_version: source_version + 1,
+            from,
+            to,
+        }
+    }
+}
+
+impl IEventUpcaster for FieldRenamer {
+    fn event_type(&self) -> &str {
+        &self.event_type
+    }
+
+    fn source_version(&self) -> u32 {
+        self.source_version
+    }
+
+    fn target_version(&self) -> u32 {
+        self.target_version
+    }
+
+    fn upcast(
+        &self,
+        payload: Value,
+    ) -> Result<Value, Error> {
+        match payload.get(self.from) {
+            None => {
+                Err(Error::TechnicalError(format!(
+                    "missing field '{}'",
+                    self.from
+                )))
+            },
+            Some(x) => Ok(json!({ self.to: x })),
+        }
+    }
+}
+
+fn upcasters() -> EventUpcasters {
+    EventUpcasters::default()
+        .with_upcaster(FieldRenamer::new(
+            "NameAdded",
+            1,
+            "name",
+            "full_name",
+        ))
+        .with_upcaster(EventUpcaster::new(
+            "NameAdded",
+            2,
+            |x| Ok(json!({ "changed_name": x["full_name"] })),
+        ))
+}
+
+#[test]
+fn test_upcast_chain() {
+    let upcasters = upcasters();
+
+    assert_eq!(
+        upcasters
+            .upcast(
+                "NameAdded",
+                1,
+                json!({ "name": "John Doe" })
+            )
+            .unwrap(),
+        (3, json!({ "changed_name": "John Doe" }))
+    );
+
+    assert_eq!(
+        upcasters
+            .upcast(
+                "NameAdded",
+                2,
+                json!({ "full_name": "John Doe" })
+            )
+            .unwrap(),
+        (3, json!({ "changed_name": "John Doe" }))
+    );
+
+    // current versions and unknown event types are left untouched
+    assert_eq!(
+        upcasters
+            .upcast(
+                "NameAdded",
+                3,
+                json!({ "changed_name": "John Doe" })
+            )
+            .unwrap(),
+        (3, json!({ "changed_name": "John Doe" }))
+    );
+
+    assert_eq!(
+        upcasters
+            .upcast(
+                "EmailUpdated",
+                1,
+                json!({ "new_email": "j@d.com" })
+            )
+            .unwrap(),
+        (1, json!({ "new_email": "j@d.com" }))
+    );
+
+    assert!(upcasters
+        .upcast(
+            "NameAdded",
+            1,
+            json!({ "first": "John" })
+        )
+        .is_err());
+}
+
+#[test]
+fn test_upcast_and_deserialize() {
+    let upcasters = upcasters();
+
+    let event: CustomerEvent = upcasters
+        .deserialize(
+            "NameAdded",
+            1,
+            json!({ "name": "John Doe" }),
+        )
+        .unwrap();
+
+    assert_eq!(
+        event,
+        CustomerEvent::NameAdded(NameAdded {
+            changed_name: "John Doe".to_string(),
+        })
+    );
+
+    let event: CustomerEvent = upcasters
+        .deserialize(
+            "EmailUpdated",
+            1,
+            json!({ "new_email": "j@d.com" }),
+        )
+        .unwrap();
+
+    assert_eq!(
+        event,
+        CustomerEvent::EmailUpdated(EmailUpdated {
+            new_email: "j@d.com".to_string(),
+        })
+    );
+
+    // without the upcasters the old schema no longer deserializes
+    assert!(EventUpcasters::default()
+        .deserialize::<CustomerEvent>(
+            "NameAdded",
+            1,
+            json!({ "name": "John Doe" })
+        )
+        .is_err());
+}
+
+#[test]
+fn test_upcaster_not_moving_forward() {
+    let mut upcaster =
+        FieldRenamer::new("NameAdded", 1, "name", "full_name");
+    upcaster.target_version = 1;
+
+    let mut upcasters = EventUpcasters::default();
+    upcasters.register(Box::new(upcaster));
+
+    assert!(upcasters
+        .upcast(
+            "NameAdded",
+            1,
+            json!({ "name": "John Doe" })
+        )
+        .is_err());
+}
+
+fn name_added_context() -> EventContext<CustomerCommand, CustomerEvent>
+{
+    let mut metadata = HashMap::new();
+    metadata.insert("user".to_string(), "tester".to_string());
+
+    EventContext::new(
+        "test_id_A".to_string(),
+        3,
+        CustomerEvent::NameAdded(NameAdded {
+            changed_name: "John Doe".to_string(),
+        }),
+        metadata,
+    )
+}
+
+#[test]
+fn test_serialized_event_layout() {
+    let serialized = SerializedEvent::from_context(
+        "customer",
+        &name_added_context(),
+    )
+    .unwrap();
+
+    assert_eq!(
+        serde_json::to_value(&serialized).unwrap(),
+        json!({
+            "event_type": "NameAdded",
+            "event_version": 1,
+            "aggregate_type": "customer",
+            "aggregate_id": "test_id_A",
+            "sequence": 3,
+            "payload": { "changed_name": "John Doe" },
+            "metadata": { "user": "tester" },
+        })
+    );
+}
+
+#[test]
+fn test_serialized_event_round_trip() {
+    let context = name_added_context();
+
+    let serialized =
+        SerializedEvent::from_context("customer", &context).unwrap();
+
+    let json = serde_json::to_string(&serialized).unwrap();
+
+    let deserialized: SerializedEvent =
+        serde_json::from_str(&json).unwrap();
+
+    assert_eq!(deserialized, serialized);
+    assert_eq!(
+        deserialized.into_context().unwrap(),
+        context
+    );
+}
+
+#[test]
+fn test_serialized_event_legacy_layout() {
+    let context = name_added_context();
+
+    let deserialized: SerializedEvent = serde_json::from_value(json!({
+        "aggregate_type": "customer",
+        "aggregate_id": "test_id_A",
+        "sequence": 3,
+        "payload": { "NameAdded": { "changed_name": "John Doe" } },
+        "metadata": { "user": "tester" },
+    }))
+    .unwrap();
+
+    assert_eq!(
+        deserialized,
+        SerializedEvent::from_context("customer", &context).unwrap()
+    );
+
+    assert_eq!(
+        SerializedEvent::from_legacy(
+            "customer",
+            "test_id_A",
+            3,
+            json!({ "NameAdded": { "changed_name": "John Doe" } }),
+            context.metadata.clone(),
+        )
+        .unwrap(),
+        deserialized
+    );
+
+    assert_eq!(
+        deserialized.into_context().unwrap(),
+        context
+    );
+
+    // a legacy payload without a single root node is rejected
+    assert!(
+        serde_json::from_value::<SerializedEvent>(json!({
+            "aggregate_type": "customer",
+            "aggregate_id": "test_id_A",
+            "sequence": 3,
+            "payload": [],
+        }))
+        .is_err()
+    );
+}
+
+#[test]
+fn test_serialized_event_upcasting() {
+    let serialized: SerializedEvent = serde_json::from_value(json!({
+        "aggregate_type": "customer",
+        "aggregate_id": "test_id_A",
+        "sequence": 3,
+        "payload": { "NameAdded": { "name": "John Doe" } },
+    }))
+    .unwrap();
+
+    assert!(serialized
+        .clone()
+        .into_context::<CustomerCommand, CustomerEvent>()
+        .is_err());
+
+    let context: EventContext<CustomerCommand, CustomerEvent> =
+        serialized
+            .into_context_with(&upcasters())
+            .unwrap();
+
+    assert_eq!(
+        context.payload,
+        CustomerEvent::NameAdded(NameAdded {
+            changed_name: "John Doe".to_string(),
+        })
+    );
+    assert_eq!(context.metadata, HashMap::new());
+}
+
+#[derive(
+    Debug,
+    PartialEq,
+    Clone,
+    Serialize,
+    Deserialize
+)]
+enum ShapeEvent {
+    Unit,
+    Newtype(u32),
+    Tuple(u32, u32),
+    Struct {
+        size: u32,
+    },
+    #[serde(rename = "renamed")]
+    Renamed,
+}
+
+impl IEvent for ShapeEvent {}
+
+#[derive(
+    Debug,
+    PartialEq,
+    Clone,
+    Serialize,
+    Deserialize
+)]
+struct StructEvent {
+    size: u32,
+}
+
+impl IEvent for StructEvent {}
+
+#[test]
+fn test_default_event_type() {
+    let result: Vec<_> = [
+        ShapeEvent::Unit,
+        ShapeEvent::Newtype(1),
+        ShapeEvent::Tuple(1, 2),
+        ShapeEvent::Struct { size: 1 },
+        ShapeEvent::Renamed,
+    ]
+    .iter()
+    .map(|x| (x.event_type(), x.event_version()))
+    .collect();
+
+    assert_eq!(
+        result,
+        vec![
+            ("Unit", DEFAULT_EVENT_VERSION),
+            ("Newtype", DEFAULT_EVENT_VERSION),
+            ("Tuple", DEFAULT_EVENT_VERSION),
+            ("Struct", DEFAULT_EVENT_VERSION),
+            ("renamed", DEFAULT_EVENT_VERSION),
+        ]
+    );
+
+    assert_eq!(
+        StructEvent { size: 1 }.event_type(),
+        "StructEvent"
+    );
+
+    assert_eq!(
+        CustomerEvent::NameAdded(NameAdded {
+            changed_name: "John Doe".to_string(),
+        })
+        .event_type(),
+        "NameAdded"
+    );
+}
+
+#[derive(
+    Debug,
+    PartialEq,
+    Clone,
+    Serialize,
+    Deserialize
+)]
+enum VersionedEvent {
+    Renamed { full_name: String },
+}
+
+impl IEvent for VersionedEvent {
+    fn event_version(&self) -> u32 {
+        2
+    }
+}
+
+#[derive(
+    Debug,
+    PartialEq,
+    Clone,
+    Serialize,
+    Deserialize
+)]
+enum MislabeledEvent {
+    Renamed,
+}
+
+impl IEvent for MislabeledEvent {
+    fn event_type(&self) -> &'static str {
+        "Other"
+    }
+}
+
+#[test]
+fn test_serialized_event_type_and_version() {
+    let context =
+        EventContext::<CustomerCommand, VersionedEvent>::new(
+            "test_id_A".to_string(),
+            1,
+            VersionedEvent::Renamed {
+                full_name: "John Doe".to_string(),
+            },
+            HashMap::new(),
+        );
+
+    let serialized =
+        SerializedEvent::from_context("customer", &context).unwrap();
+
+    assert_eq!(serialized.event_type, "Renamed");
+    assert_eq!(serialized.event_version, 2);
+
+    let context =
+        EventContext::<CustomerCommand, MislabeledEvent>::new(
+            "test_id_A".to_string(),
+            1,
+            MislabeledEvent::Renamed,
+            HashMap::new(),
+        );
+
+    let serialized =
+        SerializedEvent::from_context("customer", &context).unwrap();
+
+    assert_eq!(serialized.event_type, "Other");
+    assert_eq!(serialized.payload, Value::Null);
+
+    assert_eq!(
+        serialized.into_context().unwrap(),
+        context
+    );
+}
+
+#[derive(
+    Debug,
+    PartialEq,
+    Clone,
+    Serialize,
+    Deserialize
+)]
+#[serde(tag = "type")]
+enum InternallyTaggedEvent {
+    Unit,
+    Struct {
+        size: u32,
+    },
+    Newtype(StructEvent),
+    #[serde(rename = "renamed")]
+    Renamed {
+        name: String,
+    },
+}
+
+impl IEvent for InternallyTaggedEvent {}
+
+#[derive(
+    Debug,
+    PartialEq,
+    Clone,
+    Serialize,
+    Deserialize
+)]
+#[serde(tag = "type", content = "data")]
+enum AdjacentlyTaggedEvent {
+    Unit,
+    Newtype(u32),
+    Tuple(u32, u32),
+    #[serde(rename = "renamed")]
+    Struct {
+        size: u32,
+    },
+}
+
+impl IEvent for AdjacentlyTaggedEvent {}
+
+#[derive(
+    Debug,
+    PartialEq,
+    Clone,
+    Serialize,
+    Deserialize
+)]
+#[serde(untagged)]
+enum UntaggedEvent {
+    Struct { size: u32 },
+}
+
+impl IEvent for UntaggedEvent {}
+
+fn round_trip<E: IEvent>(event: E) -> SerializedEvent {
+    let context = EventContext::<CustomerCommand, E>::new(
+        "test_id_A".to_string(),
+        1,
+        event,
+        HashMap::new(),
+    );
+
+    let serialized =
+        SerializedEvent::from_context("customer", &context).unwrap();
+
+    assert_eq!(
+        serialized
+            .clone()
+            .into_context()
+            .unwrap(),
+        context
+    );
+
+    serialized
+}
+
+#[test]
+fn test_internally_tagged_event_type() {
+    let events = vec![
+        InternallyTaggedEvent::Unit,
+        InternallyTaggedEvent::Struct { size: 1 },
+        InternallyTaggedEvent::Newtype(StructEvent { size: 1 }),
+        InternallyTaggedEvent::Renamed {
+            name: "John Doe".to_string(),
+        },
+    ];
+
+    let result: Vec<_> = events
+        .iter()
+        .map(IEvent::event_type)
+        .collect();
+
+    assert_eq!(
+        result,
+        vec!["Unit", "Struct", "Newtype", "renamed"]
+    );
+
+    let serialized = round_trip(events[1].clone());
+
+    assert_eq!(serialized.event_type, "Struct");
+    assert_eq!(
+        serialized.payload,
+        json!({ "type": "Struct", "size": 1 })
+    );
+
+    for event in events {
+        round_trip(event);
+    }
+}
+
+#[test]
+fn test_adjacently_tagged_event_type() {
+    let events = vec![
+        AdjacentlyTaggedEvent::Unit,
+        AdjacentlyTaggedEvent::Newtype(1),
+        AdjacentlyTaggedEvent::Tuple(1, 2),
+        AdjacentlyTaggedEvent::Struct { size: 1 },
+    ];
+
+    let result: Vec<_> = events
+        .iter()
+        .map(IEvent::event_type)
+        .collect();
+
+    assert_eq!(
+        result,
+        vec!["Unit", "Newtype", "Tuple", "renamed"]
+    );
+
+    let serialized = round_trip(events[3].clone());
+
+    assert_eq!(serialized.event_type, "renamed");
+    assert_eq!(
+        serialized.payload,
+        json!({ "type": "renamed", "data": { "size": 1 } })
+    );
+
+    for event in events {
+        round_trip(event);
+    }
+}
+
+#[test]
+fn test_struct_event_round_trip() {
+    let serialized = round_trip(StructEvent { size: 1 });
+
+    assert_eq!(serialized.event_type, "StructEvent");
+    assert_eq!(serialized.payload, json!({ "size": 1 }));
+}
+
+#[test]
+fn test_untagged_event_type() {
+    // falls back to the name of the type
+    assert_eq!(
+        UntaggedEvent::Struct { size: 1 }.event_type(),
+        "UntaggedEvent"
+    );
+
+    let serialized = round_trip(UntaggedEvent::Struct { size: 1 });
+
+    assert_eq!(serialized.payload, json!({ "size": 1 }));
+}
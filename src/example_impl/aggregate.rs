@@ -12,8 +12,13 @@ use crate::{
 };
 
 use super::{
-    commands::*,
-    events::*,
+    commands::CustomerCommand,
+    events::{
+        AddressUpdated,
+        CustomerEvent,
+        EmailUpdated,
+        NameAdded,
+    },
 };
 
 #[derive(
@@ -104,7 +109,7 @@ impl IEventHandler<CustomerEvent> for Customer {
             },
             CustomerEvent::AddressUpdated(payload) => {
                 self.addresses
-                    .push(payload.new_address.clone())
+                    .push(payload.new_address.clone());
             },
         }
     }
@@ -1,6 +1,10 @@
 use std::fmt::Debug;
 
-use crate::ICommand;
+use crate::{
+    ICommand,
+    IValidateCommand,
+    ValidationErrors,
+};
 
 #[derive(Debug, PartialEq, Clone)]
 pub enum CustomerCommand {
@@ -24,4 +28,39 @@ pub struct AddAddress {
     pub new_address: String,
 }
 
-impl ICommand for CustomerCommand {}
+impl ICommand for CustomerCommand {
+    fn command_name(&self) -> &'static str {
+        match self {
+            CustomerCommand::AddCustomerName(_) => "AddCustomerName",
+            CustomerCommand::UpdateEmail(_) => "UpdateEmail",
+            CustomerCommand::AddAddress(_) => "AddAddress",
+        }
+    }
+}
+
+impl IValidateCommand for CustomerCommand {
+    fn validate(
+        &self,
+        errors: &mut ValidationErrors,
+    ) {
+        match self {
+            CustomerCommand::AddCustomerName(payload) => {
+                errors.require_length(
+                    "changed_name",
+                    &payload.changed_name,
+                    1,
+                    100,
+                );
+            },
+            CustomerCommand::UpdateEmail(payload) => {
+                errors.require_email("new_email", &payload.new_email);
+            },
+            CustomerCommand::AddAddress(payload) => {
+                errors.require_non_empty(
+                    "new_address",
+                    &payload.new_address,
+                );
+            },
+        }
+    }
+}
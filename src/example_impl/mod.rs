@@ -1,7 +1,9 @@
 //! # example_impl
 //!
 //! A full example of a CQRS implementation for a `Customer`
-//! aggregate. It serves the following purposes:
+//! aggregate, along with a `Notification` aggregate coordinated
+//! through the `EmailVerificationSaga`. It serves the following
+//! purposes:
 //!
 //! - Document a usage scenario for the CQRS pattern
 //! - Serve as shared resource for unit tests, doc tests, and
@@ -10,12 +12,16 @@
 pub use aggregate::*;
 pub use commands::*;
 pub use events::*;
+pub use notification::*;
 pub use queries::*;
+pub use saga::*;
 
 mod aggregate;
 mod commands;
 mod events;
+mod notification;
 mod queries;
+mod saga;
 
 #[cfg(test)]
 mod test;
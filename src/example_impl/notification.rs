@@ -0,0 +1,119 @@
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use std::fmt::Debug;
+
+use crate::{
+    Error,
+    IAggregate,
+    ICommand,
+    ICommandHandler,
+    IEvent,
+    IEventHandler,
+};
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum NotificationCommand {
+    SendEmail(SendEmail),
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct SendEmail {
+    pub to: String,
+    pub subject: String,
+}
+
+impl ICommand for NotificationCommand {
+    fn command_name(&self) -> &'static str {
+        match self {
+            NotificationCommand::SendEmail(_) => "SendEmail",
+        }
+    }
+}
+
+#[derive(
+    Debug,
+    PartialEq,
+    Clone,
+    Serialize,
+    Deserialize
+)]
+pub enum NotificationEvent {
+    EmailSent(EmailSent),
+}
+
+#[derive(
+    Debug,
+    PartialEq,
+    Clone,
+    Serialize,
+    Deserialize
+)]
+pub struct EmailSent {
+    pub to: String,
+    pub subject: String,
+}
+
+impl IEvent for NotificationEvent {}
+
+#[derive(
+    Debug,
+    PartialEq,
+    Default,
+    Clone,
+    Serialize,
+    Deserialize
+)]
+pub struct Notification {
+    pub sent: Vec<String>,
+}
+
+impl IAggregate<NotificationCommand, NotificationEvent>
+    for Notification
+{
+    fn aggregate_type() -> &'static str {
+        "notification"
+    }
+}
+
+impl ICommandHandler<NotificationCommand, NotificationEvent>
+    for Notification
+{
+    fn handle(
+        &self,
+        command: NotificationCommand,
+    ) -> Result<Vec<NotificationEvent>, Error> {
+        match command {
+            NotificationCommand::SendEmail(payload) => {
+                if payload.to.is_empty() {
+                    return Err(Error::new(
+                        "an email address is required",
+                    ));
+                }
+
+                let payload = EmailSent {
+                    to: payload.to,
+                    subject: payload.subject,
+                };
+
+                Ok(vec![NotificationEvent::EmailSent(
+                    payload,
+                )])
+            },
+        }
+    }
+}
+
+impl IEventHandler<NotificationEvent> for Notification {
+    fn apply(
+        &mut self,
+        event: &NotificationEvent,
+    ) {
+        match event {
+            NotificationEvent::EmailSent(payload) => {
+                self.sent.push(payload.subject.clone());
+            },
+        }
+    }
+}
@@ -0,0 +1,120 @@
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use std::{
+    fmt::Debug,
+    time::Duration,
+};
+
+use crate::{
+    Error,
+    EventContext,
+    ISaga,
+    SagaAction,
+};
+
+use super::{
+    commands::CustomerCommand,
+    events::CustomerEvent,
+    notification::{
+        NotificationCommand,
+        SendEmail,
+    },
+};
+
+pub const VERIFICATION_SUBJECT: &str = "Please verify your email";
+pub const REMINDER_SUBJECT: &str =
+    "Reminder: please verify your email";
+pub const REMINDER_TIMEOUT: &str = "verification_reminder";
+
+/// Sends a verification email whenever a customer updates their
+/// email, followed by a reminder a day later
+#[derive(
+    Debug,
+    PartialEq,
+    Default,
+    Clone,
+    Serialize,
+    Deserialize
+)]
+pub struct EmailVerificationSaga {
+    pub customer_id: String,
+    pub email: String,
+    pub failed: bool,
+}
+
+impl EmailVerificationSaga {
+    fn send_email(
+        &self,
+        subject: &str,
+    ) -> SagaAction<NotificationCommand> {
+        SagaAction::dispatch(
+            &self.customer_id,
+            NotificationCommand::SendEmail(SendEmail {
+                to: self.email.clone(),
+                subject: subject.to_string(),
+            }),
+        )
+    }
+}
+
+impl ISaga<CustomerCommand, CustomerEvent, NotificationCommand>
+    for EmailVerificationSaga
+{
+    fn saga_type() -> &'static str {
+        "email_verification_saga"
+    }
+
+    fn handle(
+        &mut self,
+        event: &EventContext<CustomerCommand, CustomerEvent>,
+    ) -> Result<Vec<SagaAction<NotificationCommand>>, Error> {
+        match &event.payload {
+            CustomerEvent::EmailUpdated(payload) => {
+                self.customer_id
+                    .clone_from(&event.aggregate_id);
+                self.email
+                    .clone_from(&payload.new_email);
+
+                Ok(vec![
+                    self.send_email(VERIFICATION_SUBJECT),
+                    SagaAction::schedule_timeout(
+                        REMINDER_TIMEOUT,
+                        Duration::from_secs(24 * 60 * 60),
+                    ),
+                ])
+            },
+            _ => Ok(Vec::new()),
+        }
+    }
+
+    fn on_timeout(
+        &mut self,
+        name: &str,
+    ) -> Result<Vec<SagaAction<NotificationCommand>>, Error> {
+        match name {
+            REMINDER_TIMEOUT => {
+                Ok(vec![
+                    self.send_email(REMINDER_SUBJECT),
+                    SagaAction::Complete,
+                ])
+            },
+            _ => Ok(Vec::new()),
+        }
+    }
+
+    fn compensate(
+        &mut self,
+        _aggregate_id: &str,
+        _command: &NotificationCommand,
+        _error: &Error,
+    ) -> Result<Vec<SagaAction<NotificationCommand>>, Error> {
+        self.failed = true;
+
+        Ok(vec![
+            SagaAction::cancel_timeout(REMINDER_TIMEOUT),
+            SagaAction::Complete,
+        ])
+    }
+}
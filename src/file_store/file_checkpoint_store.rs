@@ -0,0 +1,176 @@
+use log::{
+    debug,
+    trace,
+};
+use std::{
+    collections::HashMap,
+    fs,
+    path::{
+        Path,
+        PathBuf,
+    },
+    sync::{
+        Arc,
+        RwLock,
+    },
+};
+
+use crate::{
+    errors::Error,
+    stores::ICheckpointStore,
+};
+
+use super::{
+    file_event_store::io_error,
+    json_file::{
+        read_json_file,
+        write_json_file,
+    },
+};
+
+/// The name of the file holding the checkpoints
+const CHECKPOINTS_FILE: &str = "checkpoints.json";
+
+/// File checkpoint store keeping the position of every named
+/// subscriber in a `checkpoints.json` file, so that subscriptions
+/// resume where they stopped after a restart. Every save replaces
+/// the file atomically. Cloning the store yields a handle to the same
+/// underlying checkpoints.
+///
+/// # Examples
+/// ```rust
+/// use cqrs_es2::{
+///     FileCheckpointStore,
+///     ICheckpointStore,
+/// };
+///
+/// let directory = std::env::temp_dir().join(format!(
+///     "cqrs-es2-doc-{}",
+///     uuid::Uuid::new_v4()
+/// ));
+///
+/// let mut store = FileCheckpointStore::open(&directory).unwrap();
+///
+/// store.save("subscriber", 3).unwrap();
+///
+/// let mut store = FileCheckpointStore::open(&directory).unwrap();
+///
+/// assert_eq!(store.load("subscriber").unwrap(), 3);
+///
+/// std::fs::remove_dir_all(&directory).unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct FileCheckpointStore {
+    directory: PathBuf,
+    checkpoints: Arc<RwLock<HashMap<String, i64>>>,
+}
+
+impl FileCheckpointStore {
+    /// Opens the store in `directory`, creating the directory when
+    /// it does not exist, and reads the saved checkpoints.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` when the directory or the checkpoints can
+    /// not be read.
+    pub fn open<P: AsRef<Path>>(directory: P) -> Result<Self, Error> {
+        let directory = directory.as_ref().to_path_buf();
+
+        fs::create_dir_all(&directory).map_err(|e| {
+            io_error(
+                &format!(
+                    "unable to create the checkpoint store \
+                     directory '{}'",
+                    directory.display()
+                ),
+                e,
+            )
+        })?;
+
+        let checkpoints: HashMap<String, i64> = read_json_file(
+            &directory,
+            CHECKPOINTS_FILE,
+            "checkpoints",
+        )?;
+
+        debug!(
+            "opened checkpoint store '{}' with {} subscribers",
+            directory.display(),
+            checkpoints.len()
+        );
+
+        Ok(Self {
+            directory,
+            checkpoints: Arc::new(RwLock::new(checkpoints)),
+        })
+    }
+
+    /// The directory holding the checkpoints file
+    #[must_use]
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+}
+
+impl ICheckpointStore for FileCheckpointStore {
+    fn load(
+        &mut self,
+        subscriber: &str,
+    ) -> Result<i64, Error> {
+        let checkpoints = self.checkpoints.read().map_err(|e| {
+            Error::Store {
+                message: format!(
+                    "unable to read the checkpoint store: {e}"
+                ),
+                source: None,
+            }
+        })?;
+
+        let result = checkpoints
+            .get(subscriber)
+            .copied()
+            .unwrap_or_default();
+
+        trace!(
+            "loaded checkpoint {result} of subscriber '{subscriber}'"
+        );
+
+        Ok(result)
+    }
+
+    fn save(
+        &mut self,
+        subscriber: &str,
+        position: i64,
+    ) -> Result<(), Error> {
+        let mut checkpoints =
+            self.checkpoints.write().map_err(|e| {
+                Error::Store {
+                    message: format!(
+                        "unable to write to the checkpoint store: \
+                         {e}"
+                    ),
+                    source: None,
+                }
+            })?;
+
+        trace!(
+            "saving checkpoint {position} of subscriber \
+             '{subscriber}'"
+        );
+
+        let mut saved = checkpoints.clone();
+        saved.insert(subscriber.to_string(), position);
+
+        write_json_file(
+            &self.directory,
+            CHECKPOINTS_FILE,
+            "checkpoints",
+            &saved,
+        )?;
+
+        *checkpoints = saved;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,1056 @@
+use log::{
+    debug,
+    trace,
+    warn,
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use std::{
+    collections::{
+        hash_map::Entry,
+        HashMap,
+    },
+    convert::TryFrom,
+    fmt::Debug,
+    fs::{
+        self,
+        File,
+        OpenOptions,
+        TryLockError,
+    },
+    io::{
+        BufRead,
+        BufReader,
+        Read,
+        Seek,
+        SeekFrom,
+        Write,
+    },
+    marker::PhantomData,
+    path::{
+        Path,
+        PathBuf,
+    },
+    sync::{
+        Arc,
+        RwLock,
+        RwLockReadGuard,
+        RwLockWriteGuard,
+    },
+};
+
+use crate::{
+    aggregates::IAggregate,
+    commands::ICommand,
+    errors::Error,
+    events::{
+        EventContext,
+        EventUpcasters,
+        IEvent,
+        PositionedEvent,
+        SerializedEvent,
+    },
+    stores::IEventStore,
+};
+#[cfg(feature = "crypto")]
+use crate::{
+    shredding::{
+        CryptoShredder,
+        SharedShredder,
+    },
+    stores::IKeyStore,
+};
+
+/// The size in bytes after which a new segment file is started.
+pub const DEFAULT_MAX_SEGMENT_SIZE: u64 = 64 * 1024 * 1024;
+
+/// The extension of the segment files
+const SEGMENT_EXTENSION: &str = "jsonl";
+
+/// A line of a segment file, i.e., a `SerializedEvent` along with the
+/// number of events of the same commit written on the following lines
+#[derive(Serialize, Deserialize)]
+struct SegmentLine {
+    #[serde(flatten)]
+    event: SerializedEvent,
+
+    #[serde(default, skip_serializing_if = "is_zero")]
+    remaining: usize,
+}
+
+impl SegmentLine {
+    /// Serializes `event` as a line followed by `remaining` events of
+    /// the same commit
+    fn serialize(
+        event: SerializedEvent,
+        remaining: usize,
+    ) -> Result<Vec<u8>, Error> {
+        let record = Self { event, remaining };
+
+        let mut line = serde_json::to_vec(&record).map_err(|e| {
+            Error::Serialization {
+                message: format!(
+                    "unable to serialize event {} of aggregate \
+                     '{}': {}",
+                    record.event.sequence,
+                    record.event.aggregate_id,
+                    e
+                ),
+                source: Some(e.into()),
+            }
+        })?;
+        line.push(b'\n');
+
+        Ok(line)
+    }
+}
+
+/// The location of a serialized event within the segment files
+#[derive(Debug, Clone, Copy)]
+struct EventLocation {
+    segment: u32,
+    offset: u64,
+    length: usize,
+}
+
+/// The index rebuilt from the segment files on startup, holding the
+/// location of the events of every aggregate instance along with the
+/// global order they were committed in. The global position of an
+/// event is its index in the log plus one.
+#[derive(Debug, Default)]
+struct SegmentIndex {
+    by_aggregate: HashMap<String, Vec<(i64, EventLocation)>>,
+    log: Vec<EventLocation>,
+    active_segment: u32,
+    active_size: u64,
+}
+
+/// Append-only event store persisting the events of the aggregates
+/// of type `A` to JSON Lines segment files, useful for CLI tools and
+/// edge deployments that do not run a database.
+///
+/// Every line of a segment file is a `SerializedEvent` along with a
+/// `remaining` field counting the events of the same commit on the
+/// following lines, omitted for the last one. The segment files of
+/// an aggregate type are named `<aggregate_type>-<number>.jsonl` and
+/// a new one is started once the current one grows beyond the
+/// maximum segment size. The index from aggregate ids to file
+/// offsets is kept in memory and rebuilt from the segment files when
+/// the store is opened. Every commit is flushed to disk, along with
+/// the directory entry of a new segment file, before it returns, and
+/// is atomic: a commit interrupted by a crash, i.e., a torn trailing
+/// line or a trailing commit missing some of its events, is dropped
+/// and truncated when the store is opened.
+///
+/// Cloning the store yields a handle to the same index, so clones
+/// may be shared with subscriptions or projection rebuilders. The
+/// store is the single writer of its segment files: opening it takes
+/// an exclusive lock on the `<aggregate_type>.lock` file of the
+/// directory, held until the store and all of its clones are dropped,
+/// and opening the same aggregate type in the same directory again,
+/// from this or another process, fails in the meantime.
+///
+/// A store configured with `with_shredder` writes the personal data
+/// of the events encrypted and decrypts it when they are loaded.
+///
+/// The store does not support the transactional outbox, which needs
+/// the `InMemoryEventStore` or the `SqliteEventStore`.
+///
+/// # Examples
+/// ```rust
+/// use cqrs_es2::{
+///     example_impl::{
+///         Customer,
+///         CustomerCommand,
+///         CustomerEvent,
+///         NameAdded,
+///     },
+///     EventContext,
+///     FileEventStore,
+///     IEventStore,
+/// };
+///
+/// let directory = std::env::temp_dir().join(format!(
+///     "cqrs-es2-doc-{}",
+///     uuid::Uuid::new_v4()
+/// ));
+///
+/// let mut store = FileEventStore::<
+///     CustomerCommand,
+///     CustomerEvent,
+///     Customer,
+/// >::open(&directory)
+/// .unwrap();
+///
+/// store
+///     .commit(
+///         vec![EventContext::new(
+///             "customer-1".to_string(),
+///             1,
+///             CustomerEvent::NameAdded(NameAdded {
+///                 changed_name: "John Doe".to_string(),
+///             }),
+///             Default::default(),
+///         )],
+///         0,
+///     )
+///     .unwrap();
+///
+/// // reopening the directory rebuilds the index
+/// drop(store);
+///
+/// let mut store = FileEventStore::<
+///     CustomerCommand,
+///     CustomerEvent,
+///     Customer,
+/// >::open(&directory)
+/// .unwrap();
+///
+/// let context = store
+///     .load_aggregate("customer-1")
+///     .unwrap();
+///
+/// assert_eq!(context.version, 1);
+/// assert_eq!(context.payload.name, "John Doe");
+///
+/// std::fs::remove_dir_all(&directory).unwrap();
+/// ```
+#[derive(Debug)]
+pub struct FileEventStore<C: ICommand, E: IEvent, A: IAggregate<C, E>>
+{
+    directory: PathBuf,
+    max_segment_size: u64,
+    upcasters: Arc<EventUpcasters>,
+    index: Arc<RwLock<SegmentIndex>>,
+    #[cfg(feature = "crypto")]
+    shredder: SharedShredder,
+    lock: Arc<File>,
+    _phantom: PhantomData<(C, E, A)>,
+}
+
+impl<C: ICommand, E: IEvent, A: IAggregate<C, E>> Clone
+    for FileEventStore<C, E, A>
+{
+    fn clone(&self) -> Self {
+        Self {
+            directory: self.directory.clone(),
+            max_segment_size: self.max_segment_size,
+            upcasters: Arc::clone(&self.upcasters),
+            index: Arc::clone(&self.index),
+            #[cfg(feature = "crypto")]
+            shredder: self.shredder.clone(),
+            lock: Arc::clone(&self.lock),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<C: ICommand, E: IEvent, A: IAggregate<C, E>>
+    FileEventStore<C, E, A>
+{
+    /// Opens the store in `directory`, creating the directory when
+    /// it does not exist, locks it for the aggregate type `A` and
+    /// rebuilds the index from the segment files of `A`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` when the directory is already locked by
+    /// another store of the aggregate type `A`, when the directory
+    /// or the segment files can not be read, or when a segment file
+    /// is corrupted.
+    pub fn open<P: AsRef<Path>>(directory: P) -> Result<Self, Error> {
+        let directory = directory.as_ref().to_path_buf();
+
+        fs::create_dir_all(&directory).map_err(|e| {
+            io_error(
+                &format!(
+                    "unable to create the event store directory '{}'",
+                    directory.display()
+                ),
+                e,
+            )
+        })?;
+
+        let lock = lock_directory(&directory, A::aggregate_type())?;
+        let index = rebuild_index(&directory, A::aggregate_type())?;
+
+        debug!(
+            "opened event store '{}' with {} events of {} \
+             aggregates of type '{}'",
+            directory.display(),
+            index.log.len(),
+            index.by_aggregate.len(),
+            A::aggregate_type()
+        );
+
+        Ok(Self {
+            directory,
+            max_segment_size: DEFAULT_MAX_SEGMENT_SIZE,
+            upcasters: Arc::default(),
+            index: Arc::new(RwLock::new(index)),
+            #[cfg(feature = "crypto")]
+            shredder: SharedShredder::default(),
+            lock: Arc::new(lock),
+            _phantom: PhantomData,
+        })
+    }
+
+    /// Starts a new segment file once the current one grows beyond
+    /// `max_segment_size` bytes
+    #[must_use]
+    pub fn with_max_segment_size(
+        self,
+        max_segment_size: u64,
+    ) -> Self {
+        Self {
+            max_segment_size,
+            ..self
+        }
+    }
+
+    /// Upcasts the loaded events to their latest schema version
+    #[must_use]
+    pub fn with_upcasters(
+        self,
+        upcasters: EventUpcasters,
+    ) -> Self {
+        Self {
+            upcasters: Arc::new(upcasters),
+            ..self
+        }
+    }
+
+    /// Encrypts the personal data of the committed events with
+    /// `shredder` before they are written to the segment files, see
+    /// `CryptoShredder`
+    #[cfg(feature = "crypto")]
+    #[must_use]
+    pub fn with_shredder<KS: IKeyStore + Debug + Send + 'static>(
+        self,
+        shredder: CryptoShredder<KS>,
+    ) -> Self {
+        Self {
+            shredder: SharedShredder::new(shredder),
+            ..self
+        }
+    }
+
+    /// The directory holding the segment files
+    #[must_use]
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    fn read_index(
+        &self
+    ) -> Result<RwLockReadGuard<'_, SegmentIndex>, Error> {
+        self.index.read().map_err(|e| {
+            Error::Store {
+                message: format!(
+                    "unable to read the event store: {e}"
+                ),
+                source: None,
+            }
+        })
+    }
+
+    fn write_index(
+        &self
+    ) -> Result<RwLockWriteGuard<'_, SegmentIndex>, Error> {
+        self.index.write().map_err(|e| {
+            Error::Store {
+                message: format!(
+                    "unable to write to the event store: {e}"
+                ),
+                source: None,
+            }
+        })
+    }
+
+    fn read_events<'a, I: IntoIterator<Item = &'a EventLocation>>(
+        &self,
+        locations: I,
+    ) -> Result<Vec<EventContext<C, E>>, Error> {
+        let mut files: HashMap<u32, File> = HashMap::new();
+
+        locations
+            .into_iter()
+            .map(|location| {
+                let path = segment_path(
+                    &self.directory,
+                    A::aggregate_type(),
+                    location.segment,
+                );
+
+                let file = match files.entry(location.segment) {
+                    Entry::Occupied(x) => x.into_mut(),
+                    Entry::Vacant(x) => {
+                        x.insert(File::open(&path).map_err(|e| {
+                            io_error(
+                                &format!(
+                                    "unable to open segment '{}'",
+                                    path.display()
+                                ),
+                                e,
+                            )
+                        })?)
+                    },
+                };
+
+                let mut line = vec![0; location.length];
+
+                file.seek(SeekFrom::Start(location.offset))
+                    .and_then(|_| file.read_exact(&mut line))
+                    .map_err(|e| {
+                        io_error(
+                            &format!(
+                                "unable to read segment '{}' at \
+                                 offset {}",
+                                path.display(),
+                                location.offset
+                            ),
+                            e,
+                        )
+                    })?;
+
+                let event =
+                    parse_line(&path, location.offset, &line)?.event;
+
+                #[cfg(feature = "crypto")]
+                let event = self.shredder.decrypt(event)?;
+
+                event.into_context_with(&self.upcasters)
+            })
+            .collect()
+    }
+}
+
+impl<C: ICommand, E: IEvent, A: IAggregate<C, E>> IEventStore<C, E, A>
+    for FileEventStore<C, E, A>
+{
+    fn load_events(
+        &mut self,
+        aggregate_id: &str,
+    ) -> Result<Vec<EventContext<C, E>>, Error> {
+        IEventStore::load_events_after(self, aggregate_id, 0)
+    }
+
+    fn load_events_after(
+        &mut self,
+        aggregate_id: &str,
+        version: i64,
+    ) -> Result<Vec<EventContext<C, E>>, Error> {
+        let index = self.read_index()?;
+
+        let result = match index.by_aggregate.get(aggregate_id) {
+            None => Vec::new(),
+            Some(x) => {
+                self.read_events(
+                    x.iter()
+                        .filter(|(sequence, _)| *sequence > version)
+                        .map(|(_, location)| location),
+                )?
+            },
+        };
+
+        trace!(
+            "loaded {} events after version {} for aggregate '{}' \
+             of type '{}'",
+            result.len(),
+            version,
+            aggregate_id,
+            A::aggregate_type()
+        );
+
+        Ok(result)
+    }
+
+    fn load_all_events(
+        &mut self
+    ) -> Result<Vec<EventContext<C, E>>, Error> {
+        let index = self.read_index()?;
+
+        let result = self.read_events(&index.log)?;
+
+        trace!(
+            "loaded {} events of all aggregates of type '{}'",
+            result.len(),
+            A::aggregate_type()
+        );
+
+        Ok(result)
+    }
+
+    fn load_all_events_after(
+        &mut self,
+        position: i64,
+        limit: usize,
+    ) -> Result<Vec<PositionedEvent<C, E>>, Error> {
+        let index = self.read_index()?;
+
+        let position = position.max(0);
+        let skipped = usize::try_from(position).unwrap_or(usize::MAX);
+
+        let result: Vec<_> = self
+            .read_events(
+                index
+                    .log
+                    .iter()
+                    .skip(skipped)
+                    .take(limit),
+            )?
+            .into_iter()
+            .zip((position + 1)..)
+            .map(|(event, x)| PositionedEvent::new(x, event))
+            .collect();
+
+        trace!(
+            "loaded {} events after position {} of all aggregates \
+             of type '{}'",
+            result.len(),
+            position,
+            A::aggregate_type()
+        );
+
+        Ok(result)
+    }
+
+    fn count_events_after(
+        &mut self,
+        position: i64,
+    ) -> Result<usize, Error> {
+        let index = self.read_index()?;
+
+        let skipped =
+            usize::try_from(position.max(0)).unwrap_or(usize::MAX);
+
+        Ok(index.log.len().saturating_sub(skipped))
+    }
+
+    fn commit(
+        &mut self,
+        events: Vec<EventContext<C, E>>,
+        expected_version: i64,
+    ) -> Result<(), Error> {
+        let aggregate_id = match events.first() {
+            None => return Ok(()),
+            Some(x) => x.aggregate_id.clone(),
+        };
+
+        let mut expected_sequence = expected_version;
+
+        for event in &events {
+            if event.aggregate_id != aggregate_id {
+                return Err(Error::TechnicalError(format!(
+                    "unable to commit events of multiple aggregates \
+                     at once: '{}' and '{}'",
+                    aggregate_id, event.aggregate_id
+                )));
+            }
+
+            expected_sequence += 1;
+
+            if event.sequence != expected_sequence {
+                return Err(Error::TechnicalError(format!(
+                    "expected event sequence {} for aggregate '{}' \
+                     but found {}",
+                    expected_sequence, aggregate_id, event.sequence
+                )));
+            }
+        }
+
+        let mut index = self.write_index()?;
+
+        let current_version = index
+            .by_aggregate
+            .get(&aggregate_id)
+            .and_then(|x| x.last())
+            .map_or(0, |(sequence, _)| *sequence);
+
+        if current_version != expected_version {
+            return Err(Error::ConcurrencyConflict {
+                aggregate_id,
+                expected: expected_version,
+                actual: current_version,
+            });
+        }
+
+        // the events are encrypted once the commit can not be
+        // rejected anymore, so that it does not create orphaned keys
+        let mut lines = Vec::with_capacity(events.len());
+
+        for event in &events {
+            let serialized = SerializedEvent::from_context(
+                A::aggregate_type(),
+                event,
+            )?;
+
+            #[cfg(feature = "crypto")]
+            let serialized = self.shredder.encrypt(serialized)?;
+
+            lines.push(SegmentLine::serialize(
+                serialized,
+                events.len() - lines.len() - 1,
+            )?);
+        }
+
+        if index.active_size >= self.max_segment_size {
+            index.active_segment += 1;
+            index.active_size = 0;
+        }
+
+        let path = segment_path(
+            &self.directory,
+            A::aggregate_type(),
+            index.active_segment,
+        );
+
+        debug!(
+            "committing {} events for aggregate '{}' of type '{}' \
+             to '{}'",
+            events.len(),
+            aggregate_id,
+            A::aggregate_type(),
+            path.display()
+        );
+
+        append(
+            &self.directory,
+            &path,
+            index.active_size,
+            &lines,
+        )?;
+
+        let segment = index.active_segment;
+        let mut offset = index.active_size;
+        let mut locations = Vec::with_capacity(lines.len());
+
+        for line in &lines {
+            locations.push(EventLocation {
+                segment,
+                offset,
+                length: line.len() - 1,
+            });
+            offset += line.len() as u64;
+        }
+
+        index.active_size = offset;
+        index
+            .log
+            .extend(locations.iter().copied());
+        index
+            .by_aggregate
+            .entry(aggregate_id)
+            .or_default()
+            .extend(
+                events
+                    .iter()
+                    .map(|x| x.sequence)
+                    .zip(locations),
+            );
+
+        Ok(())
+    }
+}
+
+/// Appends `lines` to the segment file at `path` whose committed size
+/// is `size` and flushes them to disk, along with the entry of the
+/// segment file in `directory` when it is new. A failed append is
+/// rolled back so that no partial commit is left behind.
+fn append(
+    directory: &Path,
+    path: &Path,
+    size: u64,
+    lines: &[Vec<u8>],
+) -> Result<(), Error> {
+    let is_new = !path.exists();
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| {
+            io_error(
+                &format!(
+                    "unable to open segment '{}'",
+                    path.display()
+                ),
+                e,
+            )
+        })?;
+
+    let result = file
+        .write_all(&lines.concat())
+        .and_then(|()| file.sync_data());
+
+    if let Err(e) = result {
+        if let Err(x) = file.set_len(size) {
+            warn!(
+                "unable to roll back segment '{}': {}",
+                path.display(),
+                x
+            );
+        }
+
+        return Err(io_error(
+            &format!(
+                "unable to append to segment '{}'",
+                path.display()
+            ),
+            e,
+        ));
+    }
+
+    if is_new {
+        sync_directory(directory)?;
+    }
+
+    Ok(())
+}
+
+/// Flushes the entries of `directory` to disk so that a new segment
+/// file survives a crash
+#[cfg(unix)]
+pub(super) fn sync_directory(directory: &Path) -> Result<(), Error> {
+    File::open(directory)
+        .and_then(|x| x.sync_all())
+        .map_err(|e| {
+            io_error(
+                &format!(
+                    "unable to sync the store directory '{}'",
+                    directory.display()
+                ),
+                e,
+            )
+        })
+}
+
+/// Directories can not be opened, and need not be flushed, on other
+/// platforms
+#[cfg(not(unix))]
+#[allow(clippy::unnecessary_wraps)]
+pub(super) fn sync_directory(_directory: &Path) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Takes an exclusive lock on the lock file of `aggregate_type` in
+/// `directory`, released when the returned file is closed
+fn lock_directory(
+    directory: &Path,
+    aggregate_type: &str,
+) -> Result<File, Error> {
+    let path = directory.join(format!("{aggregate_type}.lock"));
+
+    let file = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&path)
+        .map_err(|e| {
+            io_error(
+                &format!(
+                    "unable to open the lock file '{}'",
+                    path.display()
+                ),
+                e,
+            )
+        })?;
+
+    match file.try_lock() {
+        Ok(()) => Ok(file),
+        Err(TryLockError::WouldBlock) => {
+            Err(Error::store(
+                &format!(
+                    "the event store '{}' of aggregate type '{}' is \
+                     already opened by another store",
+                    directory.display(),
+                    aggregate_type
+                ),
+                None,
+            ))
+        },
+        Err(TryLockError::Error(e)) => {
+            Err(io_error(
+                &format!(
+                    "unable to lock the lock file '{}'",
+                    path.display()
+                ),
+                e,
+            ))
+        },
+    }
+}
+
+/// Scans the segment files of `aggregate_type` in `directory` and
+/// rebuilds the index from their lines
+fn rebuild_index(
+    directory: &Path,
+    aggregate_type: &str,
+) -> Result<SegmentIndex, Error> {
+    let segments = list_segments(directory, aggregate_type)?;
+
+    let mut index = SegmentIndex {
+        active_segment: 1,
+        ..SegmentIndex::default()
+    };
+
+    for (position, segment) in segments.iter().enumerate() {
+        let path = segment_path(directory, aggregate_type, *segment);
+        let is_last = position + 1 == segments.len();
+
+        let size =
+            index_segment(&mut index, &path, *segment, is_last)?;
+
+        index.active_segment = *segment;
+        index.active_size = size;
+    }
+
+    Ok(index)
+}
+
+/// The numbers of the segment files of `aggregate_type` in
+/// `directory` in ascending order
+fn list_segments(
+    directory: &Path,
+    aggregate_type: &str,
+) -> Result<Vec<u32>, Error> {
+    let entries = fs::read_dir(directory).map_err(|e| {
+        io_error(
+            &format!(
+                "unable to list the event store directory '{}'",
+                directory.display()
+            ),
+            e,
+        )
+    })?;
+
+    let prefix = format!("{aggregate_type}-");
+    let suffix = format!(".{SEGMENT_EXTENSION}");
+
+    let mut segments = Vec::new();
+
+    for entry in entries {
+        let entry = entry.map_err(|e| {
+            io_error(
+                &format!(
+                    "unable to list the event store directory '{}'",
+                    directory.display()
+                ),
+                e,
+            )
+        })?;
+
+        let file_name = entry.file_name();
+
+        let segment = file_name
+            .to_str()
+            .and_then(|x| x.strip_prefix(&prefix))
+            .and_then(|x| x.strip_suffix(&suffix))
+            .and_then(|x| x.parse::<u32>().ok());
+
+        if let Some(x) = segment {
+            segments.push(x);
+        }
+    }
+
+    segments.sort_unstable();
+
+    Ok(segments)
+}
+
+/// Adds the lines of a segment file to `index` and returns the size
+/// of its committed lines. A torn trailing line, or a trailing commit
+/// missing some of its events, of the last segment is left over by an
+/// interrupted commit and is truncated.
+fn index_segment(
+    index: &mut SegmentIndex,
+    path: &Path,
+    segment: u32,
+    is_last: bool,
+) -> Result<u64, Error> {
+    let read_error = |e| {
+        io_error(
+            &format!(
+                "unable to read segment '{}'",
+                path.display()
+            ),
+            e,
+        )
+    };
+
+    let mut reader =
+        BufReader::new(File::open(path).map_err(read_error)?);
+
+    let mut offset = 0;
+    let mut committed = 0;
+    let mut batch = Vec::new();
+    let mut line = Vec::new();
+
+    loop {
+        line.clear();
+
+        let read = reader
+            .read_until(b'\n', &mut line)
+            .map_err(read_error)?;
+
+        if read == 0 || line.last() != Some(&b'\n') {
+            break;
+        }
+
+        let length = line.len() - 1;
+        let record = parse_line(path, offset, &line[..length])?;
+
+        let location = EventLocation {
+            segment,
+            offset,
+            length,
+        };
+
+        offset += read as u64;
+
+        let remaining = record.remaining;
+        batch.push((record.event, location));
+
+        if remaining == 0 {
+            for (event, location) in batch.drain(..) {
+                index_event(index, path, &event, location)?;
+            }
+
+            committed = offset;
+        }
+    }
+
+    let size = reader
+        .get_ref()
+        .metadata()
+        .map_err(read_error)?
+        .len();
+
+    if committed < size {
+        if !is_last {
+            return Err(Error::store(
+                &format!(
+                    "segment '{}' ends with an interrupted commit \
+                     at offset {}",
+                    path.display(),
+                    committed
+                ),
+                None,
+            ));
+        }
+
+        warn!(
+            "truncating interrupted commit at offset {} of segment \
+             '{}'",
+            committed,
+            path.display()
+        );
+
+        OpenOptions::new()
+            .write(true)
+            .open(path)
+            .and_then(|x| {
+                x.set_len(committed)?;
+                x.sync_data()
+            })
+            .map_err(|e| {
+                io_error(
+                    &format!(
+                        "unable to truncate segment '{}'",
+                        path.display()
+                    ),
+                    e,
+                )
+            })?;
+    }
+
+    trace!(
+        "indexed segment '{}' of {} bytes",
+        path.display(),
+        committed
+    );
+
+    Ok(committed)
+}
+
+/// Adds a committed event to `index`, checking that it follows the
+/// previous event of its aggregate instance
+fn index_event(
+    index: &mut SegmentIndex,
+    path: &Path,
+    event: &SerializedEvent,
+    location: EventLocation,
+) -> Result<(), Error> {
+    let events = index
+        .by_aggregate
+        .entry(event.aggregate_id.clone())
+        .or_default();
+
+    let expected_sequence = events.last().map_or(0, |(x, _)| *x) + 1;
+
+    if event.sequence != expected_sequence {
+        return Err(Error::store(
+            &format!(
+                "expected event sequence {} for aggregate '{}' but \
+                 found {} at offset {} of segment '{}'",
+                expected_sequence,
+                event.aggregate_id,
+                event.sequence,
+                location.offset,
+                path.display()
+            ),
+            None,
+        ));
+    }
+
+    events.push((event.sequence, location));
+    index.log.push(location);
+
+    Ok(())
+}
+
+fn parse_line(
+    path: &Path,
+    offset: u64,
+    line: &[u8],
+) -> Result<SegmentLine, Error> {
+    serde_json::from_slice(line).map_err(|e| {
+        Error::Serialization {
+            message: format!(
+                "unable to deserialize event at offset {} of \
+                 segment '{}': {}",
+                offset,
+                path.display(),
+                e
+            ),
+            source: Some(e.into()),
+        }
+    })
+}
+
+#[allow(clippy::trivially_copy_pass_by_ref)]
+fn is_zero(x: &usize) -> bool {
+    *x == 0
+}
+
+fn segment_path(
+    directory: &Path,
+    aggregate_type: &str,
+    segment: u32,
+) -> PathBuf {
+    directory.join(format!(
+        "{aggregate_type}-{segment:06}.{SEGMENT_EXTENSION}"
+    ))
+}
+
+pub(super) fn io_error(
+    message: &str,
+    e: std::io::Error,
+) -> Error {
+    Error::store(
+        &format!("{message}: {e}"),
+        Some(e.into()),
+    )
+}
@@ -0,0 +1,237 @@
+use base64::{
+    engine::general_purpose::STANDARD,
+    Engine,
+};
+use log::{
+    debug,
+    trace,
+};
+use std::{
+    collections::HashMap,
+    fs,
+    path::{
+        Path,
+        PathBuf,
+    },
+    sync::{
+        Arc,
+        RwLock,
+        RwLockWriteGuard,
+    },
+};
+
+use crate::{
+    errors::Error,
+    stores::IKeyStore,
+};
+
+use super::{
+    file_event_store::io_error,
+    json_file::{
+        read_json_file,
+        write_json_file,
+    },
+};
+
+/// The name of the file holding the keys
+const KEYS_FILE: &str = "keys.json";
+
+/// File key store keeping the crypto-shredding key of every subject,
+/// base64 encoded, in a `keys.json` file. Every change replaces the
+/// file atomically. Cloning the store yields a handle to the same
+/// underlying keys.
+///
+/// A deleted key is gone from the file once it is replaced, but the
+/// file system may still hold the blocks of the previous file, so
+/// the directory should live on an encrypted volume.
+///
+/// # Examples
+/// ```rust
+/// use cqrs_es2::{
+///     FileKeyStore,
+///     IKeyStore,
+/// };
+///
+/// let directory = std::env::temp_dir().join(format!(
+///     "cqrs-es2-doc-{}",
+///     uuid::Uuid::new_v4()
+/// ));
+///
+/// let mut store = FileKeyStore::open(&directory).unwrap();
+///
+/// store
+///     .save_key("customer-1", vec![7; 32])
+///     .unwrap();
+///
+/// let mut store = FileKeyStore::open(&directory).unwrap();
+///
+/// assert_eq!(
+///     store.load_key("customer-1").unwrap(),
+///     Some(vec![7; 32])
+/// );
+///
+/// std::fs::remove_dir_all(&directory).unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct FileKeyStore {
+    directory: PathBuf,
+    keys: Arc<RwLock<HashMap<String, Vec<u8>>>>,
+}
+
+impl FileKeyStore {
+    /// Opens the store in `directory`, creating the directory when
+    /// it does not exist, and reads the saved keys.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` when the directory or the keys can not be
+    /// read.
+    pub fn open<P: AsRef<Path>>(directory: P) -> Result<Self, Error> {
+        let directory = directory.as_ref().to_path_buf();
+
+        fs::create_dir_all(&directory).map_err(|e| {
+            io_error(
+                &format!(
+                    "unable to create the key store directory '{}'",
+                    directory.display()
+                ),
+                e,
+            )
+        })?;
+
+        let encoded: HashMap<String, String> =
+            read_json_file(&directory, KEYS_FILE, "keys")?;
+
+        let keys = encoded
+            .into_iter()
+            .map(|(subject, key)| {
+                let key = STANDARD.decode(key).map_err(|e| {
+                    Error::serialization(
+                        &format!(
+                            "invalid key of subject '{subject}': {e}"
+                        ),
+                        None,
+                    )
+                })?;
+
+                Ok((subject, key))
+            })
+            .collect::<Result<HashMap<_, _>, Error>>()?;
+
+        debug!(
+            "opened key store '{}' with {} subjects",
+            directory.display(),
+            keys.len()
+        );
+
+        Ok(Self {
+            directory,
+            keys: Arc::new(RwLock::new(keys)),
+        })
+    }
+
+    /// The directory holding the keys file
+    #[must_use]
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    fn write(
+        &self
+    ) -> Result<RwLockWriteGuard<'_, HashMap<String, Vec<u8>>>, Error>
+    {
+        self.keys.write().map_err(|e| {
+            Error::Store {
+                message: format!(
+                    "unable to write to the key store: {e}"
+                ),
+                source: None,
+            }
+        })
+    }
+
+    /// Replaces the keys file with `keys`, then the keys in memory
+    fn replace(
+        &self,
+        keys: &mut HashMap<String, Vec<u8>>,
+        saved: HashMap<String, Vec<u8>>,
+    ) -> Result<(), Error> {
+        let encoded: HashMap<&str, String> = saved
+            .iter()
+            .map(|(subject, key)| {
+                (subject.as_str(), STANDARD.encode(key))
+            })
+            .collect();
+
+        write_json_file(
+            &self.directory,
+            KEYS_FILE,
+            "keys",
+            &encoded,
+        )?;
+
+        *keys = saved;
+
+        Ok(())
+    }
+}
+
+impl IKeyStore for FileKeyStore {
+    fn load_key(
+        &mut self,
+        subject: &str,
+    ) -> Result<Option<Vec<u8>>, Error> {
+        let keys = self.keys.read().map_err(|e| {
+            Error::Store {
+                message: format!("unable to read the key store: {e}"),
+                source: None,
+            }
+        })?;
+
+        trace!("loading key of subject '{subject}'");
+
+        Ok(keys.get(subject).cloned())
+    }
+
+    fn save_key(
+        &mut self,
+        subject: &str,
+        key: Vec<u8>,
+    ) -> Result<(), Error> {
+        let mut keys = self.write()?;
+
+        if keys.contains_key(subject) {
+            return Err(Error::store(
+                &format!(
+                    "the key of subject '{subject}' already exists"
+                ),
+                None,
+            ));
+        }
+
+        trace!("saving key of subject '{subject}'");
+
+        let mut saved = keys.clone();
+        saved.insert(subject.to_string(), key);
+
+        self.replace(&mut keys, saved)
+    }
+
+    fn delete_key(
+        &mut self,
+        subject: &str,
+    ) -> Result<(), Error> {
+        let mut keys = self.write()?;
+
+        if !keys.contains_key(subject) {
+            return Ok(());
+        }
+
+        trace!("deleting key of subject '{subject}'");
+
+        let mut saved = keys.clone();
+        saved.remove(subject);
+
+        self.replace(&mut keys, saved)
+    }
+}
@@ -0,0 +1,101 @@
+use serde::{
+    de::DeserializeOwned,
+    Serialize,
+};
+use std::{
+    fs::{
+        self,
+        File,
+    },
+    io::{
+        ErrorKind,
+        Write,
+    },
+    path::Path,
+};
+
+use crate::errors::Error;
+
+use super::file_event_store::{
+    io_error,
+    sync_directory,
+};
+
+/// Reads the JSON file `name` of `directory` holding the `what` of a
+/// store, the default value when it does not exist yet
+pub(super) fn read_json_file<T: DeserializeOwned + Default>(
+    directory: &Path,
+    name: &str,
+    what: &str,
+) -> Result<T, Error> {
+    let path = directory.join(name);
+
+    let contents = match fs::read(&path) {
+        Ok(x) => x,
+        Err(e) if e.kind() == ErrorKind::NotFound => {
+            return Ok(T::default());
+        },
+        Err(e) => {
+            return Err(io_error(
+                &format!(
+                    "unable to read the {} '{}'",
+                    what,
+                    path.display()
+                ),
+                e,
+            ));
+        },
+    };
+
+    serde_json::from_slice(&contents).map_err(|e| {
+        Error::Serialization {
+            message: format!(
+                "unable to deserialize the {} '{}': {}",
+                what,
+                path.display(),
+                e
+            ),
+            source: Some(e.into()),
+        }
+    })
+}
+
+/// Replaces the JSON file `name` of `directory` holding the `what` of
+/// a store by writing a temporary file first and renaming it, so
+/// that a crash leaves either the previous or the new contents
+/// behind
+pub(super) fn write_json_file<T: Serialize>(
+    directory: &Path,
+    name: &str,
+    what: &str,
+    value: &T,
+) -> Result<(), Error> {
+    let path = directory.join(name);
+    let temporary = path.with_extension("json.tmp");
+
+    let contents = serde_json::to_vec(value).map_err(|e| {
+        Error::Serialization {
+            message: format!("unable to serialize the {what}: {e}"),
+            source: Some(e.into()),
+        }
+    })?;
+
+    File::create(&temporary)
+        .and_then(|mut x| {
+            x.write_all(&contents)?;
+            x.sync_all()
+        })
+        .and_then(|()| fs::rename(&temporary, &path))
+        .map_err(|e| {
+            io_error(
+                &format!(
+                    "unable to write the {} '{}'",
+                    what,
+                    path.display()
+                ),
+                e,
+            )
+        })?;
+
+    sync_directory(directory)
+}
@@ -0,0 +1,21 @@
+//! # file_store
+//!
+//! File system implementations of the store interfaces, useful for
+//! local and embedded deployments without a database
+
+pub use file_checkpoint_store::FileCheckpointStore;
+pub use file_event_store::{
+    FileEventStore,
+    DEFAULT_MAX_SEGMENT_SIZE,
+};
+#[cfg(feature = "crypto")]
+pub use file_key_store::FileKeyStore;
+
+mod file_checkpoint_store;
+mod file_event_store;
+#[cfg(feature = "crypto")]
+mod file_key_store;
+mod json_file;
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,599 @@
+use serde_json::json;
+use std::{
+    fs::{
+        self,
+        OpenOptions,
+    },
+    io::Write,
+    path::{
+        Path,
+        PathBuf,
+    },
+};
+
+use crate::{
+    example_impl::*,
+    store_conformance::{
+        assert_event_store_conformance,
+        fixtures::{
+            email_updated,
+            name_added,
+        },
+    },
+    Error,
+    EventUpcaster,
+    EventUpcasters,
+    ICheckpointStore,
+    IEventStore,
+    PositionedEvent,
+};
+#[cfg(feature = "crypto")]
+use crate::{
+    store_conformance::assert_shredded_subject_load,
+    IKeyStore,
+    ENCRYPTED_FIELD_KEY,
+};
+
+#[cfg(feature = "crypto")]
+use super::file_key_store::FileKeyStore;
+use super::{
+    file_checkpoint_store::FileCheckpointStore,
+    file_event_store::FileEventStore,
+};
+
+type ThisEventStore =
+    FileEventStore<CustomerCommand, CustomerEvent, Customer>;
+
+/// A scratch directory removed when dropped
+struct TempDir(PathBuf);
+
+impl TempDir {
+    fn new() -> Self {
+        Self(std::env::temp_dir().join(format!(
+            "cqrs-es2-test-{}",
+            uuid::Uuid::new_v4()
+        )))
+    }
+
+    fn path(&self) -> &Path {
+        &self.0
+    }
+
+    fn segment(
+        &self,
+        segment: u32,
+    ) -> PathBuf {
+        self.0
+            .join(format!("customer-{segment:06}.jsonl"))
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+#[test]
+fn test_load_empty_aggregate() {
+    let directory = TempDir::new();
+    let mut store = ThisEventStore::open(directory.path()).unwrap();
+
+    assert_eq!(
+        store.load_events("test_id_A").unwrap(),
+        Vec::new()
+    );
+
+    let context = store
+        .load_aggregate("test_id_A")
+        .unwrap();
+
+    assert_eq!(context.version, 0);
+    assert_eq!(context.payload, Customer::default());
+}
+
+#[test]
+fn test_commit_and_load_events() {
+    let directory = TempDir::new();
+    let mut store = ThisEventStore::open(directory.path()).unwrap();
+
+    let first = vec![
+        name_added("test_id_A", 1, "John Doe"),
+        email_updated("test_id_A", 2, "john@example.com"),
+    ];
+    let second = vec![name_added("test_id_B", 1, "Jane Doe")];
+    let third = vec![email_updated(
+        "test_id_A",
+        3,
+        "doe@example.com",
+    )];
+
+    store.commit(first.clone(), 0).unwrap();
+    store.commit(second.clone(), 0).unwrap();
+    store.commit(third.clone(), 2).unwrap();
+
+    let mut expected = first;
+    expected.extend(third.clone());
+
+    assert_eq!(
+        store.load_events("test_id_A").unwrap(),
+        expected
+    );
+    assert_eq!(
+        store.load_events("test_id_B").unwrap(),
+        second
+    );
+    assert_eq!(
+        store
+            .load_events_after("test_id_A", 2)
+            .unwrap(),
+        third
+    );
+
+    let context = store
+        .load_aggregate("test_id_A")
+        .unwrap();
+
+    assert_eq!(context.version, 3);
+    assert_eq!(context.payload.name, "John Doe");
+    assert_eq!(context.payload.email, "doe@example.com");
+
+    let text = fs::read_to_string(directory.segment(1)).unwrap();
+    assert_eq!(text.lines().count(), 4);
+}
+
+#[test]
+fn test_reopen_rebuilds_the_index() {
+    let directory = TempDir::new();
+
+    {
+        let mut store = ThisEventStore::open(directory.path())
+            .unwrap()
+            .with_max_segment_size(1);
+
+        store
+            .commit(
+                vec![
+                    name_added("test_id_A", 1, "John Doe"),
+                    email_updated("test_id_A", 2, "john@example.com"),
+                ],
+                0,
+            )
+            .unwrap();
+        store
+            .commit(
+                vec![name_added("test_id_B", 1, "Jane Doe")],
+                0,
+            )
+            .unwrap();
+    }
+
+    // every commit went to its own segment
+    assert!(directory.segment(1).exists());
+    assert!(directory.segment(2).exists());
+
+    let mut store = ThisEventStore::open(directory.path()).unwrap();
+
+    assert_eq!(
+        store
+            .load_all_events_after(1, 10)
+            .unwrap(),
+        vec![
+            PositionedEvent::new(
+                2,
+                email_updated("test_id_A", 2, "john@example.com")
+            ),
+            PositionedEvent::new(
+                3,
+                name_added("test_id_B", 1, "Jane Doe")
+            ),
+        ]
+    );
+    assert_eq!(store.count_events_after(1).unwrap(), 2);
+
+    store
+        .commit(
+            vec![email_updated(
+                "test_id_B",
+                2,
+                "jane@example.com",
+            )],
+            1,
+        )
+        .unwrap();
+
+    let context = store
+        .load_aggregate("test_id_B")
+        .unwrap();
+
+    assert_eq!(context.version, 2);
+    assert_eq!(
+        context.payload.email,
+        "jane@example.com"
+    );
+    assert_eq!(
+        store.load_all_events().unwrap().len(),
+        4
+    );
+}
+
+#[test]
+fn test_optimistic_concurrency() {
+    let directory = TempDir::new();
+    let mut store = ThisEventStore::open(directory.path()).unwrap();
+
+    store
+        .commit(
+            vec![name_added("test_id_A", 1, "John Doe")],
+            0,
+        )
+        .unwrap();
+
+    assert_eq!(
+        store
+            .commit(
+                vec![name_added("test_id_A", 1, "Jane Doe")],
+                0
+            )
+            .unwrap_err(),
+        Error::ConcurrencyConflict {
+            aggregate_id: "test_id_A".to_string(),
+            expected: 0,
+            actual: 1,
+        }
+    );
+
+    assert!(matches!(
+        store
+            .commit(
+                vec![name_added("test_id_A", 3, "Jane Doe")],
+                1
+            )
+            .unwrap_err(),
+        Error::TechnicalError(_)
+    ));
+
+    assert_eq!(
+        store.load_events("test_id_A").unwrap(),
+        vec![name_added("test_id_A", 1, "John Doe")]
+    );
+}
+
+#[test]
+fn test_clones_share_the_index() {
+    let directory = TempDir::new();
+    let mut store = ThisEventStore::open(directory.path()).unwrap();
+    let mut clone = store.clone();
+
+    store
+        .commit(
+            vec![name_added("test_id_A", 1, "John Doe")],
+            0,
+        )
+        .unwrap();
+
+    assert_eq!(
+        clone.load_events("test_id_A").unwrap(),
+        vec![name_added("test_id_A", 1, "John Doe")]
+    );
+}
+
+#[test]
+fn test_directory_is_locked_while_open() {
+    let directory = TempDir::new();
+
+    let store = ThisEventStore::open(directory.path()).unwrap();
+    let clone = store.clone();
+
+    assert!(matches!(
+        ThisEventStore::open(directory.path()).unwrap_err(),
+        Error::Store { .. }
+    ));
+
+    // the lock is held until every clone is dropped
+    drop(store);
+
+    assert!(ThisEventStore::open(directory.path()).is_err());
+
+    drop(clone);
+
+    assert!(ThisEventStore::open(directory.path()).is_ok());
+}
+
+#[test]
+fn test_torn_trailing_line_is_truncated() {
+    let directory = TempDir::new();
+
+    {
+        let mut store =
+            ThisEventStore::open(directory.path()).unwrap();
+
+        store
+            .commit(
+                vec![name_added("test_id_A", 1, "John Doe")],
+                0,
+            )
+            .unwrap();
+    }
+
+    let size = fs::metadata(directory.segment(1))
+        .unwrap()
+        .len();
+
+    OpenOptions::new()
+        .append(true)
+        .open(directory.segment(1))
+        .unwrap()
+        .write_all(b"{\"event_type\":\"EmailUp")
+        .unwrap();
+
+    let mut store = ThisEventStore::open(directory.path()).unwrap();
+
+    assert_eq!(
+        fs::metadata(directory.segment(1))
+            .unwrap()
+            .len(),
+        size
+    );
+
+    store
+        .commit(
+            vec![email_updated(
+                "test_id_A",
+                2,
+                "john@example.com",
+            )],
+            1,
+        )
+        .unwrap();
+
+    drop(store);
+
+    let mut store = ThisEventStore::open(directory.path()).unwrap();
+
+    assert_eq!(
+        store.load_events("test_id_A").unwrap(),
+        vec![
+            name_added("test_id_A", 1, "John Doe"),
+            email_updated("test_id_A", 2, "john@example.com"),
+        ]
+    );
+}
+
+#[test]
+fn test_interrupted_commit_is_dropped() {
+    let directory = TempDir::new();
+
+    let size = {
+        let mut store =
+            ThisEventStore::open(directory.path()).unwrap();
+
+        store
+            .commit(
+                vec![name_added("test_id_A", 1, "John Doe")],
+                0,
+            )
+            .unwrap();
+
+        let size = fs::metadata(directory.segment(1))
+            .unwrap()
+            .len();
+
+        store
+            .commit(
+                vec![
+                    email_updated("test_id_A", 2, "john@example.com"),
+                    name_added("test_id_A", 3, "Jane Doe"),
+                ],
+                1,
+            )
+            .unwrap();
+
+        size
+    };
+
+    // a crash before the last event of the second commit reached
+    // the disk
+    let content = fs::read_to_string(directory.segment(1)).unwrap();
+    let last_line = content.trim_end().rfind('\n').unwrap();
+
+    fs::write(
+        directory.segment(1),
+        &content[..=last_line],
+    )
+    .unwrap();
+
+    let mut store = ThisEventStore::open(directory.path()).unwrap();
+
+    assert_eq!(
+        fs::metadata(directory.segment(1))
+            .unwrap()
+            .len(),
+        size
+    );
+
+    assert_eq!(
+        store.load_events("test_id_A").unwrap(),
+        vec![name_added("test_id_A", 1, "John Doe")]
+    );
+
+    assert_eq!(
+        store
+            .load_all_events_after(0, 10)
+            .unwrap()
+            .len(),
+        1
+    );
+}
+
+#[test]
+fn test_corrupted_segment_fails_to_open() {
+    let directory = TempDir::new();
+
+    fs::create_dir_all(directory.path()).unwrap();
+    fs::write(directory.segment(1), "not json\n").unwrap();
+
+    assert_eq!(
+        ThisEventStore::open(directory.path())
+            .unwrap_err()
+            .code(),
+        "serialization_error"
+    );
+}
+
+#[test]
+fn test_upcasters_apply_on_load() {
+    let directory = TempDir::new();
+
+    fs::create_dir_all(directory.path()).unwrap();
+    let legacy = json!({
+        "event_type": "NameAdded",
+        "event_version": 1,
+        "aggregate_type": "customer",
+        "aggregate_id": "test_id_A",
+        "sequence": 1,
+        "payload": { "name": "John Doe" },
+        "metadata": {},
+    });
+
+    fs::write(
+        directory.segment(1),
+        format!("{legacy}\n"),
+    )
+    .unwrap();
+
+    let mut store = ThisEventStore::open(directory.path())
+        .unwrap()
+        .with_upcasters(EventUpcasters::default().with_upcaster(
+            EventUpcaster::new("NameAdded", 1, |x| {
+                Ok(json!({ "changed_name": x["name"] }))
+            }),
+        ));
+
+    assert_eq!(
+        store.load_events("test_id_A").unwrap(),
+        vec![name_added("test_id_A", 1, "John Doe")]
+    );
+}
+
+#[test]
+fn test_checkpoints_persist_across_reopen() {
+    let directory = TempDir::new();
+
+    let mut store =
+        FileCheckpointStore::open(directory.path()).unwrap();
+
+    assert_eq!(store.load("subscriber").unwrap(), 0);
+
+    store.save("subscriber", 1).unwrap();
+    store.save("subscriber", 3).unwrap();
+    store.save("other", 2).unwrap();
+
+    assert_eq!(
+        store
+            .clone()
+            .load("subscriber")
+            .unwrap(),
+        3
+    );
+
+    let mut store =
+        FileCheckpointStore::open(directory.path()).unwrap();
+
+    assert_eq!(store.load("subscriber").unwrap(), 3);
+    assert_eq!(store.load("other").unwrap(), 2);
+}
+
+#[test]
+fn test_corrupted_checkpoints_fail_to_open() {
+    let directory = TempDir::new();
+
+    fs::create_dir_all(directory.path()).unwrap();
+    fs::write(
+        directory
+            .path()
+            .join("checkpoints.json"),
+        "{\"subscriber\":",
+    )
+    .unwrap();
+
+    assert!(matches!(
+        FileCheckpointStore::open(directory.path()).unwrap_err(),
+        Error::Serialization { .. }
+    ));
+}
+
+#[test]
+fn test_store_conformance() {
+    let directory = TempDir::new();
+
+    assert_event_store_conformance(|| {
+        ThisEventStore::open(
+            directory
+                .path()
+                .join(uuid::Uuid::new_v4().to_string()),
+        )
+        .unwrap()
+    });
+}
+
+#[cfg(feature = "crypto")]
+#[test]
+fn test_shredded_subject_load() {
+    let directory = TempDir::new();
+
+    assert_shredded_subject_load(|shredder| {
+        ThisEventStore::open(directory.path())
+            .unwrap()
+            .with_shredder(shredder)
+    });
+
+    // the personal data is only written encrypted
+    let text = fs::read_to_string(directory.segment(1)).unwrap();
+
+    assert!(text.contains(ENCRYPTED_FIELD_KEY));
+    assert!(!text.contains("Jane Doe"));
+    assert!(!text.contains("jane@example.com"));
+    assert!(text.contains("1 Main Street"));
+}
+
+#[cfg(feature = "crypto")]
+#[test]
+fn test_keys_persist_across_reopen() {
+    let directory = TempDir::new();
+
+    let mut store = FileKeyStore::open(directory.path()).unwrap();
+
+    assert!(store
+        .load_key("customer-1")
+        .unwrap()
+        .is_none());
+
+    store
+        .save_key("customer-1", vec![1; 32])
+        .unwrap();
+    store
+        .save_key("customer-2", vec![2; 32])
+        .unwrap();
+
+    assert_eq!(
+        store
+            .save_key("customer-1", vec![3; 32])
+            .unwrap_err()
+            .code(),
+        "store_error"
+    );
+
+    store.delete_key("customer-2").unwrap();
+
+    let mut store = FileKeyStore::open(directory.path()).unwrap();
+
+    assert_eq!(
+        store.load_key("customer-1").unwrap(),
+        Some(vec![1; 32])
+    );
+    assert!(store
+        .load_key("customer-2")
+        .unwrap()
+        .is_none());
+}
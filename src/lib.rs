@@ -6,6 +6,14 @@
     clippy::pedantic,
     //missing_debug_implementations
 )]
+// lints the code base does not follow
+#![allow(
+    clippy::assigning_clones,
+    clippy::default_trait_access,
+    clippy::manual_string_new,
+    clippy::should_panic_without_expect,
+    clippy::uninlined_format_args
+)]
 
 //! # cqrs-es2
 //!
@@ -34,6 +42,38 @@
 //! cqrs-es2 = { version = "*"}
 //! ```
 //!
+//! The async counterparts of the interfaces are available with the
+//! `async` feature:
+//!
+//! ```toml
+//! [dependencies]
+//! cqrs-es2 = { version = "*", features = ["async"] }
+//! ```
+//!
+//! The `Command`, `Event`, `Aggregate` and `Query` derive macros are
+//! available with the `derive` feature:
+//!
+//! ```toml
+//! [dependencies]
+//! cqrs-es2 = { version = "*", features = ["derive"] }
+//! ```
+//!
+//! The crypto-shredding of personal data in events is available with
+//! the `crypto` feature:
+//!
+//! ```toml
+//! [dependencies]
+//! cqrs-es2 = { version = "*", features = ["crypto"] }
+//! ```
+//!
+//! The `SQLite` event, snapshot, query and outbox stores are
+//! available with the `sqlite` feature:
+//!
+//! ```toml
+//! [dependencies]
+//! cqrs-es2 = { version = "*", features = ["sqlite"] }
+//! ```
+//!
 //! ## Usage
 //!
 //! Full fledged demo applications:
@@ -44,12 +84,38 @@
 pub use crate::{
     aggregates::*,
     commands::*,
+    cqrs::*,
     errors::*,
     events::*,
+    file_store::*,
+    memory_store::*,
+    metadata::*,
+    middleware::*,
+    outbox::*,
     queries::*,
+    sagas::*,
+    stores::*,
+    subscriptions::*,
     test_framework::*,
 };
 
+#[cfg(feature = "async")]
+pub use crate::async_cqrs::*;
+
+#[cfg(feature = "crypto")]
+pub use crate::shredding::*;
+
+#[cfg(feature = "sqlite")]
+pub use crate::sqlite_store::*;
+
+#[cfg(feature = "derive")]
+pub use cqrs_es2_derive::{
+    Aggregate,
+    Command,
+    Event,
+    Query,
+};
+
 /// Errors module holds the library error types.
 mod errors;
 
@@ -64,15 +130,69 @@ mod commands;
 /// wrapper.
 mod events;
 
+/// Metadata module provides the enrichers that record auditing
+/// information in the metadata of the events.
+mod metadata;
+
+/// Middleware module provides the command and event pipelines
+/// wrapping command dispatch with cross-cutting behavior.
+mod middleware;
+
+/// Outbox module provides the transactional outbox and the relay
+/// publishing the committed events to external message brokers.
+mod outbox;
+
 /// Queries module provides the basic downstream query objects needed
 /// to render queries (or "views") that describe the state of the
 /// system.
 mod queries;
 
+/// Sagas module provides the process managers coordinating work
+/// across aggregates.
+mod sagas;
+
+/// Subscriptions module provides the named subscriptions catching up
+/// on and then following the global event stream.
+mod subscriptions;
+
+/// Shredding module provides the field-level encryption that allows
+/// erasing personal data from immutable events.
+#[cfg(feature = "crypto")]
+mod shredding;
+
+/// Stores module provides the abstract interfaces that every
+/// persistence backend implements.
+mod stores;
+
+/// Memory store module provides in-memory implementations of the
+/// store interfaces for testing and prototyping.
+mod memory_store;
+
+/// File store module provides an append-only event store persisting
+/// events to JSON Lines files, and a checkpoint store, for local and
+/// embedded use.
+mod file_store;
+
+/// Sqlite store module provides the `SQLite` event, snapshot, query,
+/// outbox and checkpoint stores for embedded use.
+#[cfg(feature = "sqlite")]
+mod sqlite_store;
+
+/// Cqrs module provides the command dispatch framework that loads
+/// aggregates, handles commands and commits the resulting events.
+mod cqrs;
+
+/// Async cqrs module provides the async counterparts of the command,
+/// event, store and dispatch interfaces.
+#[cfg(feature = "async")]
+mod async_cqrs;
+
 /// Test provides a test framework for building a resilient test base
-/// around aggregates. A `HandlerTester` and a `ConsumerTester` should
-/// be used to build a comprehensive set of aggregate and query tests
-/// to verify your application logic (aka business rules).
+/// around aggregates. A `HandlerTester`, a `ConsumerTester` and a
+/// `SagaTester` should be used to build a comprehensive set of
+/// aggregate, query and saga tests to verify your application logic
+/// (aka business rules). The `store_conformance` suite verifies store
+/// backends.
 mod test_framework;
 
 #[doc(hidden)]
@@ -0,0 +1,74 @@
+use log::trace;
+use std::{
+    collections::HashMap,
+    sync::{
+        Arc,
+        RwLock,
+    },
+};
+
+use crate::{
+    errors::Error,
+    stores::ICheckpointStore,
+};
+
+/// Simple memory checkpoint store only useful for testing purposes.
+/// Cloning the store yields a handle to the same underlying
+/// checkpoints.
+#[derive(Debug, Default, Clone)]
+pub struct InMemoryCheckpointStore {
+    checkpoints: Arc<RwLock<HashMap<String, i64>>>,
+}
+
+impl ICheckpointStore for InMemoryCheckpointStore {
+    fn load(
+        &mut self,
+        subscriber: &str,
+    ) -> Result<i64, Error> {
+        let checkpoints = self.checkpoints.read().map_err(|e| {
+            Error::Store {
+                message: format!(
+                    "unable to read the checkpoint store: {e}"
+                ),
+                source: None,
+            }
+        })?;
+
+        let result = checkpoints
+            .get(subscriber)
+            .copied()
+            .unwrap_or_default();
+
+        trace!(
+            "loaded checkpoint {result} of subscriber '{subscriber}'"
+        );
+
+        Ok(result)
+    }
+
+    fn save(
+        &mut self,
+        subscriber: &str,
+        position: i64,
+    ) -> Result<(), Error> {
+        let mut checkpoints =
+            self.checkpoints.write().map_err(|e| {
+                Error::Store {
+                    message: format!(
+                        "unable to write to the checkpoint store: \
+                         {e}"
+                    ),
+                    source: None,
+                }
+            })?;
+
+        trace!(
+            "saving checkpoint {position} of subscriber \
+             '{subscriber}'"
+        );
+
+        checkpoints.insert(subscriber.to_string(), position);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,112 @@
+use chrono::{
+    DateTime,
+    Utc,
+};
+use log::trace;
+use std::{
+    collections::HashMap,
+    sync::{
+        Arc,
+        RwLock,
+        RwLockWriteGuard,
+    },
+};
+
+use crate::{
+    commands::ProcessedCommand,
+    errors::Error,
+    stores::ICommandIdStore,
+};
+
+type ProcessedCommandMap =
+    HashMap<(String, String), ProcessedCommand>;
+
+/// Simple memory command id store only useful for testing purposes.
+/// Cloning the store yields a handle to the same underlying records.
+#[derive(Debug, Default, Clone)]
+pub struct InMemoryCommandIdStore {
+    commands: Arc<RwLock<ProcessedCommandMap>>,
+}
+
+impl InMemoryCommandIdStore {
+    fn write(
+        &self
+    ) -> Result<RwLockWriteGuard<'_, ProcessedCommandMap>, Error>
+    {
+        self.commands.write().map_err(|e| {
+            Error::Store {
+                message: format!(
+                    "unable to write to the command id store: {e}"
+                ),
+                source: None,
+            }
+        })
+    }
+}
+
+impl ICommandIdStore for InMemoryCommandIdStore {
+    fn load(
+        &mut self,
+        aggregate_id: &str,
+        command_id: &str,
+    ) -> Result<Option<ProcessedCommand>, Error> {
+        let commands = self.commands.read().map_err(|e| {
+            Error::Store {
+                message: format!(
+                    "unable to read the command id store: {e}"
+                ),
+                source: None,
+            }
+        })?;
+
+        let result = commands
+            .get(&(
+                aggregate_id.to_string(),
+                command_id.to_string(),
+            ))
+            .cloned();
+
+        trace!(
+            "loaded command '{}' of aggregate '{}': {}",
+            command_id,
+            aggregate_id,
+            if result.is_some() { "found" } else { "missing" }
+        );
+
+        Ok(result)
+    }
+
+    fn save(
+        &mut self,
+        command: ProcessedCommand,
+    ) -> Result<(), Error> {
+        trace!(
+            "saving command '{}' of aggregate '{}'",
+            command.command_id,
+            command.aggregate_id
+        );
+
+        self.write()?.insert(
+            (
+                command.aggregate_id.clone(),
+                command.command_id.clone(),
+            ),
+            command,
+        );
+
+        Ok(())
+    }
+
+    fn purge(
+        &mut self,
+        before: DateTime<Utc>,
+    ) -> Result<usize, Error> {
+        let mut commands = self.write()?;
+
+        let count = commands.len();
+
+        commands.retain(|_, x| x.processed_at >= before);
+
+        Ok(count - commands.len())
+    }
+}
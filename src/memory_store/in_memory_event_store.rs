@@ -0,0 +1,499 @@
+use log::{
+    debug,
+    trace,
+};
+use std::{
+    collections::HashMap,
+    convert::TryFrom,
+    fmt::Debug,
+    marker::PhantomData,
+    sync::{
+        Arc,
+        RwLock,
+    },
+};
+
+use crate::{
+    aggregates::IAggregate,
+    commands::ICommand,
+    errors::Error,
+    events::{
+        EventContext,
+        IEvent,
+        PositionedEvent,
+        SerializedEvent,
+    },
+    stores::IEventStore,
+};
+
+#[cfg(feature = "crypto")]
+use crate::shredding::{
+    CryptoShredder,
+    SharedShredder,
+};
+#[cfg(feature = "crypto")]
+use crate::stores::IKeyStore;
+
+use super::in_memory_outbox_store::InMemoryOutboxStore;
+
+/// A committed event, kept serialized with its personal data
+/// encrypted when the store has a shredder
+#[derive(Debug)]
+enum StoredEvent<C: ICommand, E: IEvent> {
+    Plain(EventContext<C, E>),
+    #[cfg(feature = "crypto")]
+    Encrypted(SerializedEvent),
+}
+
+impl<C: ICommand, E: IEvent> StoredEvent<C, E> {
+    fn sequence(&self) -> i64 {
+        match self {
+            Self::Plain(x) => x.sequence,
+            #[cfg(feature = "crypto")]
+            Self::Encrypted(x) => x.sequence,
+        }
+    }
+}
+
+/// The events to store along with their serialized form
+type PreparedEvents<C, E> = (
+    Vec<StoredEvent<C, E>>,
+    Vec<SerializedEvent>,
+);
+
+/// The committed events of every aggregate instance along with the
+/// global order they were committed in, recorded as the aggregate id
+/// and the index of the event within its aggregate. The global
+/// position of an event is its index in the log plus one.
+#[derive(Debug)]
+struct StoredEvents<C: ICommand, E: IEvent> {
+    by_aggregate: HashMap<String, Vec<StoredEvent<C, E>>>,
+    log: Vec<(String, usize)>,
+}
+
+impl<C: ICommand, E: IEvent> Default for StoredEvents<C, E> {
+    fn default() -> Self {
+        Self {
+            by_aggregate: HashMap::default(),
+            log: Vec::default(),
+        }
+    }
+}
+
+/// Simple memory store only useful for testing purposes. Cloning
+/// the store yields a handle to the same underlying events, which
+/// allows inspecting what has been committed by the framework.
+///
+/// A store configured with `with_outbox` also writes every committed
+/// event to the outbox, atomically with the commit. A store
+/// configured with `with_shredder` keeps the committed events
+/// serialized with their personal data encrypted, also in the
+/// outbox, and decrypts them when they are loaded.
+#[derive(Debug)]
+pub struct InMemoryEventStore<
+    C: ICommand,
+    E: IEvent,
+    A: IAggregate<C, E>,
+> {
+    events: Arc<RwLock<StoredEvents<C, E>>>,
+    outbox: Option<InMemoryOutboxStore>,
+    #[cfg(feature = "crypto")]
+    shredder: SharedShredder,
+    _phantom: PhantomData<A>,
+}
+
+impl<C: ICommand, E: IEvent, A: IAggregate<C, E>> Default
+    for InMemoryEventStore<C, E, A>
+{
+    fn default() -> Self {
+        Self {
+            events: Arc::default(),
+            outbox: None,
+            #[cfg(feature = "crypto")]
+            shredder: SharedShredder::default(),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<C: ICommand, E: IEvent, A: IAggregate<C, E>> Clone
+    for InMemoryEventStore<C, E, A>
+{
+    fn clone(&self) -> Self {
+        Self {
+            events: Arc::clone(&self.events),
+            outbox: self.outbox.clone(),
+            #[cfg(feature = "crypto")]
+            shredder: self.shredder.clone(),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<C: ICommand, E: IEvent, A: IAggregate<C, E>>
+    InMemoryEventStore<C, E, A>
+{
+    /// Writes the committed events to `outbox` as well
+    #[must_use]
+    pub fn with_outbox(
+        self,
+        outbox: InMemoryOutboxStore,
+    ) -> Self {
+        Self {
+            outbox: Some(outbox),
+            ..self
+        }
+    }
+
+    /// Encrypts the personal data of the committed events with
+    /// `shredder`, see `CryptoShredder`
+    #[cfg(feature = "crypto")]
+    #[must_use]
+    pub fn with_shredder<KS: IKeyStore + Debug + Send + 'static>(
+        self,
+        shredder: CryptoShredder<KS>,
+    ) -> Self {
+        Self {
+            shredder: SharedShredder::new(shredder),
+            ..self
+        }
+    }
+
+    #[cfg_attr(
+        not(feature = "crypto"),
+        allow(
+            clippy::unused_self,
+            clippy::unnecessary_wraps
+        )
+    )]
+    fn load_event(
+        &self,
+        event: &StoredEvent<C, E>,
+    ) -> Result<EventContext<C, E>, Error> {
+        match event {
+            StoredEvent::Plain(x) => Ok(x.clone()),
+            #[cfg(feature = "crypto")]
+            StoredEvent::Encrypted(x) => {
+                self.shredder
+                    .decrypt(x.clone())?
+                    .into_context()
+            },
+        }
+    }
+
+    /// Converts the committed `events` into the events to store,
+    /// along with their serialized form for the outbox, which is
+    /// encrypted when a shredder is configured
+    fn prepare(
+        &self,
+        events: Vec<EventContext<C, E>>,
+    ) -> Result<PreparedEvents<C, E>, Error> {
+        #[cfg(feature = "crypto")]
+        let serialize =
+            self.outbox.is_some() || self.shredder.is_enabled();
+        #[cfg(not(feature = "crypto"))]
+        let serialize = self.outbox.is_some();
+
+        let serialized = if serialize {
+            events
+                .iter()
+                .map(|x| {
+                    let event = SerializedEvent::from_context(
+                        A::aggregate_type(),
+                        x,
+                    )?;
+                    #[cfg(feature = "crypto")]
+                    let event = self.shredder.encrypt(event)?;
+                    Ok(event)
+                })
+                .collect::<Result<Vec<_>, Error>>()?
+        }
+        else {
+            Vec::new()
+        };
+
+        #[cfg(feature = "crypto")]
+        let events: Vec<_> = if self.shredder.is_enabled() {
+            serialized
+                .iter()
+                .cloned()
+                .map(StoredEvent::Encrypted)
+                .collect()
+        }
+        else {
+            events
+                .into_iter()
+                .map(StoredEvent::Plain)
+                .collect()
+        };
+        #[cfg(not(feature = "crypto"))]
+        let events: Vec<_> = events
+            .into_iter()
+            .map(StoredEvent::Plain)
+            .collect();
+
+        Ok((events, serialized))
+    }
+}
+
+impl<C: ICommand, E: IEvent, A: IAggregate<C, E>> IEventStore<C, E, A>
+    for InMemoryEventStore<C, E, A>
+{
+    fn load_events(
+        &mut self,
+        aggregate_id: &str,
+    ) -> Result<Vec<EventContext<C, E>>, Error> {
+        let events = self.events.read().map_err(|e| {
+            Error::Store {
+                message: format!(
+                    "unable to read the event store: {e}"
+                ),
+                source: None,
+            }
+        })?;
+
+        let result = events
+            .by_aggregate
+            .get(aggregate_id)
+            .map_or(&[][..], Vec::as_slice)
+            .iter()
+            .map(|x| self.load_event(x))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        trace!(
+            "loaded {} events for aggregate '{}' of type '{}'",
+            result.len(),
+            aggregate_id,
+            A::aggregate_type()
+        );
+
+        Ok(result)
+    }
+
+    fn load_events_after(
+        &mut self,
+        aggregate_id: &str,
+        version: i64,
+    ) -> Result<Vec<EventContext<C, E>>, Error> {
+        let events = self.events.read().map_err(|e| {
+            Error::Store {
+                message: format!(
+                    "unable to read the event store: {e}"
+                ),
+                source: None,
+            }
+        })?;
+
+        let result = events
+            .by_aggregate
+            .get(aggregate_id)
+            .map_or(&[][..], Vec::as_slice)
+            .iter()
+            .filter(|x| x.sequence() > version)
+            .map(|x| self.load_event(x))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        trace!(
+            "loaded {} events after version {} for aggregate '{}' \
+             of type '{}'",
+            result.len(),
+            version,
+            aggregate_id,
+            A::aggregate_type()
+        );
+
+        Ok(result)
+    }
+
+    fn load_all_events(
+        &mut self
+    ) -> Result<Vec<EventContext<C, E>>, Error> {
+        let events = self.events.read().map_err(|e| {
+            Error::Store {
+                message: format!(
+                    "unable to read the event store: {e}"
+                ),
+                source: None,
+            }
+        })?;
+
+        let result = events
+            .log
+            .iter()
+            .filter_map(|(aggregate_id, index)| {
+                events
+                    .by_aggregate
+                    .get(aggregate_id)?
+                    .get(*index)
+                    .map(|x| self.load_event(x))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        trace!(
+            "loaded {} events of all aggregates of type '{}'",
+            result.len(),
+            A::aggregate_type()
+        );
+
+        Ok(result)
+    }
+
+    fn load_all_events_after(
+        &mut self,
+        position: i64,
+        limit: usize,
+    ) -> Result<Vec<PositionedEvent<C, E>>, Error> {
+        let events = self.events.read().map_err(|e| {
+            Error::Store {
+                message: format!(
+                    "unable to read the event store: {e}"
+                ),
+                source: None,
+            }
+        })?;
+
+        let position = position.max(0);
+        let skipped = usize::try_from(position).unwrap_or(usize::MAX);
+
+        let result = events
+            .log
+            .iter()
+            .skip(skipped)
+            .take(limit)
+            .zip((position + 1)..)
+            .filter_map(|((aggregate_id, index), x)| {
+                events
+                    .by_aggregate
+                    .get(aggregate_id)?
+                    .get(*index)
+                    .map(|event| {
+                        self.load_event(event).map(|event| {
+                            PositionedEvent::new(x, event)
+                        })
+                    })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        trace!(
+            "loaded {} events after position {} of all aggregates \
+             of type '{}'",
+            result.len(),
+            position,
+            A::aggregate_type()
+        );
+
+        Ok(result)
+    }
+
+    fn count_events_after(
+        &mut self,
+        position: i64,
+    ) -> Result<usize, Error> {
+        let events = self.events.read().map_err(|e| {
+            Error::Store {
+                message: format!(
+                    "unable to read the event store: {e}"
+                ),
+                source: None,
+            }
+        })?;
+
+        let skipped =
+            usize::try_from(position.max(0)).unwrap_or(usize::MAX);
+
+        Ok(events.log.len().saturating_sub(skipped))
+    }
+
+    fn commit(
+        &mut self,
+        events: Vec<EventContext<C, E>>,
+        expected_version: i64,
+    ) -> Result<(), Error> {
+        let aggregate_id = match events.first() {
+            None => return Ok(()),
+            Some(x) => x.aggregate_id.clone(),
+        };
+
+        let mut expected_sequence = expected_version;
+
+        for event in &events {
+            if event.aggregate_id != aggregate_id {
+                return Err(Error::TechnicalError(format!(
+                    "unable to commit events of multiple aggregates \
+                     at once: '{}' and '{}'",
+                    aggregate_id, event.aggregate_id
+                )));
+            }
+
+            expected_sequence += 1;
+
+            if event.sequence != expected_sequence {
+                return Err(Error::TechnicalError(format!(
+                    "expected event sequence {} for aggregate '{}' \
+                     but found {}",
+                    expected_sequence, aggregate_id, event.sequence
+                )));
+            }
+        }
+
+        let mut stored = self.events.write().map_err(|e| {
+            Error::Store {
+                message: format!(
+                    "unable to write to the event store: {e}"
+                ),
+                source: None,
+            }
+        })?;
+
+        // both locks are held until the commit is done, so the
+        // events and their outbox messages appear together
+        let mut outbox = match &self.outbox {
+            None => None,
+            Some(x) => Some(x.write()?),
+        };
+
+        let StoredEvents { by_aggregate, log } = &mut *stored;
+
+        let aggregate_events = by_aggregate
+            .entry(aggregate_id.clone())
+            .or_default();
+
+        let current_version = aggregate_events
+            .last()
+            .map_or(0, StoredEvent::sequence);
+
+        if current_version != expected_version {
+            return Err(Error::ConcurrencyConflict {
+                aggregate_id,
+                expected: expected_version,
+                actual: current_version,
+            });
+        }
+
+        // the events are encrypted once the commit can not be
+        // rejected anymore, so that it does not create orphaned keys
+        let (events, serialized) = self.prepare(events)?;
+
+        debug!(
+            "committing {} events for aggregate '{}' of type '{}'",
+            events.len(),
+            aggregate_id,
+            A::aggregate_type()
+        );
+
+        log.extend(
+            (aggregate_events.len()..)
+                .take(events.len())
+                .map(|x| (aggregate_id.clone(), x)),
+        );
+        aggregate_events.extend(events);
+
+        if let Some(outbox) = &mut outbox {
+            for event in serialized {
+                outbox.push(event);
+            }
+        }
+
+        Ok(())
+    }
+}
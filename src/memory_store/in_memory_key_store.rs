@@ -0,0 +1,93 @@
+use log::trace;
+use std::{
+    collections::{
+        hash_map::Entry,
+        HashMap,
+    },
+    sync::{
+        Arc,
+        RwLock,
+        RwLockWriteGuard,
+    },
+};
+
+use crate::{
+    errors::Error,
+    stores::IKeyStore,
+};
+
+/// Simple memory key store only useful for testing purposes. Cloning
+/// the store yields a handle to the same underlying keys.
+#[derive(Debug, Default, Clone)]
+pub struct InMemoryKeyStore {
+    keys: Arc<RwLock<HashMap<String, Vec<u8>>>>,
+}
+
+impl InMemoryKeyStore {
+    fn write(
+        &self
+    ) -> Result<RwLockWriteGuard<'_, HashMap<String, Vec<u8>>>, Error>
+    {
+        self.keys.write().map_err(|e| {
+            Error::Store {
+                message: format!(
+                    "unable to write to the key store: {e}"
+                ),
+                source: None,
+            }
+        })
+    }
+}
+
+impl IKeyStore for InMemoryKeyStore {
+    fn load_key(
+        &mut self,
+        subject: &str,
+    ) -> Result<Option<Vec<u8>>, Error> {
+        let keys = self.keys.read().map_err(|e| {
+            Error::Store {
+                message: format!("unable to read the key store: {e}"),
+                source: None,
+            }
+        })?;
+
+        trace!("loading key of subject '{subject}'");
+
+        Ok(keys.get(subject).cloned())
+    }
+
+    fn save_key(
+        &mut self,
+        subject: &str,
+        key: Vec<u8>,
+    ) -> Result<(), Error> {
+        trace!("saving key of subject '{subject}'");
+
+        match self.write()?.entry(subject.to_string()) {
+            Entry::Occupied(_) => {
+                Err(Error::store(
+                    &format!(
+                        "the key of subject '{subject}' already \
+                         exists"
+                    ),
+                    None,
+                ))
+            },
+            Entry::Vacant(x) => {
+                x.insert(key);
+                Ok(())
+            },
+        }
+    }
+
+    fn delete_key(
+        &mut self,
+        subject: &str,
+    ) -> Result<(), Error> {
+        trace!("deleting key of subject '{subject}'");
+
+        self.write()?.remove(subject);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,132 @@
+use chrono::{
+    DateTime,
+    Utc,
+};
+use log::trace;
+use std::{
+    collections::BTreeMap,
+    sync::{
+        Arc,
+        RwLock,
+        RwLockWriteGuard,
+    },
+};
+
+use crate::{
+    errors::Error,
+    events::SerializedEvent,
+    outbox::OutboxMessage,
+    stores::IOutboxStore,
+};
+
+/// The pending messages along with the id of the next message
+#[derive(Debug, Default)]
+pub(crate) struct OutboxMessages {
+    last_id: i64,
+    messages: BTreeMap<i64, OutboxMessage>,
+}
+
+impl OutboxMessages {
+    /// Appends a message that is due right away
+    pub(crate) fn push(
+        &mut self,
+        event: SerializedEvent,
+    ) {
+        self.last_id += 1;
+
+        self.messages.insert(
+            self.last_id,
+            OutboxMessage::new(self.last_id, event),
+        );
+    }
+}
+
+/// Simple memory outbox store only useful for testing purposes. It
+/// is filled by an `InMemoryEventStore` configured with
+/// `with_outbox`. Cloning the store yields a handle to the same
+/// underlying messages.
+#[derive(Debug, Default, Clone)]
+pub struct InMemoryOutboxStore {
+    messages: Arc<RwLock<OutboxMessages>>,
+}
+
+impl InMemoryOutboxStore {
+    /// Locks the messages for writing
+    pub(crate) fn write(
+        &self
+    ) -> Result<RwLockWriteGuard<'_, OutboxMessages>, Error> {
+        self.messages.write().map_err(|e| {
+            Error::Store {
+                message: format!(
+                    "unable to write to the outbox store: {e}"
+                ),
+                source: None,
+            }
+        })
+    }
+}
+
+impl IOutboxStore for InMemoryOutboxStore {
+    fn load_pending(
+        &mut self,
+        now: DateTime<Utc>,
+        limit: usize,
+    ) -> Result<Vec<OutboxMessage>, Error> {
+        let messages = self.messages.read().map_err(|e| {
+            Error::Store {
+                message: format!(
+                    "unable to read the outbox store: {e}"
+                ),
+                source: None,
+            }
+        })?;
+
+        let result: Vec<_> = messages
+            .messages
+            .values()
+            .take_while(|x| x.next_attempt_at <= now)
+            .take(limit)
+            .cloned()
+            .collect();
+
+        trace!(
+            "loaded {} pending outbox messages",
+            result.len()
+        );
+
+        Ok(result)
+    }
+
+    fn mark_published(
+        &mut self,
+        id: i64,
+    ) -> Result<(), Error> {
+        trace!("removing published outbox message {id}");
+
+        self.write()?.messages.remove(&id);
+
+        Ok(())
+    }
+
+    fn mark_failed(
+        &mut self,
+        id: i64,
+        next_attempt_at: DateTime<Utc>,
+        error: &str,
+    ) -> Result<(), Error> {
+        let mut messages = self.write()?;
+
+        let message = messages
+            .messages
+            .get_mut(&id)
+            .ok_or_else(|| {
+                Error::not_found("outbox message", &id.to_string())
+            })?;
+
+        message.attempts += 1;
+        message.next_attempt_at = next_attempt_at;
+        message.last_error = Some(error.to_string());
+
+        Ok(())
+    }
+}
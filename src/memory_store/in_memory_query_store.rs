@@ -0,0 +1,116 @@
+use log::trace;
+use std::{
+    collections::HashMap,
+    fmt::Debug,
+    sync::{
+        Arc,
+        RwLock,
+    },
+};
+
+use crate::{
+    commands::ICommand,
+    errors::Error,
+    events::IEvent,
+    queries::{
+        IQuery,
+        QueryContext,
+    },
+    stores::IQueryStore,
+};
+
+type LockedQueryContextMap<C, E, Q> =
+    RwLock<HashMap<String, QueryContext<C, E, Q>>>;
+
+/// Simple memory query store only useful for testing purposes.
+/// Cloning the store yields a handle to the same underlying queries.
+#[derive(Debug)]
+pub struct InMemoryQueryStore<C: ICommand, E: IEvent, Q: IQuery<C, E>>
+{
+    queries: Arc<LockedQueryContextMap<C, E, Q>>,
+}
+
+impl<C: ICommand, E: IEvent, Q: IQuery<C, E>> Default
+    for InMemoryQueryStore<C, E, Q>
+{
+    fn default() -> Self {
+        Self {
+            queries: Arc::default(),
+        }
+    }
+}
+
+impl<C: ICommand, E: IEvent, Q: IQuery<C, E>> Clone
+    for InMemoryQueryStore<C, E, Q>
+{
+    fn clone(&self) -> Self {
+        Self {
+            queries: Arc::clone(&self.queries),
+        }
+    }
+}
+
+impl<C: ICommand, E: IEvent, Q: IQuery<C, E>> IQueryStore<C, E, Q>
+    for InMemoryQueryStore<C, E, Q>
+{
+    fn load(
+        &mut self,
+        aggregate_id: &str,
+    ) -> Result<QueryContext<C, E, Q>, Error> {
+        let queries = self.queries.read().map_err(|e| {
+            Error::Store {
+                message: format!(
+                    "unable to read the query store: {e}"
+                ),
+                source: None,
+            }
+        })?;
+
+        let result = match queries.get(aggregate_id) {
+            Some(x) => x.clone(),
+            None => {
+                QueryContext::new(
+                    aggregate_id.to_string(),
+                    0,
+                    Q::default(),
+                )
+            },
+        };
+
+        trace!(
+            "loaded query of type '{}' at version {} for aggregate \
+             '{}'",
+            Q::query_type(),
+            result.version,
+            aggregate_id
+        );
+
+        Ok(result)
+    }
+
+    fn save(
+        &mut self,
+        context: QueryContext<C, E, Q>,
+    ) -> Result<(), Error> {
+        let mut queries = self.queries.write().map_err(|e| {
+            Error::Store {
+                message: format!(
+                    "unable to write to the query store: {e}"
+                ),
+                source: None,
+            }
+        })?;
+
+        trace!(
+            "saving query of type '{}' at version {} for aggregate \
+             '{}'",
+            Q::query_type(),
+            context.version,
+            context.aggregate_id
+        );
+
+        queries.insert(context.aggregate_id.clone(), context);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,150 @@
+use chrono::{
+    DateTime,
+    Utc,
+};
+use log::trace;
+use std::{
+    collections::HashMap,
+    fmt::Debug,
+    sync::{
+        Arc,
+        RwLock,
+    },
+};
+
+use crate::{
+    commands::ICommand,
+    errors::Error,
+    events::IEvent,
+    sagas::{
+        ISaga,
+        SagaContext,
+    },
+    stores::ISagaStore,
+};
+
+type LockedSagaContextMap<C, E, SC, S> =
+    RwLock<HashMap<String, SagaContext<C, E, SC, S>>>;
+
+/// Simple memory saga store only useful for testing purposes.
+/// Cloning the store yields a handle to the same underlying sagas.
+#[derive(Debug)]
+pub struct InMemorySagaStore<
+    C: ICommand,
+    E: IEvent,
+    SC: ICommand,
+    S: ISaga<C, E, SC>,
+> {
+    sagas: Arc<LockedSagaContextMap<C, E, SC, S>>,
+}
+
+impl<C: ICommand, E: IEvent, SC: ICommand, S: ISaga<C, E, SC>> Default
+    for InMemorySagaStore<C, E, SC, S>
+{
+    fn default() -> Self {
+        Self {
+            sagas: Arc::default(),
+        }
+    }
+}
+
+impl<C: ICommand, E: IEvent, SC: ICommand, S: ISaga<C, E, SC>> Clone
+    for InMemorySagaStore<C, E, SC, S>
+{
+    fn clone(&self) -> Self {
+        Self {
+            sagas: Arc::clone(&self.sagas),
+        }
+    }
+}
+
+impl<C: ICommand, E: IEvent, SC: ICommand, S: ISaga<C, E, SC>>
+    ISagaStore<C, E, SC, S> for InMemorySagaStore<C, E, SC, S>
+{
+    fn load(
+        &mut self,
+        correlation_id: &str,
+    ) -> Result<SagaContext<C, E, SC, S>, Error> {
+        let sagas = self.sagas.read().map_err(|e| {
+            Error::Store {
+                message: format!(
+                    "unable to read the saga store: {e}"
+                ),
+                source: None,
+            }
+        })?;
+
+        let result = match sagas.get(correlation_id) {
+            Some(x) => x.clone(),
+            None => {
+                SagaContext::new(
+                    correlation_id.to_string(),
+                    S::default(),
+                )
+            },
+        };
+
+        trace!(
+            "loaded saga of type '{}' for correlation id '{}'",
+            S::saga_type(),
+            correlation_id
+        );
+
+        Ok(result)
+    }
+
+    fn load_due(
+        &mut self,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<SagaContext<C, E, SC, S>>, Error> {
+        let sagas = self.sagas.read().map_err(|e| {
+            Error::Store {
+                message: format!(
+                    "unable to read the saga store: {e}"
+                ),
+                source: None,
+            }
+        })?;
+
+        let mut result: Vec<_> = sagas
+            .values()
+            .filter(|x| x.timeouts.values().any(|x| *x <= now))
+            .cloned()
+            .collect();
+
+        result
+            .sort_by(|a, b| a.correlation_id.cmp(&b.correlation_id));
+
+        trace!(
+            "loaded {} sagas of type '{}' with due timeouts",
+            result.len(),
+            S::saga_type()
+        );
+
+        Ok(result)
+    }
+
+    fn save(
+        &mut self,
+        context: SagaContext<C, E, SC, S>,
+    ) -> Result<(), Error> {
+        let mut sagas = self.sagas.write().map_err(|e| {
+            Error::Store {
+                message: format!(
+                    "unable to write to the saga store: {e}"
+                ),
+                source: None,
+            }
+        })?;
+
+        trace!(
+            "saving saga of type '{}' for correlation id '{}'",
+            S::saga_type(),
+            context.correlation_id
+        );
+
+        sagas.insert(context.correlation_id.clone(), context);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,149 @@
+use log::trace;
+use std::{
+    collections::HashMap,
+    fmt::Debug,
+    marker::PhantomData,
+    sync::{
+        Arc,
+        RwLock,
+    },
+};
+
+use crate::{
+    aggregates::{
+        AggregateContext,
+        IAggregate,
+    },
+    commands::ICommand,
+    errors::Error,
+    events::IEvent,
+    stores::ISnapshotStore,
+};
+
+type LockedSnapshotMap =
+    RwLock<HashMap<String, (i64, serde_json::Value)>>;
+
+/// Simple memory snapshot store only useful for testing purposes.
+/// Snapshots are kept in their serialized form, exactly like a
+/// persistent store would keep them. Cloning the store yields a
+/// handle to the same underlying snapshots.
+#[derive(Debug)]
+pub struct InMemorySnapshotStore<
+    C: ICommand,
+    E: IEvent,
+    A: IAggregate<C, E>,
+> {
+    snapshots: Arc<LockedSnapshotMap>,
+    _phantom: PhantomData<(C, E, A)>,
+}
+
+impl<C: ICommand, E: IEvent, A: IAggregate<C, E>> Default
+    for InMemorySnapshotStore<C, E, A>
+{
+    fn default() -> Self {
+        Self {
+            snapshots: Arc::default(),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<C: ICommand, E: IEvent, A: IAggregate<C, E>> Clone
+    for InMemorySnapshotStore<C, E, A>
+{
+    fn clone(&self) -> Self {
+        Self {
+            snapshots: Arc::clone(&self.snapshots),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<C: ICommand, E: IEvent, A: IAggregate<C, E>>
+    ISnapshotStore<C, E, A> for InMemorySnapshotStore<C, E, A>
+{
+    fn load_snapshot(
+        &mut self,
+        aggregate_id: &str,
+    ) -> Result<Option<AggregateContext<C, E, A>>, Error> {
+        let snapshots = self.snapshots.read().map_err(|e| {
+            Error::Store {
+                message: format!(
+                    "unable to read the snapshot store: {e}"
+                ),
+                source: None,
+            }
+        })?;
+
+        let (version, payload) = match snapshots.get(aggregate_id) {
+            None => return Ok(None),
+            Some(x) => x.clone(),
+        };
+
+        let payload =
+            serde_json::from_value(payload).map_err(|e| {
+                Error::Serialization {
+                    message: format!(
+                        "unable to deserialize the snapshot of \
+                         aggregate '{aggregate_id}': {e}"
+                    ),
+                    source: Some(e.into()),
+                }
+            })?;
+
+        trace!(
+            "loaded snapshot at version {} for aggregate '{}' of \
+             type '{}'",
+            version,
+            aggregate_id,
+            A::aggregate_type()
+        );
+
+        Ok(Some(AggregateContext::new(
+            aggregate_id.to_string(),
+            version,
+            payload,
+        )))
+    }
+
+    fn save_snapshot(
+        &mut self,
+        context: &AggregateContext<C, E, A>,
+    ) -> Result<(), Error> {
+        let payload = serde_json::to_value(&context.payload)
+            .map_err(|e| {
+                Error::Serialization {
+                    message: format!(
+                        "unable to serialize the snapshot of \
+                         aggregate '{}': {}",
+                        context.aggregate_id, e
+                    ),
+                    source: Some(e.into()),
+                }
+            })?;
+
+        let mut snapshots = self.snapshots.write().map_err(|e| {
+            Error::Store {
+                message: format!(
+                    "unable to write to the snapshot store: {e}"
+                ),
+                source: None,
+            }
+        })?;
+
+        trace!(
+            "saving snapshot at version {} for aggregate '{}' of \
+             type '{}'",
+            context.version,
+            context.aggregate_id,
+            A::aggregate_type()
+        );
+
+        snapshots.insert(
+            context.aggregate_id.clone(),
+            (context.version, payload),
+        );
+
+        Ok(())
+    }
+}
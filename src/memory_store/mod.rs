@@ -0,0 +1,27 @@
+//! # memory_store
+//!
+//! In-memory implementations of the store interfaces, mostly useful
+//! for testing and prototyping
+
+pub use in_memory_checkpoint_store::InMemoryCheckpointStore;
+pub use in_memory_command_id_store::InMemoryCommandIdStore;
+pub use in_memory_event_store::InMemoryEventStore;
+#[cfg(feature = "crypto")]
+pub use in_memory_key_store::InMemoryKeyStore;
+pub use in_memory_outbox_store::InMemoryOutboxStore;
+pub use in_memory_query_store::InMemoryQueryStore;
+pub use in_memory_saga_store::InMemorySagaStore;
+pub use in_memory_snapshot_store::InMemorySnapshotStore;
+
+mod in_memory_checkpoint_store;
+mod in_memory_command_id_store;
+mod in_memory_event_store;
+#[cfg(feature = "crypto")]
+mod in_memory_key_store;
+mod in_memory_outbox_store;
+mod in_memory_query_store;
+mod in_memory_saga_store;
+mod in_memory_snapshot_store;
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,473 @@
+#[cfg(feature = "crypto")]
+use chrono::Utc;
+
+use crate::{
+    example_impl::*,
+    store_conformance::fixtures::{
+        email_updated,
+        name_added,
+    },
+    AggregateContext,
+    ICheckpointStore,
+    IEventStore,
+    ISnapshotStore,
+    PositionedEvent,
+};
+#[cfg(feature = "crypto")]
+use crate::{
+    CryptoShredder,
+    Error,
+    IKeyStore,
+    IOutboxStore,
+    InMemoryKeyStore,
+    InMemoryOutboxStore,
+    ENCRYPTED_FIELD_KEY,
+};
+
+use super::{
+    in_memory_checkpoint_store::InMemoryCheckpointStore,
+    in_memory_event_store::InMemoryEventStore,
+    in_memory_snapshot_store::InMemorySnapshotStore,
+};
+
+type ThisEventStore =
+    InMemoryEventStore<CustomerCommand, CustomerEvent, Customer>;
+
+type ThisSnapshotStore =
+    InMemorySnapshotStore<CustomerCommand, CustomerEvent, Customer>;
+
+#[test]
+fn test_load_empty_aggregate() {
+    let mut store = ThisEventStore::default();
+
+    assert_eq!(
+        store.load_events("test_id_A").unwrap(),
+        Vec::new()
+    );
+
+    let context = store
+        .load_aggregate("test_id_A")
+        .unwrap();
+
+    assert_eq!(context.aggregate_id, "test_id_A");
+    assert_eq!(context.version, 0);
+    assert_eq!(context.payload, Customer::default());
+}
+
+#[test]
+fn test_commit_and_load() {
+    let mut store = ThisEventStore::default();
+
+    store
+        .commit(
+            vec![
+                name_added("test_id_A", 1, "John Doe"),
+                email_updated("test_id_A", 2, "j@d.com"),
+            ],
+            0,
+        )
+        .unwrap();
+
+    store
+        .commit(
+            vec![name_added("test_id_B", 1, "Jane Doe")],
+            0,
+        )
+        .unwrap();
+
+    store
+        .commit(
+            vec![email_updated(
+                "test_id_A",
+                3,
+                "john@d.com",
+            )],
+            2,
+        )
+        .unwrap();
+
+    assert_eq!(
+        store.load_events("test_id_A").unwrap(),
+        vec![
+            name_added("test_id_A", 1, "John Doe"),
+            email_updated("test_id_A", 2, "j@d.com"),
+            email_updated("test_id_A", 3, "john@d.com"),
+        ]
+    );
+
+    let context = store
+        .load_aggregate("test_id_A")
+        .unwrap();
+
+    assert_eq!(context.version, 3);
+    assert_eq!(
+        context.payload,
+        Customer {
+            customer_id: String::new(),
+            name: "John Doe".to_string(),
+            email: "john@d.com".to_string(),
+            addresses: Vec::new(),
+        }
+    );
+
+    // clones share the same events
+    let mut other = store.clone();
+
+    assert_eq!(
+        other
+            .load_aggregate("test_id_B")
+            .unwrap()
+            .version,
+        1
+    );
+}
+
+#[test]
+fn test_load_all_events() {
+    let mut store = ThisEventStore::default();
+
+    assert_eq!(
+        store.load_all_events().unwrap(),
+        Vec::new()
+    );
+
+    store
+        .commit(
+            vec![name_added("test_id_A", 1, "John Doe")],
+            0,
+        )
+        .unwrap();
+
+    store
+        .commit(
+            vec![name_added("test_id_B", 1, "Jane Doe")],
+            0,
+        )
+        .unwrap();
+
+    store
+        .commit(
+            vec![
+                email_updated("test_id_A", 2, "j@d.com"),
+                email_updated("test_id_A", 3, "john@d.com"),
+            ],
+            1,
+        )
+        .unwrap();
+
+    // a rejected commit is not part of the global order
+    assert!(store
+        .commit(
+            vec![email_updated(
+                "test_id_B",
+                3,
+                "jane@d.com"
+            )],
+            2,
+        )
+        .is_err());
+
+    assert_eq!(
+        store.load_all_events().unwrap(),
+        vec![
+            name_added("test_id_A", 1, "John Doe"),
+            name_added("test_id_B", 1, "Jane Doe"),
+            email_updated("test_id_A", 2, "j@d.com"),
+            email_updated("test_id_A", 3, "john@d.com"),
+        ]
+    );
+}
+
+#[test]
+fn test_load_all_events_after() {
+    let mut store = ThisEventStore::default();
+
+    store
+        .commit(
+            vec![
+                name_added("test_id_A", 1, "John Doe"),
+                email_updated("test_id_A", 2, "j@d.com"),
+            ],
+            0,
+        )
+        .unwrap();
+
+    store
+        .commit(
+            vec![name_added("test_id_B", 1, "Jane Doe")],
+            0,
+        )
+        .unwrap();
+
+    let events = store
+        .load_all_events_after(1, 10)
+        .unwrap();
+
+    assert_eq!(
+        events,
+        vec![
+            PositionedEvent::new(
+                2,
+                email_updated("test_id_A", 2, "j@d.com")
+            ),
+            PositionedEvent::new(
+                3,
+                name_added("test_id_B", 1, "Jane Doe")
+            ),
+        ]
+    );
+
+    assert_eq!(
+        store
+            .load_all_events_after(0, 1)
+            .unwrap(),
+        vec![PositionedEvent::new(
+            1,
+            name_added("test_id_A", 1, "John Doe")
+        )]
+    );
+    assert_eq!(
+        store
+            .load_all_events_after(3, 10)
+            .unwrap(),
+        Vec::new()
+    );
+    assert_eq!(store.count_events_after(0).unwrap(), 3);
+    assert_eq!(store.count_events_after(1).unwrap(), 2);
+    assert_eq!(store.count_events_after(3).unwrap(), 0);
+}
+
+#[test]
+fn test_checkpoint_store() {
+    let mut store = InMemoryCheckpointStore::default();
+
+    assert_eq!(store.load("subscriber").unwrap(), 0);
+
+    store.save("subscriber", 3).unwrap();
+    store.save("other", 1).unwrap();
+
+    assert_eq!(
+        store
+            .clone()
+            .load("subscriber")
+            .unwrap(),
+        3
+    );
+    assert_eq!(store.load("other").unwrap(), 1);
+}
+
+#[test]
+fn test_commit_wrong_version() {
+    let mut store = ThisEventStore::default();
+
+    store
+        .commit(
+            vec![name_added("test_id_A", 1, "John Doe")],
+            0,
+        )
+        .unwrap();
+
+    assert!(store
+        .commit(
+            vec![email_updated("test_id_A", 1, "j@d.com")],
+            0,
+        )
+        .is_err());
+
+    assert!(store
+        .commit(
+            vec![email_updated("test_id_A", 3, "j@d.com")],
+            1,
+        )
+        .is_err());
+
+    assert_eq!(
+        store
+            .load_events("test_id_A")
+            .unwrap()
+            .len(),
+        1
+    );
+}
+
+#[test]
+fn test_load_aggregate_from_snapshot() {
+    let mut store = ThisEventStore::default();
+
+    store
+        .commit(
+            vec![
+                name_added("test_id_A", 1, "John Doe"),
+                email_updated("test_id_A", 2, "j@d.com"),
+                email_updated("test_id_A", 3, "john@d.com"),
+            ],
+            0,
+        )
+        .unwrap();
+
+    assert_eq!(
+        store
+            .load_events_after("test_id_A", 1)
+            .unwrap(),
+        vec![
+            email_updated("test_id_A", 2, "j@d.com"),
+            email_updated("test_id_A", 3, "john@d.com"),
+        ]
+    );
+
+    // only the events after the snapshot version are replayed
+    let snapshot = AggregateContext::new(
+        "test_id_A".to_string(),
+        2,
+        Customer {
+            customer_id: String::new(),
+            name: "Snapshot Doe".to_string(),
+            email: String::new(),
+            addresses: Vec::new(),
+        },
+    );
+
+    let context = store
+        .load_aggregate_from(snapshot)
+        .unwrap();
+
+    assert_eq!(context.version, 3);
+    assert_eq!(context.payload.name, "Snapshot Doe");
+    assert_eq!(context.payload.email, "john@d.com");
+}
+
+#[test]
+fn test_snapshot_store() {
+    let mut store = ThisSnapshotStore::default();
+
+    assert_eq!(
+        store
+            .load_snapshot("test_id_A")
+            .unwrap(),
+        None
+    );
+
+    let mut context = AggregateContext::new(
+        "test_id_A".to_string(),
+        2,
+        Customer {
+            customer_id: "test_id_A".to_string(),
+            name: "John Doe".to_string(),
+            email: "j@d.com".to_string(),
+            addresses: vec!["home".to_string()],
+        },
+    );
+
+    store.save_snapshot(&context).unwrap();
+
+    assert_eq!(
+        store
+            .load_snapshot("test_id_A")
+            .unwrap(),
+        Some(context.clone())
+    );
+
+    context.version = 5;
+    context
+        .payload
+        .addresses
+        .push("work".to_string());
+
+    store.save_snapshot(&context).unwrap();
+
+    assert_eq!(
+        store
+            .clone()
+            .load_snapshot("test_id_A")
+            .unwrap(),
+        Some(context)
+    );
+    assert_eq!(
+        store
+            .load_snapshot("test_id_B")
+            .unwrap(),
+        None
+    );
+}
+
+#[cfg(feature = "crypto")]
+#[test]
+fn test_shredder_encrypts_outbox() {
+    let outbox = InMemoryOutboxStore::default();
+    let mut store = ThisEventStore::default()
+        .with_outbox(outbox.clone())
+        .with_shredder(
+            CryptoShredder::new(InMemoryKeyStore::default())
+                .with_encrypted_field("EmailUpdated", "new_email"),
+        );
+
+    let events = vec![
+        name_added("test_id_A", 1, "John Doe"),
+        email_updated("test_id_A", 2, "john@example.com"),
+    ];
+
+    store.commit(events.clone(), 0).unwrap();
+
+    let messages = outbox
+        .clone()
+        .load_pending(Utc::now(), 10)
+        .unwrap();
+
+    assert_eq!(
+        messages[0].event.payload["changed_name"],
+        "John Doe"
+    );
+    assert!(messages[1].event.payload["new_email"]
+        [ENCRYPTED_FIELD_KEY]
+        .is_string());
+
+    assert_eq!(
+        store.load_events("test_id_A").unwrap(),
+        events
+    );
+}
+
+#[cfg(feature = "crypto")]
+#[test]
+fn test_rejected_commit_creates_no_key() {
+    let key_store = InMemoryKeyStore::default();
+    let mut store = ThisEventStore::default().with_shredder(
+        CryptoShredder::new(key_store.clone())
+            .with_encrypted_field("EmailUpdated", "new_email"),
+    );
+
+    assert!(matches!(
+        store
+            .commit(
+                vec![email_updated(
+                    "test_id_A",
+                    2,
+                    "john@example.com"
+                )],
+                1
+            )
+            .unwrap_err(),
+        Error::ConcurrencyConflict { .. }
+    ));
+
+    let mut keys = key_store;
+    assert!(keys
+        .load_key("test_id_A")
+        .unwrap()
+        .is_none());
+
+    // a key is never replaced, even if created concurrently
+    keys.save_key("test_id_A", vec![1; 32])
+        .unwrap();
+
+    assert_eq!(
+        keys.save_key("test_id_A", vec![2; 32])
+            .unwrap_err()
+            .code(),
+        "store_error"
+    );
+    assert_eq!(
+        keys.load_key("test_id_A").unwrap(),
+        Some(vec![1; 32])
+    );
+}
@@ -0,0 +1,41 @@
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::commands::ICommand;
+
+use super::{
+    i_metadata_enricher::IMetadataEnricher,
+    metadata_keys::{
+        CAUSATION_ID_KEY,
+        COMMAND_ID_KEY,
+    },
+};
+
+/// Records the id of the command that caused the events under
+/// `CAUSATION_ID_KEY`. Callers reacting to an earlier message pass
+/// its id, otherwise the id of the command is used when supplied
+/// under `COMMAND_ID_KEY`, or a new id is generated for it.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct CausationIdEnricher;
+
+impl<C: ICommand> IMetadataEnricher<C> for CausationIdEnricher {
+    fn enrich(
+        &self,
+        _command: &C,
+        metadata: &mut HashMap<String, String>,
+    ) {
+        if metadata.contains_key(CAUSATION_ID_KEY) {
+            return;
+        }
+
+        let causation_id = match metadata.get(COMMAND_ID_KEY) {
+            None => Uuid::new_v4().to_string(),
+            Some(x) => x.clone(),
+        };
+
+        metadata.insert(
+            CAUSATION_ID_KEY.to_string(),
+            causation_id,
+        );
+    }
+}
@@ -0,0 +1,30 @@
+use std::collections::HashMap;
+
+use crate::commands::ICommand;
+
+use super::{
+    i_metadata_enricher::IMetadataEnricher,
+    metadata_keys::COMMAND_NAME_KEY,
+};
+
+/// Records the name of the command, e.g., `AddCustomerName`, under
+/// `COMMAND_NAME_KEY`, see `ICommand::command_name`.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct CommandNameEnricher;
+
+impl<C: ICommand> IMetadataEnricher<C> for CommandNameEnricher {
+    fn enrich(
+        &self,
+        command: &C,
+        metadata: &mut HashMap<String, String>,
+    ) {
+        if metadata.contains_key(COMMAND_NAME_KEY) {
+            return;
+        }
+
+        metadata.insert(
+            COMMAND_NAME_KEY.to_string(),
+            command.command_name().to_string(),
+        );
+    }
+}
@@ -0,0 +1,41 @@
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::commands::ICommand;
+
+use super::{
+    i_metadata_enricher::IMetadataEnricher,
+    metadata_keys::{
+        CAUSATION_ID_KEY,
+        CORRELATION_ID_KEY,
+    },
+};
+
+/// Records the id of the business flow under `CORRELATION_ID_KEY`.
+/// A command that starts a new flow is correlated with itself, i.e.,
+/// the causation id is reused when present, otherwise a new id is
+/// generated.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct CorrelationIdEnricher;
+
+impl<C: ICommand> IMetadataEnricher<C> for CorrelationIdEnricher {
+    fn enrich(
+        &self,
+        _command: &C,
+        metadata: &mut HashMap<String, String>,
+    ) {
+        if metadata.contains_key(CORRELATION_ID_KEY) {
+            return;
+        }
+
+        let correlation_id = match metadata.get(CAUSATION_ID_KEY) {
+            None => Uuid::new_v4().to_string(),
+            Some(x) => x.clone(),
+        };
+
+        metadata.insert(
+            CORRELATION_ID_KEY.to_string(),
+            correlation_id,
+        );
+    }
+}
@@ -0,0 +1,46 @@
+use std::collections::HashMap;
+
+use crate::commands::ICommand;
+
+/// An `IMetadataEnricher` adds information to the metadata of the
+/// events produced by a command. The enrichers registered on the
+/// framework run once per command, before the events are wrapped
+/// into their `EventContext`s, so every event of a command shares
+/// the same metadata.
+///
+/// By convention, an enricher never overwrites a key that is already
+/// present, which lets callers supply their own values through
+/// `execute_with_metadata`.
+///
+/// # Examples
+/// ```rust
+/// use std::collections::HashMap;
+///
+/// use cqrs_es2::{
+///     example_impl::CustomerCommand,
+///     IMetadataEnricher,
+/// };
+///
+/// struct TenantEnricher;
+///
+/// impl IMetadataEnricher<CustomerCommand> for TenantEnricher {
+///     fn enrich(
+///         &self,
+///         _command: &CustomerCommand,
+///         metadata: &mut HashMap<String, String>,
+///     ) {
+///         metadata
+///             .entry("tenant".to_string())
+///             .or_insert_with(|| "acme".to_string());
+///     }
+/// }
+/// ```
+pub trait IMetadataEnricher<C: ICommand>: Send + Sync {
+    /// Adds entries to `metadata` for the events produced by
+    /// `command`
+    fn enrich(
+        &self,
+        command: &C,
+        metadata: &mut HashMap<String, String>,
+    );
+}
@@ -0,0 +1,120 @@
+use std::{
+    collections::HashMap,
+    fmt::{
+        Debug,
+        Formatter,
+        Result as fmtResult,
+    },
+};
+
+use crate::commands::ICommand;
+
+use super::{
+    causation_id_enricher::CausationIdEnricher,
+    command_name_enricher::CommandNameEnricher,
+    correlation_id_enricher::CorrelationIdEnricher,
+    i_metadata_enricher::IMetadataEnricher,
+    timestamp_enricher::TimestampEnricher,
+};
+
+/// An ordered chain of `IMetadataEnricher`s. Enrichers run in the
+/// order they were registered, so later enrichers can build on the
+/// entries added by earlier ones.
+///
+/// # Examples
+/// ```rust
+/// use std::collections::HashMap;
+///
+/// use cqrs_es2::{
+///     example_impl::{
+///         AddCustomerName,
+///         CustomerCommand,
+///     },
+///     MetadataEnrichers,
+///     PrincipalEnricher,
+///     COMMAND_NAME_KEY,
+///     PRINCIPAL_KEY,
+/// };
+///
+/// let enrichers = MetadataEnrichers::standard()
+///     .with_enricher(PrincipalEnricher::new("admin"));
+///
+/// let mut metadata = HashMap::new();
+///
+/// enrichers.enrich(
+///     &CustomerCommand::AddCustomerName(AddCustomerName {
+///         changed_name: "John Doe".to_string(),
+///     }),
+///     &mut metadata,
+/// );
+///
+/// assert_eq!(
+///     metadata[COMMAND_NAME_KEY],
+///     "AddCustomerName"
+/// );
+/// assert_eq!(metadata[PRINCIPAL_KEY], "admin");
+/// ```
+pub struct MetadataEnrichers<C: ICommand> {
+    enrichers: Vec<Box<dyn IMetadataEnricher<C>>>,
+}
+
+impl<C: ICommand> Default for MetadataEnrichers<C> {
+    fn default() -> Self {
+        Self {
+            enrichers: Vec::new(),
+        }
+    }
+}
+
+impl<C: ICommand> MetadataEnrichers<C> {
+    /// Constructor of the built-in chain recording the timestamp,
+    /// causation id, correlation id and command name
+    #[must_use]
+    pub fn standard() -> Self {
+        Self::default()
+            .with_enricher(TimestampEnricher)
+            .with_enricher(CausationIdEnricher)
+            .with_enricher(CorrelationIdEnricher)
+            .with_enricher(CommandNameEnricher)
+    }
+
+    /// Appends an enricher to the chain
+    pub fn register(
+        &mut self,
+        enricher: Box<dyn IMetadataEnricher<C>>,
+    ) {
+        self.enrichers.push(enricher);
+    }
+
+    /// Builder variant of `register`
+    #[must_use]
+    pub fn with_enricher<M: IMetadataEnricher<C> + 'static>(
+        mut self,
+        enricher: M,
+    ) -> Self {
+        self.register(Box::new(enricher));
+        self
+    }
+
+    /// Runs every enricher of the chain on `metadata`
+    pub fn enrich(
+        &self,
+        command: &C,
+        metadata: &mut HashMap<String, String>,
+    ) {
+        for enricher in &self.enrichers {
+            enricher.enrich(command, metadata);
+        }
+    }
+}
+
+impl<C: ICommand> Debug for MetadataEnrichers<C> {
+    fn fmt(
+        &self,
+        f: &mut Formatter<'_>,
+    ) -> fmtResult {
+        f.debug_struct("MetadataEnrichers")
+            .field("enrichers", &self.enrichers.len())
+            .finish()
+    }
+}
@@ -0,0 +1,25 @@
+/// Metadata key of the UTC time at which the events were produced,
+/// formatted as RFC 3339
+pub const TIMESTAMP_KEY: &str = "timestamp";
+
+/// Metadata key of the id shared by every message of the same
+/// business flow
+pub const CORRELATION_ID_KEY: &str = "correlation_id";
+
+/// Metadata key of the id of the message that caused the events
+pub const CAUSATION_ID_KEY: &str = "causation_id";
+
+/// Metadata key of the id supplied by the caller to make the
+/// execution of a command idempotent
+pub const COMMAND_ID_KEY: &str = "command_id";
+
+/// Metadata key of the name of the command that produced the events
+pub const COMMAND_NAME_KEY: &str = "command_name";
+
+/// Metadata key of the user or service on whose behalf the command
+/// was executed
+pub const PRINCIPAL_KEY: &str = "principal";
+
+/// Metadata key of the subject whose key encrypts the personal data
+/// of the events, the aggregate id when missing
+pub const SUBJECT_ID_KEY: &str = "subject_id";
@@ -0,0 +1,32 @@
+//! # metadata
+//!
+//! A central location for event metadata enrichment
+
+pub use causation_id_enricher::CausationIdEnricher;
+pub use command_name_enricher::CommandNameEnricher;
+pub use correlation_id_enricher::CorrelationIdEnricher;
+pub use i_metadata_enricher::IMetadataEnricher;
+pub use metadata_enrichers::MetadataEnrichers;
+pub use metadata_keys::{
+    CAUSATION_ID_KEY,
+    COMMAND_ID_KEY,
+    COMMAND_NAME_KEY,
+    CORRELATION_ID_KEY,
+    PRINCIPAL_KEY,
+    SUBJECT_ID_KEY,
+    TIMESTAMP_KEY,
+};
+pub use principal_enricher::PrincipalEnricher;
+pub use timestamp_enricher::TimestampEnricher;
+
+mod causation_id_enricher;
+mod command_name_enricher;
+mod correlation_id_enricher;
+mod i_metadata_enricher;
+mod metadata_enrichers;
+mod metadata_keys;
+mod principal_enricher;
+mod timestamp_enricher;
+
+#[cfg(test)]
+mod test;
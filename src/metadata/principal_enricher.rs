@@ -0,0 +1,71 @@
+use std::{
+    collections::HashMap,
+    fmt::{
+        Debug,
+        Formatter,
+        Result as fmtResult,
+    },
+};
+
+use crate::commands::ICommand;
+
+use super::{
+    i_metadata_enricher::IMetadataEnricher,
+    metadata_keys::PRINCIPAL_KEY,
+};
+
+type PrincipalProvider = dyn Fn() -> Option<String> + Send + Sync;
+
+/// Records the user or service executing the command under
+/// `PRINCIPAL_KEY`. The principal is either fixed, e.g., for a
+/// background worker, or resolved by a caller-supplied function for
+/// every command, e.g., from the current request.
+pub struct PrincipalEnricher {
+    provider: Box<PrincipalProvider>,
+}
+
+impl PrincipalEnricher {
+    /// Constructor of an enricher recording a fixed principal
+    #[must_use]
+    pub fn new(principal: &str) -> Self {
+        let principal = principal.to_string();
+
+        Self::from_fn(move || Some(principal.clone()))
+    }
+
+    /// Constructor of an enricher resolving the principal for every
+    /// command. Nothing is recorded when `provider` returns `None`.
+    pub fn from_fn<F>(provider: F) -> Self
+    where
+        F: Fn() -> Option<String> + Send + Sync + 'static, {
+        Self {
+            provider: Box::new(provider),
+        }
+    }
+}
+
+impl Debug for PrincipalEnricher {
+    fn fmt(
+        &self,
+        f: &mut Formatter<'_>,
+    ) -> fmtResult {
+        f.debug_struct("PrincipalEnricher")
+            .finish_non_exhaustive()
+    }
+}
+
+impl<C: ICommand> IMetadataEnricher<C> for PrincipalEnricher {
+    fn enrich(
+        &self,
+        _command: &C,
+        metadata: &mut HashMap<String, String>,
+    ) {
+        if metadata.contains_key(PRINCIPAL_KEY) {
+            return;
+        }
+
+        if let Some(x) = (self.provider)() {
+            metadata.insert(PRINCIPAL_KEY.to_string(), x);
+        }
+    }
+}
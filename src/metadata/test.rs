@@ -0,0 +1,199 @@
+use std::collections::HashMap;
+
+use crate::{
+    example_impl::*,
+    EventContext,
+};
+
+use super::{
+    causation_id_enricher::CausationIdEnricher,
+    command_name_enricher::CommandNameEnricher,
+    correlation_id_enricher::CorrelationIdEnricher,
+    i_metadata_enricher::IMetadataEnricher,
+    metadata_enrichers::MetadataEnrichers,
+    metadata_keys::*,
+    principal_enricher::PrincipalEnricher,
+    timestamp_enricher::TimestampEnricher,
+};
+
+fn add_customer_name() -> CustomerCommand {
+    CustomerCommand::AddCustomerName(AddCustomerName {
+        changed_name: "John Doe".to_string(),
+    })
+}
+
+fn enrich<M: IMetadataEnricher<CustomerCommand>>(
+    enricher: &M,
+    metadata: &[(&str, &str)],
+) -> HashMap<String, String> {
+    let mut metadata: HashMap<_, _> = metadata
+        .iter()
+        .map(|&(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+
+    enricher.enrich(&add_customer_name(), &mut metadata);
+
+    metadata
+}
+
+#[test]
+fn test_timestamp_enricher() {
+    let metadata = enrich(&TimestampEnricher, &[]);
+
+    let context = EventContext::<CustomerCommand, CustomerEvent>::new(
+        "test_id_A".to_string(),
+        1,
+        CustomerEvent::NameAdded(NameAdded {
+            changed_name: "John Doe".to_string(),
+        }),
+        metadata,
+    );
+
+    assert!(context.timestamp().is_some());
+
+    let metadata = enrich(
+        &TimestampEnricher,
+        &[(TIMESTAMP_KEY, "2021-08-01T10:00:00Z")],
+    );
+
+    assert_eq!(
+        metadata[TIMESTAMP_KEY],
+        "2021-08-01T10:00:00Z"
+    );
+}
+
+#[test]
+fn test_causation_and_correlation_enrichers() {
+    let enrichers = MetadataEnrichers::default()
+        .with_enricher(CausationIdEnricher)
+        .with_enricher(CorrelationIdEnricher);
+
+    let mut metadata = HashMap::new();
+    enrichers.enrich(&add_customer_name(), &mut metadata);
+
+    // a new flow is correlated with the command that started it
+    assert_eq!(metadata[CAUSATION_ID_KEY].len(), 36);
+    assert_eq!(
+        metadata[CORRELATION_ID_KEY],
+        metadata[CAUSATION_ID_KEY]
+    );
+
+    let mut metadata = HashMap::new();
+    metadata.insert(
+        CAUSATION_ID_KEY.to_string(),
+        "event-1".to_string(),
+    );
+    metadata.insert(
+        CORRELATION_ID_KEY.to_string(),
+        "flow-1".to_string(),
+    );
+    enrichers.enrich(&add_customer_name(), &mut metadata);
+
+    assert_eq!(metadata[CAUSATION_ID_KEY], "event-1");
+    assert_eq!(metadata[CORRELATION_ID_KEY], "flow-1");
+
+    let metadata = enrich(&CorrelationIdEnricher, &[]);
+
+    assert_eq!(metadata[CORRELATION_ID_KEY].len(), 36);
+
+    // a dispatched command is the cause of its events
+    let metadata = enrich(
+        &CausationIdEnricher,
+        &[(COMMAND_ID_KEY, "command-1")],
+    );
+
+    assert_eq!(metadata[CAUSATION_ID_KEY], "command-1");
+}
+
+#[test]
+fn test_command_name_enricher() {
+    let metadata = enrich(&CommandNameEnricher, &[]);
+
+    assert_eq!(
+        metadata[COMMAND_NAME_KEY],
+        "AddCustomerName"
+    );
+
+    let metadata = enrich(
+        &CommandNameEnricher,
+        &[(COMMAND_NAME_KEY, "RenameCustomer")],
+    );
+
+    assert_eq!(
+        metadata[COMMAND_NAME_KEY],
+        "RenameCustomer"
+    );
+}
+
+#[test]
+fn test_principal_enricher() {
+    let metadata = enrich(&PrincipalEnricher::new("admin"), &[]);
+    assert_eq!(metadata[PRINCIPAL_KEY], "admin");
+
+    let metadata = enrich(
+        &PrincipalEnricher::new("admin"),
+        &[(PRINCIPAL_KEY, "tester")],
+    );
+    assert_eq!(metadata[PRINCIPAL_KEY], "tester");
+
+    let metadata = enrich(
+        &PrincipalEnricher::from_fn(|| None),
+        &[],
+    );
+    assert_eq!(metadata, HashMap::new());
+}
+
+#[test]
+fn test_standard_enrichers() {
+    let mut metadata = HashMap::new();
+
+    MetadataEnrichers::standard()
+        .enrich(&add_customer_name(), &mut metadata);
+
+    let mut keys: Vec<_> = metadata.keys().cloned().collect();
+    keys.sort();
+
+    assert_eq!(
+        keys,
+        vec![
+            CAUSATION_ID_KEY,
+            COMMAND_NAME_KEY,
+            CORRELATION_ID_KEY,
+            TIMESTAMP_KEY,
+        ]
+    );
+}
+
+#[test]
+fn test_typed_accessors() {
+    let metadata = enrich(
+        &PrincipalEnricher::new("admin"),
+        &[
+            (CORRELATION_ID_KEY, "flow-1"),
+            (CAUSATION_ID_KEY, "command-1"),
+            (COMMAND_NAME_KEY, "AddCustomerName"),
+            (TIMESTAMP_KEY, "not a timestamp"),
+        ],
+    );
+
+    let context = EventContext::<CustomerCommand, CustomerEvent>::new(
+        "test_id_A".to_string(),
+        1,
+        CustomerEvent::NameAdded(NameAdded {
+            changed_name: "John Doe".to_string(),
+        }),
+        metadata,
+    );
+
+    assert_eq!(context.correlation_id(), Some("flow-1"));
+    assert_eq!(
+        context.causation_id(),
+        Some("command-1")
+    );
+    assert_eq!(
+        context.command_name(),
+        Some("AddCustomerName")
+    );
+    assert_eq!(context.principal(), Some("admin"));
+    assert_eq!(context.timestamp(), None);
+}
@@ -0,0 +1,31 @@
+use chrono::{
+    SecondsFormat,
+    Utc,
+};
+use std::collections::HashMap;
+
+use crate::commands::ICommand;
+
+use super::{
+    i_metadata_enricher::IMetadataEnricher,
+    metadata_keys::TIMESTAMP_KEY,
+};
+
+/// Records the current UTC time under `TIMESTAMP_KEY`
+#[derive(Debug, Default, Clone, Copy)]
+pub struct TimestampEnricher;
+
+impl<C: ICommand> IMetadataEnricher<C> for TimestampEnricher {
+    fn enrich(
+        &self,
+        _command: &C,
+        metadata: &mut HashMap<String, String>,
+    ) {
+        metadata
+            .entry(TIMESTAMP_KEY.to_string())
+            .or_insert_with(|| {
+                Utc::now()
+                    .to_rfc3339_opts(SecondsFormat::Micros, true)
+            });
+    }
+}
@@ -0,0 +1,129 @@
+use std::{
+    collections::HashMap,
+    fmt::{
+        Debug,
+        Formatter,
+        Result as fmtResult,
+    },
+};
+
+use crate::{
+    commands::ICommand,
+    errors::Error,
+    events::{
+        EventContext,
+        IEvent,
+    },
+};
+
+use super::i_command_middleware::ICommandMiddleware;
+
+/// An ordered pipeline of `ICommandMiddleware`s. The `before` hooks
+/// run in registration order and the `after` and `on_error` hooks in
+/// reverse order, so every middleware wraps the ones registered
+/// after it.
+pub struct CommandMiddlewares<C: ICommand, E: IEvent> {
+    middlewares: Vec<Box<dyn ICommandMiddleware<C, E>>>,
+}
+
+impl<C: ICommand, E: IEvent> Default for CommandMiddlewares<C, E> {
+    fn default() -> Self {
+        Self {
+            middlewares: Vec::new(),
+        }
+    }
+}
+
+impl<C: ICommand, E: IEvent> CommandMiddlewares<C, E> {
+    /// Appends a middleware to the pipeline
+    pub fn register(
+        &mut self,
+        middleware: Box<dyn ICommandMiddleware<C, E>>,
+    ) {
+        self.middlewares.push(middleware);
+    }
+
+    /// Builder variant of `register`
+    #[must_use]
+    pub fn with_middleware<M: ICommandMiddleware<C, E> + 'static>(
+        mut self,
+        middleware: M,
+    ) -> Self {
+        self.register(Box::new(middleware));
+        self
+    }
+
+    /// Whether the pipeline has no middleware
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.middlewares.is_empty()
+    }
+
+    /// Runs the `before` hooks in order. When a middleware rejects
+    /// the command, the `on_error` hooks of the middlewares before it
+    /// run in reverse order.
+    ///
+    /// # Errors
+    ///
+    /// Returns the `Error` of the middleware rejecting the command.
+    pub fn before(
+        &mut self,
+        aggregate_id: &str,
+        command: &C,
+        metadata: &HashMap<String, String>,
+    ) -> Result<(), Error> {
+        for index in 0..self.middlewares.len() {
+            if let Err(e) = self.middlewares[index].before(
+                aggregate_id,
+                command,
+                metadata,
+            ) {
+                for middleware in self.middlewares[..index]
+                    .iter_mut()
+                    .rev()
+                {
+                    middleware.on_error(aggregate_id, command, &e);
+                }
+
+                return Err(e);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Runs the `after` hooks in reverse order
+    pub fn after(
+        &mut self,
+        aggregate_id: &str,
+        command: &C,
+        events: &[EventContext<C, E>],
+    ) {
+        for middleware in self.middlewares.iter_mut().rev() {
+            middleware.after(aggregate_id, command, events);
+        }
+    }
+
+    /// Runs the `on_error` hooks in reverse order
+    pub fn on_error(
+        &mut self,
+        aggregate_id: &str,
+        command: &C,
+        error: &Error,
+    ) {
+        for middleware in self.middlewares.iter_mut().rev() {
+            middleware.on_error(aggregate_id, command, error);
+        }
+    }
+}
+
+impl<C: ICommand, E: IEvent> Debug for CommandMiddlewares<C, E> {
+    fn fmt(
+        &self,
+        f: &mut Formatter<'_>,
+    ) -> fmtResult {
+        f.debug_struct("CommandMiddlewares")
+            .field("middlewares", &self.middlewares.len())
+            .finish()
+    }
+}
@@ -0,0 +1,78 @@
+use std::fmt::{
+    Debug,
+    Formatter,
+    Result as fmtResult,
+};
+
+use crate::{
+    commands::ICommand,
+    errors::Error,
+    events::{
+        EventContext,
+        IEvent,
+    },
+};
+
+use super::i_event_middleware::IEventMiddleware;
+
+/// An ordered pipeline of `IEventMiddleware`s, run in registration
+/// order on every event before it is committed.
+pub struct EventMiddlewares<C: ICommand, E: IEvent> {
+    middlewares: Vec<Box<dyn IEventMiddleware<C, E>>>,
+}
+
+impl<C: ICommand, E: IEvent> Default for EventMiddlewares<C, E> {
+    fn default() -> Self {
+        Self {
+            middlewares: Vec::new(),
+        }
+    }
+}
+
+impl<C: ICommand, E: IEvent> EventMiddlewares<C, E> {
+    /// Appends a middleware to the pipeline
+    pub fn register(
+        &mut self,
+        middleware: Box<dyn IEventMiddleware<C, E>>,
+    ) {
+        self.middlewares.push(middleware);
+    }
+
+    /// Builder variant of `register`
+    #[must_use]
+    pub fn with_middleware<M: IEventMiddleware<C, E> + 'static>(
+        mut self,
+        middleware: M,
+    ) -> Self {
+        self.register(Box::new(middleware));
+        self
+    }
+
+    /// Runs every middleware of the pipeline on `event`
+    ///
+    /// # Errors
+    ///
+    /// Returns the `Error` of the first middleware rejecting the
+    /// event.
+    pub fn process(
+        &mut self,
+        event: &mut EventContext<C, E>,
+    ) -> Result<(), Error> {
+        for middleware in &mut self.middlewares {
+            middleware.process(event)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<C: ICommand, E: IEvent> Debug for EventMiddlewares<C, E> {
+    fn fmt(
+        &self,
+        f: &mut Formatter<'_>,
+    ) -> fmtResult {
+        f.debug_struct("EventMiddlewares")
+            .field("middlewares", &self.middlewares.len())
+            .finish()
+    }
+}
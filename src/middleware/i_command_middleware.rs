@@ -0,0 +1,93 @@
+use std::collections::HashMap;
+
+use crate::{
+    commands::ICommand,
+    errors::Error,
+    events::{
+        EventContext,
+        IEvent,
+    },
+};
+
+/// An `ICommandMiddleware` wraps the dispatch of every command with
+/// cross-cutting behavior, e.g., authorization, logging, metrics or
+/// tenant checks, without touching the command handlers.
+///
+/// The middlewares registered on the framework form an ordered
+/// pipeline. `before` runs in registration order ahead of
+/// validation and handling, and may reject the command. `after` and
+/// `on_error` run in reverse order once the command succeeded or
+/// failed, and only for the middlewares whose `before` succeeded.
+///
+/// # Examples
+/// ```rust
+/// use std::collections::HashMap;
+///
+/// use cqrs_es2::{
+///     example_impl::{
+///         CustomerCommand,
+///         CustomerEvent,
+///     },
+///     Error,
+///     ICommandMiddleware,
+///     PRINCIPAL_KEY,
+/// };
+///
+/// struct AdminOnly;
+///
+/// impl ICommandMiddleware<CustomerCommand, CustomerEvent>
+///     for AdminOnly
+/// {
+///     fn before(
+///         &mut self,
+///         _aggregate_id: &str,
+///         _command: &CustomerCommand,
+///         metadata: &HashMap<String, String>,
+///     ) -> Result<(), Error> {
+///         match metadata
+///             .get(PRINCIPAL_KEY)
+///             .map(String::as_str)
+///         {
+///             Some("admin") => Ok(()),
+///             _ => Err(Error::unauthorized("admins only")),
+///         }
+///     }
+/// }
+/// ```
+pub trait ICommandMiddleware<C: ICommand, E: IEvent>:
+    Send + Sync {
+    /// Runs before the command is validated and handled. Returning
+    /// an `Error` rejects the command.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` to reject the command.
+    fn before(
+        &mut self,
+        _aggregate_id: &str,
+        _command: &C,
+        _metadata: &HashMap<String, String>,
+    ) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// Runs after the events produced by the command have been
+    /// committed
+    fn after(
+        &mut self,
+        _aggregate_id: &str,
+        _command: &C,
+        _events: &[EventContext<C, E>],
+    ) {
+    }
+
+    /// Runs after the command has failed, including when it was
+    /// rejected by a later middleware
+    fn on_error(
+        &mut self,
+        _aggregate_id: &str,
+        _command: &C,
+        _error: &Error,
+    ) {
+    }
+}
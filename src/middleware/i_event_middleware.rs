@@ -0,0 +1,61 @@
+use crate::{
+    commands::ICommand,
+    errors::Error,
+    events::{
+        EventContext,
+        IEvent,
+    },
+};
+
+/// An `IEventMiddleware` inspects or annotates every `EventContext`
+/// produced by a command before it is applied to the aggregate and
+/// committed, e.g., to add metadata or to reject events that break a
+/// cross-cutting rule. The middlewares registered on the framework
+/// run in registration order.
+///
+/// Middlewares are meant to work on the metadata, the payload should
+/// be left untouched.
+///
+/// # Examples
+/// ```rust
+/// use cqrs_es2::{
+///     example_impl::{
+///         CustomerCommand,
+///         CustomerEvent,
+///     },
+///     Error,
+///     EventContext,
+///     IEventMiddleware,
+/// };
+///
+/// struct TenantAnnotator;
+///
+/// impl IEventMiddleware<CustomerCommand, CustomerEvent>
+///     for TenantAnnotator
+/// {
+///     fn process(
+///         &mut self,
+///         event: &mut EventContext<CustomerCommand, CustomerEvent>,
+///     ) -> Result<(), Error> {
+///         event
+///             .metadata
+///             .insert("tenant".to_string(), "acme".to_string());
+///
+///         Ok(())
+///     }
+/// }
+/// ```
+pub trait IEventMiddleware<C: ICommand, E: IEvent>:
+    Send + Sync {
+    /// Inspects or annotates an event before it is committed.
+    /// Returning an `Error` fails the command and nothing is
+    /// committed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` to reject the event.
+    fn process(
+        &mut self,
+        event: &mut EventContext<C, E>,
+    ) -> Result<(), Error>;
+}
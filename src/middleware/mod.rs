@@ -0,0 +1,17 @@
+//! # middleware
+//!
+//! A central location for the middleware wrapping command dispatch
+//! with cross-cutting behavior
+
+pub use command_middlewares::CommandMiddlewares;
+pub use event_middlewares::EventMiddlewares;
+pub use i_command_middleware::ICommandMiddleware;
+pub use i_event_middleware::IEventMiddleware;
+
+mod command_middlewares;
+mod event_middlewares;
+mod i_command_middleware;
+mod i_event_middleware;
+
+#[cfg(test)]
+mod test;
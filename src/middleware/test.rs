@@ -0,0 +1,240 @@
+use std::{
+    collections::HashMap,
+    sync::{
+        Arc,
+        Mutex,
+    },
+};
+
+use crate::{
+    example_impl::*,
+    CqrsFramework,
+    Error,
+    EventContext,
+    IEventStore,
+    InMemoryEventStore,
+};
+
+use super::{
+    i_command_middleware::ICommandMiddleware,
+    i_event_middleware::IEventMiddleware,
+};
+
+type ThisEventStore =
+    InMemoryEventStore<CustomerCommand, CustomerEvent, Customer>;
+
+type Calls = Arc<Mutex<Vec<String>>>;
+
+struct RecordingMiddleware {
+    name: &'static str,
+    reject: bool,
+    calls: Calls,
+}
+
+impl RecordingMiddleware {
+    fn new(
+        name: &'static str,
+        calls: &Calls,
+    ) -> Self {
+        Self {
+            name,
+            reject: false,
+            calls: Arc::clone(calls),
+        }
+    }
+
+    fn record(
+        &self,
+        call: &str,
+    ) {
+        self.calls
+            .lock()
+            .unwrap()
+            .push(format!("{} {}", self.name, call));
+    }
+}
+
+impl ICommandMiddleware<CustomerCommand, CustomerEvent>
+    for RecordingMiddleware
+{
+    fn before(
+        &mut self,
+        aggregate_id: &str,
+        _command: &CustomerCommand,
+        _metadata: &HashMap<String, String>,
+    ) -> Result<(), Error> {
+        self.record(&format!("before {aggregate_id}"));
+
+        if self.reject {
+            return Err(Error::unauthorized("rejected"));
+        }
+
+        Ok(())
+    }
+
+    fn after(
+        &mut self,
+        _aggregate_id: &str,
+        _command: &CustomerCommand,
+        events: &[EventContext<CustomerCommand, CustomerEvent>],
+    ) {
+        self.record(&format!("after {}", events.len()));
+    }
+
+    fn on_error(
+        &mut self,
+        _aggregate_id: &str,
+        _command: &CustomerCommand,
+        error: &Error,
+    ) {
+        self.record(&format!("on_error {}", error.code()));
+    }
+}
+
+struct TenantAnnotator;
+
+impl IEventMiddleware<CustomerCommand, CustomerEvent>
+    for TenantAnnotator
+{
+    fn process(
+        &mut self,
+        event: &mut EventContext<CustomerCommand, CustomerEvent>,
+    ) -> Result<(), Error> {
+        event
+            .metadata
+            .insert("tenant".to_string(), "acme".to_string());
+
+        Ok(())
+    }
+}
+
+struct TenantCheck;
+
+impl IEventMiddleware<CustomerCommand, CustomerEvent>
+    for TenantCheck
+{
+    fn process(
+        &mut self,
+        event: &mut EventContext<CustomerCommand, CustomerEvent>,
+    ) -> Result<(), Error> {
+        match event.metadata.get("tenant") {
+            Some(_) => Ok(()),
+            None => Err(Error::unauthorized("missing tenant")),
+        }
+    }
+}
+
+fn add_address(address: &str) -> CustomerCommand {
+    CustomerCommand::AddAddress(AddAddress {
+        new_address: address.to_string(),
+    })
+}
+
+#[test]
+fn test_command_middleware_order() {
+    let calls = Calls::default();
+
+    let mut cqrs =
+        CqrsFramework::new(ThisEventStore::default(), Vec::new())
+            .with_command_middleware(RecordingMiddleware::new(
+                "first", &calls,
+            ))
+            .with_command_middleware(RecordingMiddleware::new(
+                "second", &calls,
+            ));
+
+    cqrs.execute("test_id_A", add_address("home"))
+        .unwrap();
+
+    // handling errors are reported in reverse order
+    cqrs.execute("test_id_A", add_address("home"))
+        .unwrap_err();
+
+    assert_eq!(
+        *calls.lock().unwrap(),
+        vec![
+            "first before test_id_A",
+            "second before test_id_A",
+            "second after 1",
+            "first after 1",
+            "first before test_id_A",
+            "second before test_id_A",
+            "second on_error user_error",
+            "first on_error user_error",
+        ]
+    );
+}
+
+#[test]
+fn test_command_middleware_rejection() {
+    let calls = Calls::default();
+    let mut store = ThisEventStore::default();
+
+    let mut rejecting = RecordingMiddleware::new("second", &calls);
+    rejecting.reject = true;
+
+    let mut cqrs = CqrsFramework::new(store.clone(), Vec::new())
+        .with_command_middleware(RecordingMiddleware::new(
+            "first", &calls,
+        ))
+        .with_command_middleware(rejecting)
+        .with_command_middleware(RecordingMiddleware::new(
+            "third", &calls,
+        ));
+
+    assert_eq!(
+        cqrs.execute("test_id_A", add_address("home")),
+        Err(Error::unauthorized("rejected"))
+    );
+
+    // only the middlewares that ran before the rejection are told
+    assert_eq!(
+        *calls.lock().unwrap(),
+        vec![
+            "first before test_id_A",
+            "second before test_id_A",
+            "first on_error unauthorized",
+        ]
+    );
+    assert!(store
+        .load_events("test_id_A")
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn test_event_middleware() {
+    let mut store = ThisEventStore::default();
+
+    let mut cqrs = CqrsFramework::new(store.clone(), Vec::new())
+        .with_event_middleware(TenantAnnotator)
+        .with_event_middleware(TenantCheck);
+
+    let events = cqrs
+        .execute("test_id_A", add_address("home"))
+        .unwrap();
+
+    assert_eq!(events[0].metadata["tenant"], "acme");
+    assert_eq!(
+        store.load_events("test_id_A").unwrap(),
+        events
+    );
+
+    // the pipeline runs in order, so the check fails without the
+    // annotation
+    let mut cqrs = CqrsFramework::new(store.clone(), Vec::new())
+        .with_event_middleware(TenantCheck)
+        .with_event_middleware(TenantAnnotator);
+
+    assert_eq!(
+        cqrs.execute("test_id_A", add_address("work")),
+        Err(Error::unauthorized("missing tenant"))
+    );
+    assert_eq!(
+        store
+            .load_events("test_id_A")
+            .unwrap()
+            .len(),
+        1
+    );
+}
@@ -0,0 +1,20 @@
+use crate::errors::Error;
+
+use super::outbox_message::OutboxMessage;
+
+/// Publishes the messages of the outbox to a message broker. The
+/// `OutboxRelay` retries a message until `publish` succeeds, so a
+/// message may be published more than once and downstream consumers
+/// should use the message id to drop duplicates.
+pub trait IEventPublisher {
+    /// Publishes a single message.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` when the message could not be handed over
+    /// to the broker, in which case it will be retried.
+    fn publish(
+        &mut self,
+        message: &OutboxMessage,
+    ) -> Result<(), Error>;
+}
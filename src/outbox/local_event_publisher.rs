@@ -0,0 +1,98 @@
+use std::sync::{
+    Arc,
+    RwLock,
+};
+
+use crate::errors::Error;
+
+use super::{
+    i_event_publisher::IEventPublisher,
+    outbox_message::OutboxMessage,
+};
+
+type MessageHandler =
+    Box<dyn FnMut(&OutboxMessage) -> Result<(), Error> + Send + Sync>;
+
+/// An in-process `IEventPublisher` for tests and local setups. It
+/// passes every message to its handlers, in registration order, and
+/// records the messages all handlers accepted. Cloning the publisher
+/// yields a handle to the same recorded messages.
+#[derive(Default, Clone)]
+pub struct LocalEventPublisher {
+    handlers: Arc<RwLock<Vec<MessageHandler>>>,
+    published: Arc<RwLock<Vec<OutboxMessage>>>,
+}
+
+impl LocalEventPublisher {
+    /// Appends a handler of the published messages. A handler
+    /// returning an `Error` fails the publication, so the message is
+    /// retried.
+    #[must_use]
+    pub fn with_handler<F>(
+        self,
+        handler: F,
+    ) -> Self
+    where
+        F: FnMut(&OutboxMessage) -> Result<(), Error>
+            + Send
+            + Sync
+            + 'static, {
+        if let Ok(mut handlers) = self.handlers.write() {
+            handlers.push(Box::new(handler));
+        }
+
+        self
+    }
+
+    /// The messages published so far
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` when the recorded messages can not be
+    /// read.
+    pub fn published(&self) -> Result<Vec<OutboxMessage>, Error> {
+        let published = self.published.read().map_err(|e| {
+            Error::Store {
+                message: format!(
+                    "unable to read the published messages: {e}"
+                ),
+                source: None,
+            }
+        })?;
+
+        Ok(published.clone())
+    }
+}
+
+impl IEventPublisher for LocalEventPublisher {
+    fn publish(
+        &mut self,
+        message: &OutboxMessage,
+    ) -> Result<(), Error> {
+        let mut handlers = self.handlers.write().map_err(|e| {
+            Error::Store {
+                message: format!(
+                    "unable to lock the message handlers: {e}"
+                ),
+                source: None,
+            }
+        })?;
+
+        for handler in handlers.iter_mut() {
+            handler(message)?;
+        }
+
+        let mut published = self.published.write().map_err(|e| {
+            Error::Store {
+                message: format!(
+                    "unable to record the published message: {e}"
+                ),
+                source: None,
+            }
+        })?;
+
+        published.push(message.clone());
+
+        Ok(())
+    }
+}
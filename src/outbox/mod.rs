@@ -0,0 +1,22 @@
+//! # outbox
+//!
+//! A central location for the transactional outbox publishing the
+//! committed events to external message brokers
+
+pub use i_event_publisher::IEventPublisher;
+pub use local_event_publisher::LocalEventPublisher;
+pub use outbox_message::OutboxMessage;
+pub use outbox_relay::{
+    OutboxRelay,
+    RelayReport,
+};
+pub use retry_backoff::RetryBackoff;
+
+mod i_event_publisher;
+mod local_event_publisher;
+mod outbox_message;
+mod outbox_relay;
+mod retry_backoff;
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,44 @@
+use chrono::{
+    DateTime,
+    Utc,
+};
+
+use crate::events::SerializedEvent;
+
+/// A committed event waiting in the outbox to be published.
+#[derive(Debug, PartialEq, Clone)]
+pub struct OutboxMessage {
+    /// The id of the message, increasing in the order the events
+    /// were committed. Publishers may forward it to let downstream
+    /// consumers drop redelivered messages.
+    pub id: i64,
+
+    /// The serialized event.
+    pub event: SerializedEvent,
+
+    /// The number of failed attempts to publish the message.
+    pub attempts: u32,
+
+    /// The earliest time the message may be published at.
+    pub next_attempt_at: DateTime<Utc>,
+
+    /// The error of the last failed attempt, if any.
+    pub last_error: Option<String>,
+}
+
+impl OutboxMessage {
+    /// Constructor of a message due right away
+    #[must_use]
+    pub fn new(
+        id: i64,
+        event: SerializedEvent,
+    ) -> Self {
+        Self {
+            id,
+            event,
+            attempts: 0,
+            next_attempt_at: Utc::now(),
+            last_error: None,
+        }
+    }
+}
@@ -0,0 +1,227 @@
+use chrono::{
+    DateTime,
+    Duration,
+    Utc,
+};
+use log::{
+    debug,
+    warn,
+};
+
+use crate::{
+    errors::Error,
+    stores::IOutboxStore,
+};
+
+use super::{
+    i_event_publisher::IEventPublisher,
+    retry_backoff::RetryBackoff,
+};
+
+/// The default number of messages read from the outbox at once
+const DEFAULT_BATCH_SIZE: usize = 100;
+
+/// The outcome of a single `OutboxRelay::relay` run
+#[derive(
+    Debug, PartialEq, Eq, Clone, Copy, Default
+)]
+pub struct RelayReport {
+    /// The number of messages published and removed from the outbox.
+    pub published: usize,
+
+    /// The number of messages that failed and were rescheduled.
+    pub failed: usize,
+}
+
+/// `OutboxRelay` drains the outbox through an `IEventPublisher`.
+/// Messages are published in the order they were committed and
+/// removed from the outbox only after they have been published,
+/// which gives at-least-once delivery.
+///
+/// A message that fails to publish is rescheduled according to the
+/// `RetryBackoff`, and the run stops at it so that later messages do
+/// not overtake it. Messages are retried until they succeed.
+///
+/// The relay is meant to be run periodically, e.g., from a
+/// background thread or a scheduled job.
+///
+/// # Examples
+/// ```rust
+/// use cqrs_es2::{
+///     example_impl::{
+///         AddCustomerName,
+///         Customer,
+///         CustomerCommand,
+///         CustomerEvent,
+///     },
+///     CqrsFramework,
+///     InMemoryEventStore,
+///     InMemoryOutboxStore,
+///     LocalEventPublisher,
+///     OutboxRelay,
+/// };
+///
+/// let outbox = InMemoryOutboxStore::default();
+///
+/// let mut cqrs =
+///     CqrsFramework::new(
+///         InMemoryEventStore::<
+///             CustomerCommand,
+///             CustomerEvent,
+///             Customer,
+///         >::default()
+///         .with_outbox(outbox.clone()),
+///         vec![],
+///     );
+///
+/// cqrs.execute(
+///     "customer-1",
+///     CustomerCommand::AddCustomerName(AddCustomerName {
+///         changed_name: "John Doe".to_string(),
+///     }),
+/// )
+/// .unwrap();
+///
+/// let publisher = LocalEventPublisher::default();
+///
+/// let report = OutboxRelay::new(outbox, publisher.clone())
+///     .relay()
+///     .unwrap();
+///
+/// assert_eq!(report.published, 1);
+///
+/// let published = publisher.published().unwrap();
+///
+/// assert_eq!(
+///     published[0].event.event_type,
+///     "NameAdded"
+/// );
+/// ```
+pub struct OutboxRelay<OS: IOutboxStore, P: IEventPublisher> {
+    store: OS,
+    publisher: P,
+    backoff: RetryBackoff,
+    batch_size: usize,
+}
+
+impl<OS: IOutboxStore, P: IEventPublisher> OutboxRelay<OS, P> {
+    /// Constructor
+    pub fn new(
+        store: OS,
+        publisher: P,
+    ) -> Self {
+        Self {
+            store,
+            publisher,
+            backoff: RetryBackoff::default(),
+            batch_size: DEFAULT_BATCH_SIZE,
+        }
+    }
+
+    /// Sets the backoff between the attempts to publish a message
+    #[must_use]
+    pub fn with_backoff(
+        self,
+        backoff: RetryBackoff,
+    ) -> Self {
+        Self { backoff, ..self }
+    }
+
+    /// Sets the number of messages read from the outbox at once
+    #[must_use]
+    pub fn with_batch_size(
+        self,
+        batch_size: usize,
+    ) -> Self {
+        Self {
+            batch_size: batch_size.max(1),
+            ..self
+        }
+    }
+
+    /// Publishes the messages that are currently due
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` when the outbox store fails. Publishing
+    /// failures are not errors, they are rescheduled.
+    pub fn relay(&mut self) -> Result<RelayReport, Error> {
+        self.relay_at(Utc::now())
+    }
+
+    /// Publishes the messages that are due at `now`
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` when the outbox store fails. Publishing
+    /// failures are not errors, they are rescheduled.
+    pub fn relay_at(
+        &mut self,
+        now: DateTime<Utc>,
+    ) -> Result<RelayReport, Error> {
+        let mut report = RelayReport::default();
+
+        loop {
+            let messages = self
+                .store
+                .load_pending(now, self.batch_size)?;
+
+            let count = messages.len();
+
+            for message in messages {
+                if let Err(e) = self.publisher.publish(&message) {
+                    let attempts = message.attempts + 1;
+                    let delay = self.backoff.delay(attempts);
+
+                    warn!(
+                        "unable to publish outbox message {} after \
+                         {} attempts, retrying in {:?}: {}",
+                        message.id, attempts, delay, e
+                    );
+
+                    let delay =
+                        Duration::from_std(delay).map_err(|e| {
+                            Error::TechnicalError(format!(
+                                "invalid outbox retry delay: {e}"
+                            ))
+                        })?;
+
+                    let Some(next_attempt_at) =
+                        now.checked_add_signed(delay)
+                    else {
+                        return Err(Error::TechnicalError(format!(
+                            "the next attempt of outbox message {} \
+                             is out of range",
+                            message.id
+                        )));
+                    };
+
+                    self.store.mark_failed(
+                        message.id,
+                        next_attempt_at,
+                        &e.to_string(),
+                    )?;
+
+                    report.failed += 1;
+
+                    return Ok(report);
+                }
+
+                self.store.mark_published(message.id)?;
+
+                report.published += 1;
+            }
+
+            if count < self.batch_size {
+                break;
+            }
+        }
+
+        debug!(
+            "relayed {} outbox messages",
+            report.published
+        );
+
+        Ok(report)
+    }
+}
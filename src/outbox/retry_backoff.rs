@@ -0,0 +1,50 @@
+use std::time::Duration;
+
+/// The exponential backoff between the attempts of the
+/// `OutboxRelay` to publish a message. The delay after the first
+/// failed attempt is `initial_delay`, and it is multiplied by
+/// `multiplier` after every further failed attempt up to
+/// `max_delay`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct RetryBackoff {
+    /// The delay after the first failed attempt.
+    pub initial_delay: Duration,
+
+    /// The upper bound of the delay.
+    pub max_delay: Duration,
+
+    /// The factor the delay grows by after every failed attempt.
+    pub multiplier: u32,
+}
+
+impl Default for RetryBackoff {
+    fn default() -> Self {
+        Self {
+            initial_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(300),
+            multiplier: 2,
+        }
+    }
+}
+
+impl RetryBackoff {
+    /// The delay before the next attempt after `attempts` failed
+    /// attempts
+    #[must_use]
+    pub fn delay(
+        &self,
+        attempts: u32,
+    ) -> Duration {
+        let mut delay = self.initial_delay;
+
+        for _ in 1..attempts {
+            if delay >= self.max_delay {
+                break;
+            }
+
+            delay = delay.saturating_mul(self.multiplier);
+        }
+
+        delay.min(self.max_delay)
+    }
+}
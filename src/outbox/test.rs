@@ -0,0 +1,320 @@
+use chrono::{
+    Duration,
+    Utc,
+};
+use std::{
+    sync::{
+        atomic::{
+            AtomicUsize,
+            Ordering,
+        },
+        Arc,
+    },
+    time,
+};
+
+use crate::{
+    example_impl::*,
+    store_conformance::fixtures::name_added,
+    Error,
+    IEventStore,
+    IOutboxStore,
+    InMemoryEventStore,
+    InMemoryOutboxStore,
+};
+
+use super::{
+    local_event_publisher::LocalEventPublisher,
+    outbox_relay::{
+        OutboxRelay,
+        RelayReport,
+    },
+    retry_backoff::RetryBackoff,
+};
+
+type ThisEventStore =
+    InMemoryEventStore<CustomerCommand, CustomerEvent, Customer>;
+
+fn outbox_with_events() -> InMemoryOutboxStore {
+    let outbox = InMemoryOutboxStore::default();
+
+    let mut store =
+        ThisEventStore::default().with_outbox(outbox.clone());
+
+    store
+        .commit(
+            vec![
+                name_added("test_id_A", 1, "John Doe"),
+                name_added("test_id_A", 2, "John Doe"),
+            ],
+            0,
+        )
+        .unwrap();
+    store
+        .commit(
+            vec![name_added("test_id_B", 1, "John Doe")],
+            0,
+        )
+        .unwrap();
+
+    outbox
+}
+
+#[test]
+fn test_retry_backoff() {
+    let backoff = RetryBackoff {
+        initial_delay: time::Duration::from_secs(1),
+        max_delay: time::Duration::from_secs(5),
+        multiplier: 2,
+    };
+
+    assert_eq!(
+        backoff.delay(1),
+        time::Duration::from_secs(1)
+    );
+    assert_eq!(
+        backoff.delay(2),
+        time::Duration::from_secs(2)
+    );
+    assert_eq!(
+        backoff.delay(3),
+        time::Duration::from_secs(4)
+    );
+    assert_eq!(
+        backoff.delay(4),
+        time::Duration::from_secs(5)
+    );
+    assert_eq!(
+        backoff.delay(100),
+        time::Duration::from_secs(5)
+    );
+}
+
+#[test]
+fn test_commit_writes_outbox() {
+    let mut outbox = outbox_with_events();
+
+    let messages = outbox
+        .load_pending(Utc::now(), 10)
+        .unwrap();
+
+    assert_eq!(messages.len(), 3);
+    assert_eq!(
+        messages
+            .iter()
+            .map(|x| x.id)
+            .collect::<Vec<_>>(),
+        vec![1, 2, 3]
+    );
+    assert_eq!(
+        messages[0].event.aggregate_type,
+        "customer"
+    );
+    assert_eq!(
+        messages[0].event.event_type,
+        "NameAdded"
+    );
+    assert_eq!(
+        messages[2].event.aggregate_id,
+        "test_id_B"
+    );
+    assert_eq!(messages[2].attempts, 0);
+}
+
+#[test]
+fn test_rejected_commit_skips_outbox() {
+    let mut outbox = InMemoryOutboxStore::default();
+
+    let mut store =
+        ThisEventStore::default().with_outbox(outbox.clone());
+
+    store
+        .commit(
+            vec![name_added("test_id_A", 1, "John Doe")],
+            0,
+        )
+        .unwrap();
+
+    assert!(store
+        .commit(
+            vec![name_added("test_id_A", 2, "John Doe")],
+            0
+        )
+        .is_err());
+
+    assert_eq!(
+        outbox
+            .load_pending(Utc::now(), 10)
+            .unwrap()
+            .len(),
+        1
+    );
+
+    // stores without an outbox do not write any
+    ThisEventStore::default()
+        .commit(
+            vec![name_added("test_id_A", 1, "John Doe")],
+            0,
+        )
+        .unwrap();
+
+    assert_eq!(
+        outbox
+            .load_pending(Utc::now(), 10)
+            .unwrap()
+            .len(),
+        1
+    );
+}
+
+#[test]
+fn test_relay_publishes_in_order() {
+    let mut outbox = outbox_with_events();
+    let publisher = LocalEventPublisher::default();
+
+    let report = OutboxRelay::new(outbox.clone(), publisher.clone())
+        .with_batch_size(2)
+        .relay()
+        .unwrap();
+
+    assert_eq!(
+        report,
+        RelayReport {
+            published: 3,
+            failed: 0,
+        }
+    );
+    assert_eq!(
+        publisher
+            .published()
+            .unwrap()
+            .iter()
+            .map(|x| x.id)
+            .collect::<Vec<_>>(),
+        vec![1, 2, 3]
+    );
+    assert!(outbox
+        .load_pending(Utc::now(), 10)
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn test_relay_retries_with_backoff() {
+    let mut outbox = outbox_with_events();
+
+    let calls = Arc::new(AtomicUsize::new(0));
+    let counter = Arc::clone(&calls);
+
+    // the broker is down for the first two attempts
+    let publisher =
+        LocalEventPublisher::default().with_handler(move |_| {
+            if counter.fetch_add(1, Ordering::SeqCst) < 2 {
+                return Err(Error::TechnicalError(
+                    "broker unavailable".to_string(),
+                ));
+            }
+
+            Ok(())
+        });
+
+    let mut relay =
+        OutboxRelay::new(outbox.clone(), publisher.clone())
+            .with_backoff(RetryBackoff {
+                initial_delay: time::Duration::from_secs(10),
+                max_delay: time::Duration::from_secs(100),
+                multiplier: 2,
+            });
+
+    let now = Utc::now();
+
+    assert_eq!(
+        relay.relay_at(now).unwrap(),
+        RelayReport {
+            published: 0,
+            failed: 1,
+        }
+    );
+
+    let messages = outbox
+        .load_pending(now + Duration::seconds(10), 10)
+        .unwrap();
+
+    assert_eq!(messages[0].attempts, 1);
+    assert_eq!(
+        messages[0].last_error.as_deref(),
+        Some("broker unavailable")
+    );
+
+    // later messages do not overtake the rescheduled one
+    assert_eq!(
+        relay.relay_at(now).unwrap(),
+        RelayReport::default()
+    );
+
+    assert_eq!(
+        relay
+            .relay_at(now + Duration::seconds(10))
+            .unwrap()
+            .failed,
+        1
+    );
+
+    // the second failure doubles the delay
+    assert_eq!(
+        relay
+            .relay_at(now + Duration::seconds(29))
+            .unwrap(),
+        RelayReport::default()
+    );
+
+    assert_eq!(
+        relay
+            .relay_at(now + Duration::seconds(30))
+            .unwrap(),
+        RelayReport {
+            published: 3,
+            failed: 0,
+        }
+    );
+    assert_eq!(publisher.published().unwrap().len(), 3);
+    assert_eq!(calls.load(Ordering::SeqCst), 5);
+}
+
+#[test]
+fn test_relay_rejects_out_of_range_retry() {
+    let outbox = outbox_with_events();
+
+    let publisher =
+        LocalEventPublisher::default().with_handler(|_| {
+            Err(Error::TechnicalError(
+                "broker unavailable".to_string(),
+            ))
+        });
+
+    // a million years is beyond the range of the retry timestamps
+    let delay = time::Duration::from_secs(1_000_000 * 365 * 86_400);
+
+    let mut relay = OutboxRelay::new(outbox, publisher).with_backoff(
+        RetryBackoff {
+            initial_delay: delay,
+            max_delay: delay,
+            multiplier: 2,
+        },
+    );
+
+    assert!(matches!(
+        relay.relay_at(Utc::now()),
+        Err(Error::TechnicalError(_))
+    ));
+}
+
+#[test]
+fn test_mark_unknown_message_failed() {
+    let mut outbox = InMemoryOutboxStore::default();
+
+    assert_eq!(
+        outbox.mark_failed(1, Utc::now(), "error"),
+        Err(Error::not_found("outbox message", "1"))
+    );
+}
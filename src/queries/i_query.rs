@@ -88,6 +88,6 @@ pub trait IQuery<C: ICommand, E: IEvent>:
     + IEventConsumer<C, E>
     + Sync
     + Send {
-    /// query_type is a unique identifier for this query
+    /// `query_type` is a unique identifier for this query
     fn query_type() -> &'static str;
 }
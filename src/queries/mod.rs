@@ -3,7 +3,21 @@
 //! A central location for `Query` interfaces
 
 pub use i_query::IQuery;
+pub use projection_rebuilder::{
+    ProjectionRebuilder,
+    DEFAULT_REPLAY_BATCH_SIZE,
+};
 pub use query_context::QueryContext;
+pub use query_processor::QueryProcessor;
+pub use replay_progress::ReplayProgress;
+pub use swappable_query_store::SwappableQueryStore;
 
 mod i_query;
+mod projection_rebuilder;
 mod query_context;
+mod query_processor;
+mod replay_progress;
+mod swappable_query_store;
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,307 @@
+use log::{
+    debug,
+    info,
+};
+use std::{
+    collections::{
+        hash_map::Entry,
+        HashMap,
+    },
+    marker::PhantomData,
+};
+
+use crate::{
+    aggregates::IAggregate,
+    commands::ICommand,
+    errors::Error,
+    events::{
+        IEvent,
+        PositionedEvent,
+    },
+    stores::{
+        IEventStore,
+        IQueryStore,
+    },
+};
+
+use super::{
+    i_query::IQuery,
+    replay_progress::ReplayProgress,
+    swappable_query_store::SwappableQueryStore,
+};
+
+/// The number of events loaded at once while replaying.
+pub const DEFAULT_REPLAY_BATCH_SIZE: usize = 1000;
+
+type ProgressReporter = Box<dyn FnMut(&ReplayProgress)>;
+
+/// `ProjectionRebuilder` rebuilds the queries of an aggregate type
+/// from scratch, e.g., after fixing a bug in a query or adding a new
+/// one. It pages through all events of the aggregate type in their
+/// global order, feeds them to a fresh `Q::default()` per aggregate
+/// instance and saves the resulting queries to a shadow query store,
+/// which can then atomically replace the live one.
+///
+/// The rebuilt queries carry the sequence of the last replayed event
+/// as their version, so a `QueryProcessor` can keep them up to date
+/// afterwards. Events committed while the rebuild runs are replayed
+/// as well: before the swap, the rebuilder catches up from the last
+/// replayed position while holding the lock of the live store, which
+/// blocks the loads and saves of the `QueryProcessor`s writing to it
+/// until the rebuilt store is in place.
+///
+/// # Examples
+/// ```rust
+/// use cqrs_es2::{
+///     example_impl::{
+///         AddCustomerName,
+///         Customer,
+///         CustomerCommand,
+///         CustomerContactQuery,
+///         CustomerEvent,
+///     },
+///     CqrsFramework,
+///     IQueryStore,
+///     InMemoryEventStore,
+///     InMemoryQueryStore,
+///     ProjectionRebuilder,
+///     SwappableQueryStore,
+/// };
+///
+/// type ThisQueryStore = InMemoryQueryStore<
+///     CustomerCommand,
+///     CustomerEvent,
+///     CustomerContactQuery,
+/// >;
+///
+/// let events = InMemoryEventStore::<
+///     CustomerCommand,
+///     CustomerEvent,
+///     Customer,
+/// >::default();
+///
+/// let mut cqrs = CqrsFramework::new(events.clone(), vec![]);
+///
+/// cqrs.execute(
+///     "customer-1",
+///     CustomerCommand::AddCustomerName(AddCustomerName {
+///         changed_name: "John Doe".to_string(),
+///     }),
+/// )
+/// .unwrap();
+///
+/// let mut queries =
+///     SwappableQueryStore::new(ThisQueryStore::default());
+///
+/// let progress = ProjectionRebuilder::new(events)
+///     .rebuild(&queries, ThisQueryStore::default())
+///     .unwrap();
+///
+/// assert_eq!(progress.processed_events, 1);
+///
+/// let context = queries.load("customer-1").unwrap();
+///
+/// assert_eq!(context.version, 1);
+/// assert_eq!(context.payload.name, "John Doe");
+/// ```
+pub struct ProjectionRebuilder<
+    C: ICommand,
+    E: IEvent,
+    A: IAggregate<C, E>,
+    Q: IQuery<C, E>,
+    ES: IEventStore<C, E, A>,
+> {
+    store: ES,
+    batch_size: usize,
+    progress: Option<ProgressReporter>,
+    _phantom: PhantomData<(C, E, A, Q)>,
+}
+
+impl<
+        C: ICommand,
+        E: IEvent,
+        A: IAggregate<C, E>,
+        Q: IQuery<C, E>,
+        ES: IEventStore<C, E, A>,
+    > ProjectionRebuilder<C, E, A, Q, ES>
+{
+    /// Constructor
+    pub fn new(store: ES) -> Self {
+        Self {
+            store,
+            batch_size: DEFAULT_REPLAY_BATCH_SIZE,
+            progress: None,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Loads `batch_size` events at once while replaying
+    #[must_use]
+    pub fn with_batch_size(
+        self,
+        batch_size: usize,
+    ) -> Self {
+        Self {
+            batch_size: batch_size.max(1),
+            ..self
+        }
+    }
+
+    /// Reports the progress of the replay after every event. The
+    /// event store counts the events up front to provide the total,
+    /// see `IEventStore::count_events_after`.
+    #[must_use]
+    pub fn with_progress<F: FnMut(&ReplayProgress) + 'static>(
+        self,
+        progress: F,
+    ) -> Self {
+        Self {
+            progress: Some(Box::new(progress)),
+            ..self
+        }
+    }
+
+    /// Replays all events into `shadow`, which is expected to be
+    /// empty, and returns the final progress.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` when the events can not be loaded or the
+    /// shadow store fails.
+    pub fn replay<QS: IQueryStore<C, E, Q>>(
+        &mut self,
+        shadow: &mut QS,
+    ) -> Result<ReplayProgress, Error> {
+        let mut progress = ReplayProgress::default();
+
+        self.replay_after(shadow, 0, &mut progress)?;
+
+        Ok(progress)
+    }
+
+    /// Replays all events into `shadow`, catches up with the events
+    /// committed in the meantime while holding the lock of `live`
+    /// and then atomically swaps `shadow` in as the underlying store
+    /// of `live`. The live store is left untouched when the replay
+    /// fails.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` when the replay or the swap fails.
+    pub fn rebuild<QS: IQueryStore<C, E, Q>>(
+        &mut self,
+        live: &SwappableQueryStore<C, E, Q, QS>,
+        shadow: QS,
+    ) -> Result<ReplayProgress, Error> {
+        let mut shadow = shadow;
+        let mut progress = ReplayProgress::default();
+
+        let position =
+            self.replay_after(&mut shadow, 0, &mut progress)?;
+
+        live.swap_with(|| {
+            let position = self.replay_after(
+                &mut shadow,
+                position,
+                &mut progress,
+            )?;
+
+            debug!(
+                "caught up with query type '{}' at position {}",
+                Q::query_type(),
+                position
+            );
+
+            Ok(shadow)
+        })?;
+
+        info!(
+            "rebuilt query type '{}' from {} events",
+            Q::query_type(),
+            progress.processed_events
+        );
+
+        Ok(progress)
+    }
+
+    /// Replays the events after `position` into `shadow` a batch at
+    /// a time, and returns the position of the last replayed event
+    fn replay_after<QS: IQueryStore<C, E, Q>>(
+        &mut self,
+        shadow: &mut QS,
+        position: i64,
+        progress: &mut ReplayProgress,
+    ) -> Result<i64, Error> {
+        if self.progress.is_some() {
+            progress.total_events += self
+                .store
+                .count_events_after(position)?;
+        }
+
+        info!(
+            "replaying events of aggregate type '{}' after position \
+             {} into query type '{}'",
+            A::aggregate_type(),
+            position,
+            Q::query_type()
+        );
+
+        let mut position = position;
+
+        loop {
+            let events = self
+                .store
+                .load_all_events_after(position, self.batch_size)?;
+
+            let last_position = match events.last() {
+                None => break,
+                Some(x) => x.position,
+            };
+
+            let mut contexts = HashMap::new();
+
+            for PositionedEvent { event, .. } in events {
+                let context = match contexts
+                    .entry(event.aggregate_id.clone())
+                {
+                    Entry::Occupied(x) => x.into_mut(),
+                    Entry::Vacant(x) => {
+                        x.insert(shadow.load(&event.aggregate_id)?)
+                    },
+                };
+
+                if context.version == 0 {
+                    progress.aggregates += 1;
+                }
+
+                context.payload.update(&event);
+                context.version = event.sequence;
+
+                progress.processed_events += 1;
+                progress.total_events = progress
+                    .total_events
+                    .max(progress.processed_events);
+
+                if let Some(report) = &mut self.progress {
+                    report(progress);
+                }
+            }
+
+            for (_, context) in contexts {
+                shadow.save(context)?;
+            }
+
+            position = last_position;
+        }
+
+        debug!(
+            "replayed {} events of {} aggregates into query type \
+             '{}'",
+            progress.processed_events,
+            progress.aggregates,
+            Q::query_type()
+        );
+
+        Ok(position)
+    }
+}
@@ -41,7 +41,7 @@ impl<C: ICommand, E: IEvent, Q: IQuery<C, E>> QueryContext<C, E, Q> {
             _phantom: PhantomData,
         };
 
-        trace!("Created new {:?}", x,);
+        trace!("Created new {x:?}");
 
         x
     }
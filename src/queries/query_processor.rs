@@ -0,0 +1,179 @@
+use log::{
+    error,
+    trace,
+};
+use std::marker::PhantomData;
+
+use crate::{
+    commands::ICommand,
+    errors::Error,
+    events::{
+        EventContext,
+        IEvent,
+        IEventConsumer,
+    },
+    stores::IQueryStore,
+};
+
+use super::i_query::IQuery;
+
+/// `QueryProcessor` keeps the queries of a query store up to date
+/// with the committed events. For every event it loads the
+/// `QueryContext` of the event aggregate, lets the query consume the
+/// event, sets the query version to the event sequence and saves it
+/// back.
+///
+/// Events whose sequence is at or below the stored version have
+/// already been consumed and are skipped, which makes redelivering
+/// events idempotent. An event that does not directly follow the
+/// stored version is rejected, as applying it would silently skip
+/// the missing events; the query is left untouched so that the gap
+/// can be filled by redelivering the missing events, or by rebuilding
+/// the query with a `ProjectionRebuilder`.
+///
+/// The processor is itself an `IEventConsumer` so it can be
+/// registered with the `CqrsFramework`.
+///
+/// # Examples
+/// ```rust
+/// use cqrs_es2::{
+///     example_impl::{
+///         AddCustomerName,
+///         Customer,
+///         CustomerCommand,
+///         CustomerContactQuery,
+///         CustomerEvent,
+///     },
+///     CqrsFramework,
+///     IQueryStore,
+///     InMemoryEventStore,
+///     InMemoryQueryStore,
+///     QueryProcessor,
+/// };
+///
+/// let mut queries = InMemoryQueryStore::<
+///     CustomerCommand,
+///     CustomerEvent,
+///     CustomerContactQuery,
+/// >::default();
+///
+/// let mut cqrs =
+///     CqrsFramework::new(
+///         InMemoryEventStore::<
+///             CustomerCommand,
+///             CustomerEvent,
+///             Customer,
+///         >::default(),
+///         vec![Box::new(QueryProcessor::new(
+///             queries.clone(),
+///         ))],
+///     );
+///
+/// cqrs.execute(
+///     "customer-1",
+///     CustomerCommand::AddCustomerName(AddCustomerName {
+///         changed_name: "John Doe".to_string(),
+///     }),
+/// )
+/// .unwrap();
+///
+/// let context = queries.load("customer-1").unwrap();
+///
+/// assert_eq!(context.version, 1);
+/// assert_eq!(context.payload.name, "John Doe");
+/// ```
+pub struct QueryProcessor<
+    C: ICommand,
+    E: IEvent,
+    Q: IQuery<C, E>,
+    QS: IQueryStore<C, E, Q>,
+> {
+    store: QS,
+    _phantom: PhantomData<(C, E, Q)>,
+}
+
+impl<
+        C: ICommand,
+        E: IEvent,
+        Q: IQuery<C, E>,
+        QS: IQueryStore<C, E, Q>,
+    > QueryProcessor<C, E, Q, QS>
+{
+    /// Constructor
+    pub fn new(store: QS) -> Self {
+        Self {
+            store,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Applies an event to the query of its aggregate and saves the
+    /// result. Returns `false` when the event had already been
+    /// consumed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` when the event does not directly follow
+    /// the version of the query, or when the query store fails.
+    pub fn process(
+        &mut self,
+        event: &EventContext<C, E>,
+    ) -> Result<bool, Error> {
+        let mut context = self.store.load(&event.aggregate_id)?;
+
+        if event.sequence <= context.version {
+            trace!(
+                "skipping event {} of aggregate '{}' already \
+                 consumed by query of type '{}' at version {}",
+                event.sequence,
+                event.aggregate_id,
+                Q::query_type(),
+                context.version
+            );
+
+            return Ok(false);
+        }
+
+        if event.sequence != context.version + 1 {
+            return Err(Error::TechnicalError(format!(
+                "event {} of aggregate '{}' does not follow the \
+                 version {} of query of type '{}'",
+                event.sequence,
+                event.aggregate_id,
+                context.version,
+                Q::query_type()
+            )));
+        }
+
+        context.payload.update(event);
+        context.version = event.sequence;
+
+        self.store.save(context)?;
+
+        Ok(true)
+    }
+}
+
+impl<
+        C: ICommand,
+        E: IEvent,
+        Q: IQuery<C, E>,
+        QS: IQueryStore<C, E, Q>,
+    > IEventConsumer<C, E> for QueryProcessor<C, E, Q, QS>
+{
+    fn update(
+        &mut self,
+        event: &EventContext<C, E>,
+    ) {
+        if let Err(e) = self.process(event) {
+            error!(
+                "unable to update query of type '{}' with event {} \
+                 of aggregate '{}': {}",
+                Q::query_type(),
+                event.sequence,
+                event.aggregate_id,
+                e
+            );
+        }
+    }
+}
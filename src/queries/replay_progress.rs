@@ -0,0 +1,42 @@
+use std::convert::TryFrom;
+
+/// The progress of a projection rebuild, reported after every
+/// replayed event and returned once the rebuild is done.
+#[derive(
+    Debug, PartialEq, Eq, Clone, Copy, Default
+)]
+pub struct ReplayProgress {
+    /// The number of events to replay.
+    pub total_events: usize,
+
+    /// The number of events replayed so far.
+    pub processed_events: usize,
+
+    /// The number of aggregate instances seen so far.
+    pub aggregates: usize,
+}
+
+impl ReplayProgress {
+    /// Whether all events have been replayed
+    #[must_use]
+    pub fn is_complete(&self) -> bool {
+        self.processed_events >= self.total_events
+    }
+
+    /// The replayed share of the events in percent, `100` when there
+    /// are no events at all
+    #[must_use]
+    pub fn percent(&self) -> u8 {
+        if self.total_events == 0 {
+            return 100;
+        }
+
+        let percent = self
+            .processed_events
+            .min(self.total_events) *
+            100 /
+            self.total_events;
+
+        u8::try_from(percent).unwrap_or(100)
+    }
+}
@@ -0,0 +1,135 @@
+use log::debug;
+use std::{
+    marker::PhantomData,
+    sync::{
+        Arc,
+        Mutex,
+        MutexGuard,
+    },
+};
+
+use crate::{
+    commands::ICommand,
+    errors::Error,
+    events::IEvent,
+    stores::IQueryStore,
+};
+
+use super::{
+    i_query::IQuery,
+    query_context::QueryContext,
+};
+
+/// A query store wrapper whose underlying store can be replaced
+/// atomically, e.g., by the `ProjectionRebuilder` once a rebuilt
+/// shadow store is ready. Cloning the wrapper yields a handle to the
+/// same underlying store, so readers and the `QueryProcessor` keep
+/// working with whichever store is current.
+pub struct SwappableQueryStore<
+    C: ICommand,
+    E: IEvent,
+    Q: IQuery<C, E>,
+    QS: IQueryStore<C, E, Q>,
+> {
+    store: Arc<Mutex<QS>>,
+    _phantom: PhantomData<(C, E, Q)>,
+}
+
+impl<
+        C: ICommand,
+        E: IEvent,
+        Q: IQuery<C, E>,
+        QS: IQueryStore<C, E, Q>,
+    > SwappableQueryStore<C, E, Q, QS>
+{
+    /// Constructor
+    pub fn new(store: QS) -> Self {
+        Self {
+            store: Arc::new(Mutex::new(store)),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Replaces the underlying store with `store` and returns the
+    /// previous one. Loads and saves either see the previous store
+    /// or the new one, never a mix of both.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` when the underlying store can not be
+    /// locked.
+    pub fn swap(
+        &self,
+        store: QS,
+    ) -> Result<QS, Error> {
+        self.swap_with(|| Ok(store))
+    }
+
+    /// Locks the underlying store, blocking its loads and saves,
+    /// until `prepare` yields the store replacing it, and returns the
+    /// previous one. The underlying store is left in place when
+    /// `prepare` fails.
+    pub(crate) fn swap_with<F: FnOnce() -> Result<QS, Error>>(
+        &self,
+        prepare: F,
+    ) -> Result<QS, Error> {
+        let mut current = self.lock()?;
+
+        let store = prepare()?;
+
+        debug!(
+            "swapping the store of query type '{}'",
+            Q::query_type()
+        );
+
+        Ok(std::mem::replace(&mut *current, store))
+    }
+
+    fn lock(&self) -> Result<MutexGuard<'_, QS>, Error> {
+        self.store.lock().map_err(|e| {
+            Error::Store {
+                message: format!(
+                    "unable to lock the query store: {e}"
+                ),
+                source: None,
+            }
+        })
+    }
+}
+
+impl<
+        C: ICommand,
+        E: IEvent,
+        Q: IQuery<C, E>,
+        QS: IQueryStore<C, E, Q>,
+    > Clone for SwappableQueryStore<C, E, Q, QS>
+{
+    fn clone(&self) -> Self {
+        Self {
+            store: Arc::clone(&self.store),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<
+        C: ICommand,
+        E: IEvent,
+        Q: IQuery<C, E>,
+        QS: IQueryStore<C, E, Q>,
+    > IQueryStore<C, E, Q> for SwappableQueryStore<C, E, Q, QS>
+{
+    fn load(
+        &mut self,
+        aggregate_id: &str,
+    ) -> Result<QueryContext<C, E, Q>, Error> {
+        self.lock()?.load(aggregate_id)
+    }
+
+    fn save(
+        &mut self,
+        context: QueryContext<C, E, Q>,
+    ) -> Result<(), Error> {
+        self.lock()?.save(context)
+    }
+}
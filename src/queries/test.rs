@@ -0,0 +1,382 @@
+use std::sync::{
+    Arc,
+    Mutex,
+};
+
+use crate::{
+    example_impl::*,
+    store_conformance::fixtures::email_updated,
+    Error,
+    IEventConsumer,
+    IEventStore,
+    IQueryStore,
+    InMemoryEventStore,
+    InMemoryQueryStore,
+    QueryContext,
+};
+
+use super::{
+    projection_rebuilder::ProjectionRebuilder,
+    query_processor::QueryProcessor,
+    replay_progress::ReplayProgress,
+    swappable_query_store::SwappableQueryStore,
+};
+
+type ThisEventStore =
+    InMemoryEventStore<CustomerCommand, CustomerEvent, Customer>;
+
+type ThisQueryStore = InMemoryQueryStore<
+    CustomerCommand,
+    CustomerEvent,
+    CustomerContactQuery,
+>;
+
+#[test]
+fn test_query_store() {
+    let mut store = ThisQueryStore::default();
+
+    assert_eq!(
+        store.load("test_id_A").unwrap(),
+        QueryContext::new(
+            "test_id_A".to_string(),
+            0,
+            CustomerContactQuery::default()
+        )
+    );
+
+    let context = QueryContext::new(
+        "test_id_A".to_string(),
+        2,
+        CustomerContactQuery {
+            name: "John Doe".to_string(),
+            email: "j@d.com".to_string(),
+            latest_address: String::new(),
+        },
+    );
+
+    store.save(context.clone()).unwrap();
+
+    assert_eq!(
+        store.clone().load("test_id_A").unwrap(),
+        context
+    );
+    assert_eq!(
+        store.load("test_id_B").unwrap().version,
+        0
+    );
+}
+
+#[test]
+fn test_query_processor() {
+    let mut store = ThisQueryStore::default();
+    let mut processor = QueryProcessor::new(store.clone());
+
+    assert!(processor
+        .process(&email_updated(
+            "test_id_A",
+            1,
+            "j@d.com"
+        ))
+        .unwrap());
+
+    processor.update(&email_updated(
+        "test_id_A",
+        2,
+        "john@d.com",
+    ));
+    processor.update(&email_updated(
+        "test_id_B",
+        1,
+        "jane@d.com",
+    ));
+
+    let context = store.load("test_id_A").unwrap();
+
+    assert_eq!(context.version, 2);
+    assert_eq!(context.payload.email, "john@d.com");
+
+    let context = store.load("test_id_B").unwrap();
+
+    assert_eq!(context.version, 1);
+    assert_eq!(context.payload.email, "jane@d.com");
+}
+
+#[test]
+fn test_query_processor_redelivery() {
+    let mut store = ThisQueryStore::default();
+    let mut processor = QueryProcessor::new(store.clone());
+
+    processor.update(&email_updated(
+        "test_id_A",
+        1,
+        "j@d.com",
+    ));
+    processor.update(&email_updated(
+        "test_id_A",
+        2,
+        "john@d.com",
+    ));
+
+    // redelivered events do not roll the query back
+    assert!(!processor
+        .process(&email_updated(
+            "test_id_A",
+            1,
+            "j@d.com"
+        ))
+        .unwrap());
+    assert!(!processor
+        .process(&email_updated(
+            "test_id_A",
+            2,
+            "john@d.com"
+        ))
+        .unwrap());
+
+    let context = store.load("test_id_A").unwrap();
+
+    assert_eq!(context.version, 2);
+    assert_eq!(context.payload.email, "john@d.com");
+}
+
+#[test]
+fn test_query_processor_rejects_gaps() {
+    let mut store = ThisQueryStore::default();
+    let mut processor = QueryProcessor::new(store.clone());
+
+    processor
+        .process(&email_updated(
+            "test_id_A",
+            1,
+            "j@d.com",
+        ))
+        .unwrap();
+
+    // an event delivered ahead of its predecessor is not applied
+    assert!(matches!(
+        processor.process(&email_updated(
+            "test_id_A",
+            3,
+            "doe@d.com"
+        )),
+        Err(Error::TechnicalError(_))
+    ));
+
+    let context = store.load("test_id_A").unwrap();
+
+    assert_eq!(context.version, 1);
+    assert_eq!(context.payload.email, "j@d.com");
+
+    // and is applied once the missing event has been delivered
+    for (sequence, email) in [(2, "john@d.com"), (3, "doe@d.com")] {
+        assert!(processor
+            .process(&email_updated(
+                "test_id_A",
+                sequence,
+                email
+            ))
+            .unwrap());
+    }
+
+    let context = store.load("test_id_A").unwrap();
+
+    assert_eq!(context.version, 3);
+    assert_eq!(context.payload.email, "doe@d.com");
+}
+
+fn committed_events() -> ThisEventStore {
+    let mut store = ThisEventStore::default();
+
+    store
+        .commit(
+            vec![email_updated("test_id_A", 1, "j@d.com")],
+            0,
+        )
+        .unwrap();
+    store
+        .commit(
+            vec![email_updated(
+                "test_id_B",
+                1,
+                "jane@d.com",
+            )],
+            0,
+        )
+        .unwrap();
+    store
+        .commit(
+            vec![email_updated(
+                "test_id_A",
+                2,
+                "john@d.com",
+            )],
+            1,
+        )
+        .unwrap();
+
+    store
+}
+
+#[test]
+fn test_replay_progress() {
+    assert!(ReplayProgress::default().is_complete());
+    assert_eq!(ReplayProgress::default().percent(), 100);
+
+    let progress = ReplayProgress {
+        total_events: 3,
+        processed_events: 1,
+        aggregates: 1,
+    };
+
+    assert!(!progress.is_complete());
+    assert_eq!(progress.percent(), 33);
+}
+
+#[test]
+fn test_projection_replay() {
+    let reports = Arc::new(Mutex::new(Vec::new()));
+    let recorded = Arc::clone(&reports);
+
+    let mut shadow = ThisQueryStore::default();
+
+    let progress = ProjectionRebuilder::new(committed_events())
+        .with_progress(move |x| {
+            recorded.lock().unwrap().push(*x);
+        })
+        .replay(&mut shadow)
+        .unwrap();
+
+    assert_eq!(
+        progress,
+        ReplayProgress {
+            total_events: 3,
+            processed_events: 3,
+            aggregates: 2,
+        }
+    );
+
+    let reports = reports.lock().unwrap();
+
+    assert_eq!(reports.len(), 3);
+    assert_eq!(reports[0].processed_events, 1);
+    assert_eq!(reports[1].aggregates, 2);
+    assert_eq!(reports[2], progress);
+
+    let context = shadow.load("test_id_A").unwrap();
+
+    assert_eq!(context.version, 2);
+    assert_eq!(context.payload.email, "john@d.com");
+
+    let context = shadow.load("test_id_B").unwrap();
+
+    assert_eq!(context.version, 1);
+    assert_eq!(context.payload.email, "jane@d.com");
+}
+
+#[test]
+fn test_projection_rebuild_swaps_store() {
+    let mut old = ThisQueryStore::default();
+
+    // a stale query left behind by a buggy projection
+    old.save(QueryContext::new(
+        "test_id_A".to_string(),
+        2,
+        CustomerContactQuery {
+            name: String::new(),
+            email: "stale@d.com".to_string(),
+            latest_address: String::new(),
+        },
+    ))
+    .unwrap();
+
+    let mut live = SwappableQueryStore::new(old.clone());
+    let mut processor = QueryProcessor::new(live.clone());
+
+    ProjectionRebuilder::new(committed_events())
+        .rebuild(&live, ThisQueryStore::default())
+        .unwrap();
+
+    assert_eq!(
+        live.load("test_id_A")
+            .unwrap()
+            .payload
+            .email,
+        "john@d.com"
+    );
+    assert_eq!(
+        live.load("test_id_B").unwrap().version,
+        1
+    );
+
+    // the previous store is left untouched
+    assert_eq!(
+        old.load("test_id_A")
+            .unwrap()
+            .payload
+            .email,
+        "stale@d.com"
+    );
+
+    // processors registered with the live store follow the swap
+    assert!(processor
+        .process(&email_updated(
+            "test_id_A",
+            3,
+            "jd@d.com"
+        ))
+        .unwrap());
+    assert!(!processor
+        .process(&email_updated(
+            "test_id_B",
+            1,
+            "jane@d.com"
+        ))
+        .unwrap());
+    assert_eq!(
+        live.load("test_id_A").unwrap().version,
+        3
+    );
+}
+
+#[test]
+fn test_projection_rebuild_with_concurrent_commits() {
+    let events = committed_events();
+    let mut writer = events.clone();
+
+    let live = SwappableQueryStore::new(ThisQueryStore::default());
+
+    // commits an event as soon as the initially committed events are
+    // replayed
+    let progress = ProjectionRebuilder::new(events)
+        .with_batch_size(2)
+        .with_progress(move |x| {
+            if x.processed_events == 3 {
+                writer
+                    .commit(
+                        vec![email_updated(
+                            "test_id_C",
+                            1,
+                            "jim@d.com",
+                        )],
+                        0,
+                    )
+                    .unwrap();
+            }
+        })
+        .rebuild(&live, ThisQueryStore::default())
+        .unwrap();
+
+    assert_eq!(
+        progress,
+        ReplayProgress {
+            total_events: 4,
+            processed_events: 4,
+            aggregates: 3,
+        }
+    );
+
+    let context = live.clone().load("test_id_C").unwrap();
+
+    assert_eq!(context.version, 1);
+    assert_eq!(context.payload.email, "jim@d.com");
+}
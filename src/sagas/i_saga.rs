@@ -0,0 +1,159 @@
+use serde::{
+    de::DeserializeOwned,
+    Serialize,
+};
+use std::fmt::Debug;
+
+use crate::{
+    commands::ICommand,
+    errors::Error,
+    events::{
+        EventContext,
+        IEvent,
+    },
+};
+
+use super::saga_action::SagaAction;
+
+/// An `ISaga`, aka process manager, coordinates work across
+/// aggregates. It consumes the events of one aggregate type, keeps
+/// its own state per business flow and reacts by requesting
+/// `SagaAction`s, e.g., dispatching commands of type `SC` to other
+/// aggregates.
+///
+/// A saga instance is identified by a correlation id, which is read
+/// from the event metadata by default (see `CorrelationIdEnricher`).
+/// Every instance starts from `Self::default()`, and its state is
+/// saved in an `ISagaStore` between events, which is why the state
+/// must be serializable.
+///
+/// # Examples
+/// ```rust
+/// use serde::{
+///     Deserialize,
+///     Serialize,
+/// };
+/// use std::time::Duration;
+///
+/// use cqrs_es2::{
+///     example_impl::{
+///         CustomerCommand,
+///         CustomerEvent,
+///         NotificationCommand,
+///         SendEmail,
+///     },
+///     Error,
+///     EventContext,
+///     ISaga,
+///     SagaAction,
+/// };
+///
+/// #[derive(
+///     Debug,
+///     PartialEq,
+///     Default,
+///     Clone,
+///     Serialize,
+///     Deserialize
+/// )]
+/// struct WelcomeSaga {
+///     email: String,
+/// }
+///
+/// impl ISaga<CustomerCommand, CustomerEvent, NotificationCommand>
+///     for WelcomeSaga
+/// {
+///     fn saga_type() -> &'static str {
+///         "welcome_saga"
+///     }
+///
+///     fn handle(
+///         &mut self,
+///         event: &EventContext<CustomerCommand, CustomerEvent>,
+///     ) -> Result<Vec<SagaAction<NotificationCommand>>, Error>
+///     {
+///         match &event.payload {
+///             CustomerEvent::EmailUpdated(payload) => {
+///                 self.email = payload.new_email.clone();
+///
+///                 Ok(vec![
+///                     SagaAction::dispatch(
+///                         &event.aggregate_id,
+///                         NotificationCommand::SendEmail(
+///                             SendEmail {
+///                                 to: self.email.clone(),
+///                                 subject: "Welcome".to_string(),
+///                             },
+///                         ),
+///                     ),
+///                     SagaAction::Complete,
+///                 ])
+///             },
+///             _ => Ok(Vec::new()),
+///         }
+///     }
+/// }
+/// ```
+pub trait ISaga<C: ICommand, E: IEvent, SC: ICommand>:
+    Debug
+    + PartialEq
+    + Default
+    + Clone
+    + Serialize
+    + DeserializeOwned
+    + Sync
+    + Send {
+    /// `saga_type` is a unique identifier for this saga
+    fn saga_type() -> &'static str;
+
+    /// The correlation id of the saga instance an event belongs to.
+    /// Events without one are ignored by the saga. Defaults to the
+    /// correlation id of the event metadata.
+    fn correlation_id(event: &EventContext<C, E>) -> Option<String> {
+        event
+            .correlation_id()
+            .map(str::to_string)
+    }
+
+    /// Consumes an event and returns the actions to perform.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` when the event can not be handled, in
+    /// which case the saga state is not saved.
+    fn handle(
+        &mut self,
+        event: &EventContext<C, E>,
+    ) -> Result<Vec<SagaAction<SC>>, Error>;
+
+    /// Reacts to the timeout `name` scheduled through
+    /// `SagaAction::ScheduleTimeout`. Does nothing by default.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` when the timeout can not be handled, in
+    /// which case the saga state is not saved.
+    fn on_timeout(
+        &mut self,
+        _name: &str,
+    ) -> Result<Vec<SagaAction<SC>>, Error> {
+        Ok(Vec::new())
+    }
+
+    /// Compensates for a dispatched command that failed with `error`,
+    /// e.g., by dispatching commands undoing the completed steps.
+    /// The remaining dispatches of the failed batch are skipped.
+    /// Does nothing by default.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` when the failure can not be compensated.
+    fn compensate(
+        &mut self,
+        _aggregate_id: &str,
+        _command: &SC,
+        _error: &Error,
+    ) -> Result<Vec<SagaAction<SC>>, Error> {
+        Ok(Vec::new())
+    }
+}
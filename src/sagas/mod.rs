@@ -0,0 +1,17 @@
+//! # sagas
+//!
+//! A central location for sagas, aka process managers, coordinating
+//! work across aggregates
+
+pub use i_saga::ISaga;
+pub use saga_action::SagaAction;
+pub use saga_context::SagaContext;
+pub use saga_manager::SagaManager;
+
+mod i_saga;
+mod saga_action;
+mod saga_context;
+mod saga_manager;
+
+#[cfg(test)]
+mod test;
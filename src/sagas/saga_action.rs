@@ -0,0 +1,70 @@
+use std::time::Duration;
+
+use crate::commands::ICommand;
+
+/// An action requested by an `ISaga` in response to an event, a
+/// timeout or a failed command.
+#[derive(Debug, PartialEq, Clone)]
+pub enum SagaAction<SC: ICommand> {
+    /// Dispatches `command` to the aggregate instance identified by
+    /// `aggregate_id`.
+    Dispatch {
+        /// The id of the target aggregate instance
+        aggregate_id: String,
+
+        /// The command to dispatch
+        command: SC,
+    },
+
+    /// Schedules the timeout `name` to fire once `after` has
+    /// elapsed, replacing any pending timeout with the same
+    /// name.
+    ScheduleTimeout {
+        /// The name of the timeout
+        name: String,
+
+        /// The delay before the timeout fires
+        after: Duration,
+    },
+
+    /// Cancels the pending timeout `name`, if any.
+    CancelTimeout(String),
+
+    /// Marks the saga as completed. A completed saga ignores any
+    /// further events and timeouts.
+    Complete,
+}
+
+impl<SC: ICommand> SagaAction<SC> {
+    /// Convenience function to construct a `SagaAction::Dispatch`
+    #[must_use]
+    pub fn dispatch(
+        aggregate_id: &str,
+        command: SC,
+    ) -> Self {
+        SagaAction::Dispatch {
+            aggregate_id: aggregate_id.to_string(),
+            command,
+        }
+    }
+
+    /// Convenience function to construct a
+    /// `SagaAction::ScheduleTimeout`
+    #[must_use]
+    pub fn schedule_timeout(
+        name: &str,
+        after: Duration,
+    ) -> Self {
+        SagaAction::ScheduleTimeout {
+            name: name.to_string(),
+            after,
+        }
+    }
+
+    /// Convenience function to construct a
+    /// `SagaAction::CancelTimeout`
+    #[must_use]
+    pub fn cancel_timeout(name: &str) -> Self {
+        SagaAction::CancelTimeout(name.to_string())
+    }
+}
@@ -0,0 +1,92 @@
+use chrono::{
+    DateTime,
+    Utc,
+};
+use log::trace;
+use std::{
+    collections::{
+        BTreeMap,
+        HashMap,
+    },
+    fmt::Debug,
+    marker::PhantomData,
+};
+
+use crate::{
+    commands::ICommand,
+    events::IEvent,
+};
+
+use super::i_saga::ISaga;
+
+/// Returns the saga and the context around it that is needed when
+/// saving it in a saga store implementation.
+#[derive(Debug, PartialEq, Clone)]
+pub struct SagaContext<
+    C: ICommand,
+    E: IEvent,
+    SC: ICommand,
+    S: ISaga<C, E, SC>,
+> {
+    /// The correlation id identifying the saga instance.
+    pub correlation_id: String,
+
+    /// The current state of the saga instance.
+    pub payload: S,
+
+    /// The sequence of the last event consumed from every aggregate
+    /// instance, used to skip redelivered events.
+    pub versions: HashMap<String, i64>,
+
+    /// The pending timeouts by name along with their deadlines.
+    pub timeouts: BTreeMap<String, DateTime<Utc>>,
+
+    /// Whether the saga has completed.
+    pub completed: bool,
+
+    _phantom: PhantomData<(C, E, SC)>,
+}
+
+impl<C: ICommand, E: IEvent, SC: ICommand, S: ISaga<C, E, SC>>
+    SagaContext<C, E, SC, S>
+{
+    /// Constructor of a saga instance that has not consumed any
+    /// events yet
+    pub fn new(
+        correlation_id: String,
+        payload: S,
+    ) -> Self {
+        let x = Self {
+            correlation_id,
+            payload,
+            versions: HashMap::new(),
+            timeouts: BTreeMap::new(),
+            completed: false,
+            _phantom: PhantomData,
+        };
+
+        trace!("Created new {x:?}");
+
+        x
+    }
+
+    /// The names of the timeouts whose deadline is at or before
+    /// `now`, ordered by deadline
+    pub fn due_timeouts(
+        &self,
+        now: DateTime<Utc>,
+    ) -> Vec<String> {
+        let mut result: Vec<_> = self
+            .timeouts
+            .iter()
+            .filter(|(_, deadline)| **deadline <= now)
+            .collect();
+
+        result.sort_by_key(|(_, deadline)| **deadline);
+
+        result
+            .into_iter()
+            .map(|(name, _)| name.clone())
+            .collect()
+    }
+}
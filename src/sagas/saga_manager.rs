@@ -0,0 +1,396 @@
+use chrono::{
+    DateTime,
+    Duration,
+    Utc,
+};
+use log::{
+    debug,
+    error,
+    trace,
+    warn,
+};
+use std::{
+    collections::HashMap,
+    marker::PhantomData,
+};
+
+use crate::{
+    commands::ICommand,
+    cqrs::ICommandDispatcher,
+    errors::Error,
+    events::{
+        EventContext,
+        IEvent,
+        IEventConsumer,
+    },
+    metadata::CORRELATION_ID_KEY,
+    stores::ISagaStore,
+};
+
+use super::{
+    i_saga::ISaga,
+    saga_action::SagaAction,
+    saga_context::SagaContext,
+};
+
+/// `SagaManager` drives the instances of an `ISaga`. For every event
+/// it loads the saga instance of the event correlation id, lets the
+/// saga handle the event, performs the requested actions and saves
+/// the saga back.
+///
+/// Commands are dispatched with the correlation id of the saga in
+/// their metadata, so the whole business flow shares it. When a
+/// dispatch fails, the saga is asked to compensate and the remaining
+/// dispatches of the batch are skipped. The saga state is saved once
+/// the dispatches have been attempted, which makes the delivery of
+/// the commands at-least-once.
+///
+/// Events whose sequence is at or below the last consumed sequence
+/// of their aggregate are skipped, as are events of completed
+/// sagas.
+///
+/// Timeouts are not fired on their own, `trigger_timeouts` has to be
+/// called periodically, e.g., from a scheduler.
+///
+/// The manager is itself an `IEventConsumer` so it can be registered
+/// with the `CqrsFramework` of the source aggregate.
+///
+/// # Examples
+/// ```rust
+/// use cqrs_es2::{
+///     example_impl::{
+///         Customer,
+///         CustomerCommand,
+///         CustomerEvent,
+///         EmailVerificationSaga,
+///         Notification,
+///         NotificationCommand,
+///         NotificationEvent,
+///         UpdateEmail,
+///     },
+///     CqrsFramework,
+///     InMemoryEventStore,
+///     InMemorySagaStore,
+///     MetadataEnrichers,
+///     SagaManager,
+/// };
+///
+/// let notifications = InMemoryEventStore::<
+///     NotificationCommand,
+///     NotificationEvent,
+///     Notification,
+/// >::default();
+///
+/// let saga_manager = SagaManager::new(
+///     InMemorySagaStore::<_, _, _, EmailVerificationSaga>::default(
+///     ),
+///     CqrsFramework::new(notifications.clone(), Vec::new()),
+/// );
+///
+/// let mut cqrs =
+///     CqrsFramework::new(
+///         InMemoryEventStore::<
+///             CustomerCommand,
+///             CustomerEvent,
+///             Customer,
+///         >::default(),
+///         vec![Box::new(saga_manager)],
+///     )
+///     .with_metadata_enrichers(MetadataEnrichers::standard());
+///
+/// cqrs.execute(
+///     "customer-1",
+///     CustomerCommand::UpdateEmail(UpdateEmail {
+///         new_email: "j@d.com".to_string(),
+///     }),
+/// )
+/// .unwrap();
+/// ```
+pub struct SagaManager<C, E, SC, S, SS, D>
+where
+    C: ICommand,
+    E: IEvent,
+    SC: ICommand,
+    S: ISaga<C, E, SC>,
+    SS: ISagaStore<C, E, SC, S>,
+    D: ICommandDispatcher<SC>, {
+    store: SS,
+    dispatcher: D,
+    _phantom: PhantomData<(C, E, SC, S)>,
+}
+
+impl<C, E, SC, S, SS, D> SagaManager<C, E, SC, S, SS, D>
+where
+    C: ICommand,
+    E: IEvent,
+    SC: ICommand,
+    S: ISaga<C, E, SC>,
+    SS: ISagaStore<C, E, SC, S>,
+    D: ICommandDispatcher<SC>,
+{
+    /// Constructor
+    pub fn new(
+        store: SS,
+        dispatcher: D,
+    ) -> Self {
+        Self {
+            store,
+            dispatcher,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Lets the saga instance of the event correlation id handle the
+    /// event. Returns `false` when the event was skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` when the saga fails to handle the event or
+    /// to compensate, when a compensating command fails, or when the
+    /// saga store fails.
+    pub fn process(
+        &mut self,
+        event: &EventContext<C, E>,
+    ) -> Result<bool, Error> {
+        let Some(correlation_id) = S::correlation_id(event)
+        else {
+            trace!(
+                "skipping event {} of aggregate '{}' without a \
+                 correlation id for saga of type '{}'",
+                event.sequence,
+                event.aggregate_id,
+                S::saga_type()
+            );
+
+            return Ok(false);
+        };
+
+        let mut context = self.store.load(&correlation_id)?;
+
+        let version = context
+            .versions
+            .get(&event.aggregate_id)
+            .copied()
+            .unwrap_or_default();
+
+        if context.completed || event.sequence <= version {
+            trace!(
+                "skipping event {} of aggregate '{}' for saga of \
+                 type '{}' with correlation id '{}'",
+                event.sequence,
+                event.aggregate_id,
+                S::saga_type(),
+                correlation_id
+            );
+
+            return Ok(false);
+        }
+
+        let actions = context.payload.handle(event)?;
+
+        context.versions.insert(
+            event.aggregate_id.clone(),
+            event.sequence,
+        );
+
+        let result = self.perform(&mut context, actions);
+
+        self.store.save(context)?;
+
+        result.map(|()| true)
+    }
+
+    /// Fires every timeout due at or before `now` and returns the
+    /// number of fired timeouts.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` when a saga fails to handle a timeout or
+    /// to compensate, when a compensating command fails, or when the
+    /// saga store fails.
+    pub fn trigger_timeouts(
+        &mut self,
+        now: DateTime<Utc>,
+    ) -> Result<usize, Error> {
+        let mut count = 0;
+
+        for mut context in self.store.load_due(now)? {
+            let mut result = Ok(());
+
+            for name in context.due_timeouts(now) {
+                if context.completed {
+                    break;
+                }
+
+                debug!(
+                    "firing timeout '{}' of saga of type '{}' with \
+                     correlation id '{}'",
+                    name,
+                    S::saga_type(),
+                    context.correlation_id
+                );
+
+                context.timeouts.remove(&name);
+                count += 1;
+
+                result = context
+                    .payload
+                    .on_timeout(&name)
+                    .and_then(|x| self.perform(&mut context, x));
+
+                if result.is_err() {
+                    break;
+                }
+            }
+
+            self.store.save(context)?;
+
+            result?;
+        }
+
+        Ok(count)
+    }
+
+    fn perform(
+        &mut self,
+        context: &mut SagaContext<C, E, SC, S>,
+        actions: Vec<SagaAction<SC>>,
+    ) -> Result<(), Error> {
+        let mut metadata = HashMap::new();
+        metadata.insert(
+            CORRELATION_ID_KEY.to_string(),
+            context.correlation_id.clone(),
+        );
+
+        for (aggregate_id, command) in apply(context, actions)? {
+            let e = match self.dispatcher.dispatch(
+                &aggregate_id,
+                command.clone(),
+                metadata.clone(),
+            ) {
+                Ok(()) => continue,
+                Err(e) => e,
+            };
+
+            warn!(
+                "compensating failed command on aggregate '{}' \
+                 dispatched by saga of type '{}' with correlation \
+                 id '{}': {}",
+                aggregate_id,
+                S::saga_type(),
+                context.correlation_id,
+                e
+            );
+
+            let actions = context.payload.compensate(
+                &aggregate_id,
+                &command,
+                &e,
+            )?;
+
+            for (aggregate_id, command) in apply(context, actions)? {
+                self.dispatcher
+                    .dispatch(
+                        &aggregate_id,
+                        command,
+                        metadata.clone(),
+                    )
+                    .map_err(|e| {
+                        error!(
+                            "compensating command on aggregate '{}' \
+                             dispatched by saga of type '{}' with \
+                             correlation id '{}' failed: {}",
+                            aggregate_id,
+                            S::saga_type(),
+                            context.correlation_id,
+                            e
+                        );
+
+                        e
+                    })?;
+            }
+
+            break;
+        }
+
+        Ok(())
+    }
+}
+
+/// Applies the state changing actions to the saga context and
+/// returns the commands to dispatch
+fn apply<C, E, SC, S>(
+    context: &mut SagaContext<C, E, SC, S>,
+    actions: Vec<SagaAction<SC>>,
+) -> Result<Vec<(String, SC)>, Error>
+where
+    C: ICommand,
+    E: IEvent,
+    SC: ICommand,
+    S: ISaga<C, E, SC>, {
+    let mut result = Vec::new();
+
+    for action in actions {
+        match action {
+            SagaAction::Dispatch {
+                aggregate_id,
+                command,
+            } => result.push((aggregate_id, command)),
+            SagaAction::ScheduleTimeout { name, after } => {
+                let after =
+                    Duration::from_std(after).map_err(|e| {
+                        Error::TechnicalError(format!(
+                            "invalid delay of timeout '{name}': {e}"
+                        ))
+                    })?;
+
+                let Some(deadline) =
+                    Utc::now().checked_add_signed(after)
+                else {
+                    return Err(Error::TechnicalError(format!(
+                        "the deadline of timeout '{name}' is out of \
+                         range"
+                    )));
+                };
+
+                context.timeouts.insert(name, deadline);
+            },
+            SagaAction::CancelTimeout(name) => {
+                context.timeouts.remove(&name);
+            },
+            SagaAction::Complete => {
+                context.completed = true;
+                context.timeouts.clear();
+            },
+        }
+    }
+
+    Ok(result)
+}
+
+impl<C, E, SC, S, SS, D> IEventConsumer<C, E>
+    for SagaManager<C, E, SC, S, SS, D>
+where
+    C: ICommand,
+    E: IEvent,
+    SC: ICommand,
+    S: ISaga<C, E, SC>,
+    SS: ISagaStore<C, E, SC, S>,
+    D: ICommandDispatcher<SC>,
+{
+    fn update(
+        &mut self,
+        event: &EventContext<C, E>,
+    ) {
+        if let Err(e) = self.process(event) {
+            error!(
+                "unable to process event {} of aggregate '{}' by \
+                 saga of type '{}': {}",
+                event.sequence,
+                event.aggregate_id,
+                S::saga_type(),
+                e
+            );
+        }
+    }
+}
@@ -0,0 +1,318 @@
+use chrono::{
+    Duration,
+    Utc,
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use std::time;
+
+use crate::{
+    example_impl::*,
+    store_conformance::fixtures::email_updated,
+    CqrsFramework,
+    Error,
+    EventContext,
+    IEventStore,
+    ISaga,
+    ISagaStore,
+    InMemoryEventStore,
+    InMemorySagaStore,
+    MetadataEnrichers,
+    SagaAction,
+    CORRELATION_ID_KEY,
+};
+
+use super::saga_manager::SagaManager;
+
+type NotificationStore = InMemoryEventStore<
+    NotificationCommand,
+    NotificationEvent,
+    Notification,
+>;
+
+type ThisSagaStore = InMemorySagaStore<
+    CustomerCommand,
+    CustomerEvent,
+    NotificationCommand,
+    EmailVerificationSaga,
+>;
+
+type ThisSagaManager = SagaManager<
+    CustomerCommand,
+    CustomerEvent,
+    NotificationCommand,
+    EmailVerificationSaga,
+    ThisSagaStore,
+    CqrsFramework<
+        NotificationCommand,
+        NotificationEvent,
+        Notification,
+        NotificationStore,
+    >,
+>;
+
+fn saga_manager(
+    sagas: &ThisSagaStore,
+    notifications: &NotificationStore,
+) -> ThisSagaManager {
+    SagaManager::new(
+        sagas.clone(),
+        CqrsFramework::new(notifications.clone(), Vec::new()),
+    )
+}
+
+fn correlated(
+    context: EventContext<CustomerCommand, CustomerEvent>,
+    correlation_id: &str,
+) -> EventContext<CustomerCommand, CustomerEvent> {
+    let mut context = context;
+    context.metadata.insert(
+        CORRELATION_ID_KEY.to_string(),
+        correlation_id.to_string(),
+    );
+    context
+}
+
+fn subjects(
+    notifications: &mut NotificationStore
+) -> Vec<(String, Option<String>)> {
+    notifications
+        .load_events("test_id_A")
+        .unwrap()
+        .into_iter()
+        .map(|x| {
+            let correlation_id =
+                x.correlation_id().map(str::to_string);
+
+            match x.payload {
+                NotificationEvent::EmailSent(payload) => {
+                    (payload.subject, correlation_id)
+                },
+            }
+        })
+        .collect()
+}
+
+#[test]
+fn test_process() {
+    let mut sagas = ThisSagaStore::default();
+    let mut notifications = NotificationStore::default();
+    let mut manager = saga_manager(&sagas, &notifications);
+
+    let event = correlated(
+        email_updated("test_id_A", 1, "j@d.com"),
+        "flow-1",
+    );
+
+    assert!(manager.process(&event).unwrap());
+
+    // redelivered events are skipped
+    assert!(!manager.process(&event).unwrap());
+
+    // events without a correlation id are ignored
+    assert!(!manager
+        .process(&email_updated(
+            "test_id_A",
+            2,
+            "j@d.com"
+        ))
+        .unwrap());
+
+    assert_eq!(
+        subjects(&mut notifications),
+        vec![(
+            VERIFICATION_SUBJECT.to_string(),
+            Some("flow-1".to_string())
+        )]
+    );
+
+    let context = sagas.load("flow-1").unwrap();
+
+    assert_eq!(context.payload.email, "j@d.com");
+    assert_eq!(context.versions["test_id_A"], 1);
+    assert!(context
+        .timeouts
+        .contains_key(REMINDER_TIMEOUT));
+    assert!(!context.completed);
+}
+
+#[test]
+fn test_trigger_timeouts() {
+    let mut sagas = ThisSagaStore::default();
+    let mut notifications = NotificationStore::default();
+    let mut manager = saga_manager(&sagas, &notifications);
+
+    manager
+        .process(&correlated(
+            email_updated("test_id_A", 1, "j@d.com"),
+            "flow-1",
+        ))
+        .unwrap();
+
+    // not due yet
+    assert_eq!(
+        manager
+            .trigger_timeouts(Utc::now())
+            .unwrap(),
+        0
+    );
+
+    let later = Utc::now() + Duration::days(2);
+
+    assert_eq!(
+        manager.trigger_timeouts(later).unwrap(),
+        1
+    );
+    assert_eq!(
+        manager.trigger_timeouts(later).unwrap(),
+        0
+    );
+
+    assert_eq!(
+        subjects(&mut notifications),
+        vec![
+            (
+                VERIFICATION_SUBJECT.to_string(),
+                Some("flow-1".to_string())
+            ),
+            (
+                REMINDER_SUBJECT.to_string(),
+                Some("flow-1".to_string())
+            ),
+        ]
+    );
+
+    let context = sagas.load("flow-1").unwrap();
+
+    assert!(context.completed);
+    assert!(context.timeouts.is_empty());
+
+    // completed sagas ignore further events
+    assert!(!manager
+        .process(&correlated(
+            email_updated("test_id_A", 2, "k@d.com"),
+            "flow-1"
+        ))
+        .unwrap());
+}
+
+#[test]
+fn test_compensation() {
+    let mut sagas = ThisSagaStore::default();
+    let mut notifications = NotificationStore::default();
+    let mut manager = saga_manager(&sagas, &notifications);
+
+    // the notification aggregate rejects an empty address
+    assert!(manager
+        .process(&correlated(
+            email_updated("test_id_A", 1, ""),
+            "flow-1"
+        ))
+        .unwrap());
+
+    assert_eq!(subjects(&mut notifications), Vec::new());
+
+    let context = sagas.load("flow-1").unwrap();
+
+    assert!(context.payload.failed);
+    assert!(context.completed);
+    assert!(context.timeouts.is_empty());
+}
+
+#[test]
+fn test_saga_as_consumer() {
+    let sagas = ThisSagaStore::default();
+    let mut notifications = NotificationStore::default();
+
+    let mut cqrs =
+        CqrsFramework::new(
+            InMemoryEventStore::<
+                CustomerCommand,
+                CustomerEvent,
+                Customer,
+            >::default(),
+            vec![Box::new(saga_manager(
+                &sagas,
+                &notifications,
+            ))],
+        )
+        .with_metadata_enrichers(MetadataEnrichers::standard());
+
+    let events = cqrs
+        .execute(
+            "test_id_A",
+            CustomerCommand::UpdateEmail(UpdateEmail {
+                new_email: "j@d.com".to_string(),
+            }),
+        )
+        .unwrap();
+
+    let correlation_id = events[0]
+        .correlation_id()
+        .map(str::to_string);
+
+    assert_eq!(
+        subjects(&mut notifications),
+        vec![(
+            VERIFICATION_SUBJECT.to_string(),
+            correlation_id
+        )]
+    );
+}
+
+/// Schedules a timeout too far in the future to be represented
+#[derive(
+    Debug,
+    PartialEq,
+    Default,
+    Clone,
+    Serialize,
+    Deserialize
+)]
+struct DistantTimeoutSaga;
+
+impl ISaga<CustomerCommand, CustomerEvent, NotificationCommand>
+    for DistantTimeoutSaga
+{
+    fn saga_type() -> &'static str {
+        "distant_timeout_saga"
+    }
+
+    fn handle(
+        &mut self,
+        _event: &EventContext<CustomerCommand, CustomerEvent>,
+    ) -> Result<Vec<SagaAction<NotificationCommand>>, Error> {
+        Ok(vec![SagaAction::schedule_timeout(
+            "distant",
+            time::Duration::from_secs(1_000_000_000_000_000),
+        )])
+    }
+}
+
+#[test]
+fn test_timeout_deadline_out_of_range() {
+    let sagas = InMemorySagaStore::<
+        CustomerCommand,
+        CustomerEvent,
+        NotificationCommand,
+        DistantTimeoutSaga,
+    >::default();
+    let notifications = NotificationStore::default();
+
+    let mut manager = SagaManager::new(
+        sagas,
+        CqrsFramework::new(notifications, Vec::new()),
+    );
+
+    assert!(matches!(
+        manager
+            .process(&correlated(
+                email_updated("test_id_A", 1, "j@d.com"),
+                "flow-1"
+            ))
+            .unwrap_err(),
+        Error::TechnicalError(_)
+    ));
+}
@@ -0,0 +1,578 @@
+use aes_gcm::{
+    aead::{
+        generic_array::GenericArray,
+        Aead,
+        AeadCore,
+        KeyInit,
+        OsRng,
+        Payload,
+    },
+    aes::{
+        cipher::{
+            BlockEncrypt,
+            InvalidLength,
+        },
+        Aes256,
+    },
+    Aes256Gcm,
+    Nonce,
+};
+use base64::{
+    engine::general_purpose::STANDARD,
+    Engine,
+};
+use log::{
+    debug,
+    trace,
+};
+use serde_json::{
+    json,
+    Value,
+};
+use std::{
+    collections::HashMap,
+    fmt::{
+        Debug,
+        Formatter,
+    },
+};
+
+use crate::{
+    commands::ICommand,
+    errors::Error,
+    events::{
+        EventContext,
+        EventUpcasters,
+        IEvent,
+        SerializedEvent,
+    },
+    metadata::SUBJECT_ID_KEY,
+    stores::IKeyStore,
+};
+
+/// The value that replaces an encrypted field once its key has been
+/// deleted.
+pub const REDACTED_PLACEHOLDER: &str = "[redacted]";
+
+/// The key of the JSON object that replaces an encrypted field in the
+/// payload of a `SerializedEvent`.
+pub const ENCRYPTED_FIELD_KEY: &str = "$encrypted";
+
+/// The key of the JSON object of an encrypted field holding the id
+/// of the key it was encrypted with.
+pub const KEY_ID_FIELD_KEY: &str = "$key_id";
+
+/// The length of the AES-GCM nonce stored ahead of the ciphertext
+const NONCE_LENGTH: usize = 12;
+
+/// The number of bytes of the key check value making up a key id
+const KEY_ID_LENGTH: usize = 8;
+
+/// A field of an event type holding personal data
+struct EncryptedField {
+    path: String,
+    placeholder: Value,
+}
+
+/// `CryptoShredder` encrypts the fields holding personal data in the
+/// payloads of `SerializedEvent`s before they are persisted, and
+/// decrypts them transparently when they are loaded.
+///
+/// Every subject has its own AES-256-GCM key kept in an `IKeyStore`.
+/// The subject is the aggregate id unless the event metadata records
+/// a `SUBJECT_ID_KEY`. Deleting the key of a subject with `forget`
+/// shreds its personal data: the encrypted fields then deserialize
+/// to their placeholder, `REDACTED_PLACEHOLDER` by default, instead
+/// of failing. The placeholder must deserialize into the type of the
+/// field. Every encrypted field records the id of its key, so the
+/// fields encrypted before a subject was forgotten stay redacted
+/// once the subject gets a new key with its next event.
+///
+/// Fields are addressed by their dotted path within the payload,
+/// e.g., `address.street`, and missing fields are skipped. The
+/// ciphertext of a field is authenticated along with its subject,
+/// path and event identity, i.e., the aggregate type, id and
+/// sequence and the event type, so it can not be moved to another
+/// field, event or subject.
+///
+/// The `InMemoryEventStore`, the `FileEventStore` and the
+/// `SqliteEventStore` configured `with_shredder` encrypt the events
+/// they commit and decrypt the events they load.
+///
+/// # Examples
+/// ```rust
+/// use cqrs_es2::{
+///     example_impl::{
+///         CustomerCommand,
+///         CustomerEvent,
+///         EmailUpdated,
+///     },
+///     CryptoShredder,
+///     EventContext,
+///     InMemoryKeyStore,
+///     REDACTED_PLACEHOLDER,
+/// };
+///
+/// let mut shredder =
+///     CryptoShredder::new(InMemoryKeyStore::default())
+///         .with_encrypted_field("EmailUpdated", "new_email");
+///
+/// let context = EventContext::<CustomerCommand, CustomerEvent>::new(
+///     "customer-1".to_string(),
+///     1,
+///     CustomerEvent::EmailUpdated(EmailUpdated {
+///         new_email: "john@example.com".to_string(),
+///     }),
+///     Default::default(),
+/// );
+///
+/// let serialized = shredder
+///     .serialize("customer", &context)
+///     .unwrap();
+///
+/// assert!(serialized.payload["new_email"].is_object());
+///
+/// shredder.forget("customer-1").unwrap();
+///
+/// let loaded: EventContext<CustomerCommand, CustomerEvent> =
+///     shredder
+///         .deserialize(serialized)
+///         .unwrap();
+///
+/// assert_eq!(
+///     loaded.payload,
+///     CustomerEvent::EmailUpdated(EmailUpdated {
+///         new_email: REDACTED_PLACEHOLDER.to_string(),
+///     })
+/// );
+/// ```
+pub struct CryptoShredder<KS: IKeyStore> {
+    key_store: KS,
+    fields: HashMap<String, Vec<EncryptedField>>,
+}
+
+impl<KS: IKeyStore> CryptoShredder<KS> {
+    /// Constructor
+    pub fn new(key_store: KS) -> Self {
+        Self {
+            key_store,
+            fields: HashMap::new(),
+        }
+    }
+
+    /// Marks the field at `path` of the events of type `event_type`
+    /// as personal data redacted to `REDACTED_PLACEHOLDER`.
+    #[must_use]
+    pub fn with_encrypted_field(
+        self,
+        event_type: &str,
+        path: &str,
+    ) -> Self {
+        self.with_encrypted_field_placeholder(
+            event_type,
+            path,
+            Value::String(REDACTED_PLACEHOLDER.to_string()),
+        )
+    }
+
+    /// Marks the field at `path` of the events of type `event_type`
+    /// as personal data redacted to `placeholder`, e.g., for fields
+    /// that are not strings.
+    #[must_use]
+    pub fn with_encrypted_field_placeholder(
+        mut self,
+        event_type: &str,
+        path: &str,
+        placeholder: Value,
+    ) -> Self {
+        self.fields
+            .entry(event_type.to_string())
+            .or_default()
+            .push(EncryptedField {
+                path: path.to_string(),
+                placeholder,
+            });
+        self
+    }
+
+    /// The subject whose key encrypts the personal data of `event`
+    #[must_use]
+    pub fn subject_of(event: &SerializedEvent) -> &str {
+        event
+            .metadata
+            .get(SUBJECT_ID_KEY)
+            .unwrap_or(&event.aggregate_id)
+    }
+
+    /// Encrypts the marked fields of `event` in place, creating the
+    /// key of its subject when it does not exist yet. The event
+    /// stores encrypt the events they commit once the concurrency
+    /// check has passed, so that rejected commits do not create keys.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` when the key can not be loaded or saved, or
+    /// when a field can not be encrypted.
+    pub fn encrypt(
+        &mut self,
+        event: &mut SerializedEvent,
+    ) -> Result<(), Error> {
+        let Some(fields) = self.fields.get(&event.event_type)
+        else {
+            return Ok(());
+        };
+
+        let subject = Self::subject_of(event).to_string();
+
+        let key =
+            if let Some(x) = self.key_store.load_key(&subject)? {
+                x
+            }
+            else {
+                debug!("creating key of subject '{subject}'");
+
+                let key = Aes256Gcm::generate_key(OsRng).to_vec();
+                self.key_store
+                    .save_key(&subject, key.clone())?;
+                key
+            };
+
+        let (cipher, key_id) = new_cipher(&subject, &key)?;
+
+        for field in fields {
+            let aad = associated_data(&subject, event, &field.path);
+
+            let value =
+                match field_mut(&mut event.payload, &field.path) {
+                    Some(x) if !is_encrypted(x) => x,
+                    _ => continue,
+                };
+
+            trace!(
+                "encrypting field '{}' of event '{}'",
+                field.path,
+                event.event_type
+            );
+
+            *value = encrypt_value(
+                &cipher,
+                &key_id,
+                &field.path,
+                &aad,
+                value,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Decrypts the marked fields of `event` in place. When the key
+    /// of its subject has been deleted, or replaced by a new key
+    /// since, the fields are replaced by their placeholder.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` when the key can not be loaded, or when a
+    /// field can not be decrypted with an existing key.
+    pub fn decrypt(
+        &mut self,
+        event: &mut SerializedEvent,
+    ) -> Result<(), Error> {
+        let Some(fields) = self.fields.get(&event.event_type)
+        else {
+            return Ok(());
+        };
+
+        let subject = Self::subject_of(event).to_string();
+
+        let mut key = None;
+
+        for field in fields {
+            let aad = associated_data(&subject, event, &field.path);
+
+            let value =
+                match field_mut(&mut event.payload, &field.path) {
+                    Some(x) if is_encrypted(x) => x,
+                    _ => continue,
+                };
+
+            // the key is loaded once, and only for encrypted fields
+            if key.is_none() {
+                key = Some(
+                    self.key_store
+                        .load_key(&subject)?
+                        .map(|x| new_cipher(&subject, &x))
+                        .transpose()?,
+                );
+            }
+
+            *value = match &key {
+                Some(Some((cipher, key_id)))
+                    if value[KEY_ID_FIELD_KEY] == key_id.as_str() =>
+                {
+                    decrypt_value(cipher, &field.path, &aad, value)?
+                },
+                _ => {
+                    trace!(
+                        "redacting field '{}' of event '{}'",
+                        field.path,
+                        event.event_type
+                    );
+
+                    field.placeholder.clone()
+                },
+            };
+        }
+
+        Ok(())
+    }
+
+    /// Deletes the key of `subject`, which permanently redacts its
+    /// personal data in every event committed so far.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` when the key can not be deleted.
+    pub fn forget(
+        &mut self,
+        subject: &str,
+    ) -> Result<(), Error> {
+        debug!("forgetting subject '{subject}'");
+
+        self.key_store.delete_key(subject)
+    }
+
+    /// Serializes an `EventContext` of an aggregate of type
+    /// `aggregate_type` and encrypts its marked fields.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` when the event can not be serialized or
+    /// encrypted.
+    pub fn serialize<C: ICommand, E: IEvent>(
+        &mut self,
+        aggregate_type: &str,
+        context: &EventContext<C, E>,
+    ) -> Result<SerializedEvent, Error> {
+        let mut event =
+            SerializedEvent::from_context(aggregate_type, context)?;
+
+        self.encrypt(&mut event)?;
+
+        Ok(event)
+    }
+
+    /// Decrypts the marked fields of `event` and deserializes it back
+    /// into an `EventContext`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` when the event can not be decrypted or
+    /// deserialized.
+    pub fn deserialize<C: ICommand, E: IEvent>(
+        &mut self,
+        event: SerializedEvent,
+    ) -> Result<EventContext<C, E>, Error> {
+        self.deserialize_with(event, &EventUpcasters::default())
+    }
+
+    /// Decrypts the marked fields of `event`, upcasts its payload to
+    /// the latest schema version and deserializes it back into an
+    /// `EventContext`. The fields are marked with the event type and
+    /// paths of the stored schema version.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` when the event can not be decrypted,
+    /// upcasted or deserialized.
+    pub fn deserialize_with<C: ICommand, E: IEvent>(
+        &mut self,
+        event: SerializedEvent,
+        upcasters: &EventUpcasters,
+    ) -> Result<EventContext<C, E>, Error> {
+        let mut event = event;
+
+        self.decrypt(&mut event)?;
+
+        event.into_context_with(upcasters)
+    }
+}
+
+impl<KS: IKeyStore + Debug> Debug for CryptoShredder<KS> {
+    fn fmt(
+        &self,
+        f: &mut Formatter<'_>,
+    ) -> std::fmt::Result {
+        let fields: HashMap<&str, Vec<&str>> = self
+            .fields
+            .iter()
+            .map(|(event_type, fields)| {
+                (
+                    event_type.as_str(),
+                    fields
+                        .iter()
+                        .map(|x| x.path.as_str())
+                        .collect(),
+                )
+            })
+            .collect();
+
+        f.debug_struct("CryptoShredder")
+            .field("key_store", &self.key_store)
+            .field("fields", &fields)
+            .finish()
+    }
+}
+
+/// The cipher of the `key` of `subject` along with the id of the
+/// key, its key check value, i.e., the leading bytes of a zero block
+/// encrypted with it, which identifies the key without revealing it
+fn new_cipher(
+    subject: &str,
+    key: &[u8],
+) -> Result<(Aes256Gcm, String), Error> {
+    let invalid = |e: InvalidLength| {
+        Error::serialization(
+            &format!("invalid key of subject '{subject}': {e}"),
+            None,
+        )
+    };
+
+    let cipher = Aes256Gcm::new_from_slice(key).map_err(invalid)?;
+
+    let mut block = GenericArray::default();
+    Aes256::new_from_slice(key)
+        .map_err(invalid)?
+        .encrypt_block(&mut block);
+
+    Ok((
+        cipher,
+        STANDARD.encode(&block[..KEY_ID_LENGTH]),
+    ))
+}
+
+fn field_mut<'a>(
+    payload: &'a mut Value,
+    path: &str,
+) -> Option<&'a mut Value> {
+    path.split('.')
+        .try_fold(payload, |x, key| x.get_mut(key))
+}
+
+fn is_encrypted(value: &Value) -> bool {
+    value.as_object().is_some_and(|x| {
+        x.len() == 2 &&
+            x.contains_key(ENCRYPTED_FIELD_KEY) &&
+            x.contains_key(KEY_ID_FIELD_KEY)
+    })
+}
+
+/// The additional authenticated data of an encrypted field, which
+/// binds its ciphertext to the subject, the event and the path it
+/// was encrypted for
+fn associated_data(
+    subject: &str,
+    event: &SerializedEvent,
+    path: &str,
+) -> Vec<u8> {
+    json!([
+        subject,
+        event.aggregate_type,
+        event.aggregate_id,
+        event.sequence,
+        event.event_type,
+        path,
+    ])
+    .to_string()
+    .into_bytes()
+}
+
+fn encrypt_value(
+    cipher: &Aes256Gcm,
+    key_id: &str,
+    path: &str,
+    aad: &[u8],
+    value: &Value,
+) -> Result<Value, Error> {
+    let plaintext = serde_json::to_vec(value).map_err(|e| {
+        Error::Serialization {
+            message: format!(
+                "unable to serialize field '{path}': {e}"
+            ),
+            source: Some(e.into()),
+        }
+    })?;
+
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+
+    let ciphertext = cipher
+        .encrypt(
+            &nonce,
+            Payload {
+                msg: &plaintext,
+                aad,
+            },
+        )
+        .map_err(|e| {
+            Error::serialization(
+                &format!("unable to encrypt field '{path}': {e}"),
+                None,
+            )
+        })?;
+
+    let mut data = nonce.to_vec();
+    data.extend(ciphertext);
+
+    Ok(json!({
+        ENCRYPTED_FIELD_KEY: STANDARD.encode(data),
+        KEY_ID_FIELD_KEY: key_id,
+    }))
+}
+
+fn decrypt_value(
+    cipher: &Aes256Gcm,
+    path: &str,
+    aad: &[u8],
+    value: &Value,
+) -> Result<Value, Error> {
+    let invalid = |reason: &str| {
+        Error::serialization(
+            &format!("unable to decrypt field '{path}': {reason}"),
+            None,
+        )
+    };
+
+    let data = value[ENCRYPTED_FIELD_KEY]
+        .as_str()
+        .ok_or_else(|| invalid("the ciphertext is not a string"))
+        .and_then(|x| {
+            STANDARD
+                .decode(x)
+                .map_err(|e| invalid(&e.to_string()))
+        })?;
+
+    if data.len() < NONCE_LENGTH {
+        return Err(invalid("the ciphertext is too short"));
+    }
+
+    let (nonce, ciphertext) = data.split_at(NONCE_LENGTH);
+
+    let plaintext = cipher
+        .decrypt(
+            Nonce::from_slice(nonce),
+            Payload {
+                msg: ciphertext,
+                aad,
+            },
+        )
+        .map_err(|e| invalid(&e.to_string()))?;
+
+    serde_json::from_slice(&plaintext).map_err(|e| {
+        Error::Serialization {
+            message: format!(
+                "unable to deserialize field '{path}': {e}"
+            ),
+            source: Some(e.into()),
+        }
+    })
+}
@@ -0,0 +1,19 @@
+//! # shredding
+//!
+//! A central location for crypto-shredding, i.e., the field-level
+//! encryption of personal data in events
+
+pub use crypto_shredder::{
+    CryptoShredder,
+    ENCRYPTED_FIELD_KEY,
+    KEY_ID_FIELD_KEY,
+    REDACTED_PLACEHOLDER,
+};
+
+pub(crate) use shared_shredder::SharedShredder;
+
+mod crypto_shredder;
+mod shared_shredder;
+
+#[cfg(test)]
+mod test;
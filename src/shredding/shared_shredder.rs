@@ -0,0 +1,116 @@
+use std::{
+    fmt::Debug,
+    sync::{
+        Arc,
+        Mutex,
+    },
+};
+
+use crate::{
+    errors::Error,
+    events::SerializedEvent,
+    stores::IKeyStore,
+};
+
+use super::crypto_shredder::CryptoShredder;
+
+/// The part of a `CryptoShredder` an event store needs, independent
+/// of its key store
+trait IEventShredder: Debug + Send {
+    fn encrypt(
+        &mut self,
+        event: &mut SerializedEvent,
+    ) -> Result<(), Error>;
+
+    fn decrypt(
+        &mut self,
+        event: &mut SerializedEvent,
+    ) -> Result<(), Error>;
+}
+
+impl<KS: IKeyStore + Debug + Send> IEventShredder
+    for CryptoShredder<KS>
+{
+    fn encrypt(
+        &mut self,
+        event: &mut SerializedEvent,
+    ) -> Result<(), Error> {
+        CryptoShredder::encrypt(self, event)
+    }
+
+    fn decrypt(
+        &mut self,
+        event: &mut SerializedEvent,
+    ) -> Result<(), Error> {
+        CryptoShredder::decrypt(self, event)
+    }
+}
+
+/// The optional `CryptoShredder` of an event store, shared by the
+/// clones of the store
+#[derive(Debug, Default, Clone)]
+pub(crate) struct SharedShredder {
+    shredder: Option<Arc<Mutex<dyn IEventShredder>>>,
+}
+
+impl SharedShredder {
+    /// Constructor
+    pub(crate) fn new<KS: IKeyStore + Debug + Send + 'static>(
+        shredder: CryptoShredder<KS>
+    ) -> Self {
+        Self {
+            shredder: Some(Arc::new(Mutex::new(shredder))),
+        }
+    }
+
+    /// Whether a `CryptoShredder` is configured
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.shredder.is_some()
+    }
+
+    /// Encrypts the personal data of `event` before it is persisted
+    pub(crate) fn encrypt(
+        &self,
+        event: SerializedEvent,
+    ) -> Result<SerializedEvent, Error> {
+        self.apply(event, |x, event| x.encrypt(event))
+    }
+
+    /// Decrypts the personal data of `event` once it is loaded
+    pub(crate) fn decrypt(
+        &self,
+        event: SerializedEvent,
+    ) -> Result<SerializedEvent, Error> {
+        self.apply(event, |x, event| x.decrypt(event))
+    }
+
+    fn apply<F>(
+        &self,
+        event: SerializedEvent,
+        f: F,
+    ) -> Result<SerializedEvent, Error>
+    where
+        F: FnOnce(
+            &mut dyn IEventShredder,
+            &mut SerializedEvent,
+        ) -> Result<(), Error>, {
+        let Some(shredder) = &self.shredder
+        else {
+            return Ok(event);
+        };
+
+        let mut shredder = shredder.lock().map_err(|e| {
+            Error::Store {
+                message: format!(
+                    "unable to lock the crypto shredder: {e}"
+                ),
+                source: None,
+            }
+        })?;
+
+        let mut event = event;
+        f(&mut *shredder, &mut event)?;
+
+        Ok(event)
+    }
+}
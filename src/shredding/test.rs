@@ -0,0 +1,370 @@
+use serde_json::{
+    json,
+    Value,
+};
+use std::collections::HashMap;
+
+use crate::{
+    example_impl::*,
+    store_conformance::fixtures::email_updated,
+    EventContext,
+    IKeyStore,
+    InMemoryKeyStore,
+    SerializedEvent,
+    SUBJECT_ID_KEY,
+};
+
+use super::{
+    CryptoShredder,
+    ENCRYPTED_FIELD_KEY,
+    KEY_ID_FIELD_KEY,
+    REDACTED_PLACEHOLDER,
+};
+
+type ThisEventContext = EventContext<CustomerCommand, CustomerEvent>;
+
+fn shredder() -> CryptoShredder<InMemoryKeyStore> {
+    CryptoShredder::new(InMemoryKeyStore::default())
+        .with_encrypted_field("NameAdded", "changed_name")
+        .with_encrypted_field("EmailUpdated", "new_email")
+}
+
+fn envelope(payload: Value) -> SerializedEvent {
+    SerializedEvent {
+        event_type: "Registered".to_string(),
+        event_version: 1,
+        aggregate_type: "customer".to_string(),
+        aggregate_id: "customer-1".to_string(),
+        sequence: 1,
+        payload,
+        metadata: HashMap::default(),
+    }
+}
+
+#[test]
+fn test_encrypt_marked_fields_and_decrypt_on_load() {
+    let mut shredder = shredder();
+
+    let context = email_updated("customer-1", 1, "john@example.com");
+
+    let serialized = shredder
+        .serialize("customer", &context)
+        .unwrap();
+
+    let encrypted = &serialized.payload["new_email"];
+    assert!(encrypted[ENCRYPTED_FIELD_KEY].is_string());
+    assert!(!serialized
+        .payload
+        .to_string()
+        .contains("john@example.com"));
+
+    assert_eq!(
+        shredder
+            .deserialize::<CustomerCommand, CustomerEvent>(serialized)
+            .unwrap(),
+        context
+    );
+}
+
+#[test]
+fn test_unmarked_events_are_left_untouched() {
+    let mut shredder = shredder();
+
+    let context = EventContext::<CustomerCommand, CustomerEvent>::new(
+        "customer-1".to_string(),
+        1,
+        CustomerEvent::AddressUpdated(AddressUpdated {
+            new_address: "1 Main Street".to_string(),
+        }),
+        HashMap::new(),
+    );
+
+    let serialized = shredder
+        .serialize("customer", &context)
+        .unwrap();
+
+    assert_eq!(
+        serialized,
+        SerializedEvent::from_context("customer", &context).unwrap()
+    );
+}
+
+#[test]
+fn test_forgotten_subject_is_redacted() {
+    let mut shredder = shredder();
+
+    let first = shredder
+        .serialize(
+            "customer",
+            &email_updated("customer-1", 1, "john@example.com"),
+        )
+        .unwrap();
+    let second = shredder
+        .serialize(
+            "customer",
+            &email_updated("customer-2", 1, "jane@example.com"),
+        )
+        .unwrap();
+
+    shredder.forget("customer-1").unwrap();
+
+    let first: ThisEventContext =
+        shredder.deserialize(first).unwrap();
+    let second: ThisEventContext =
+        shredder.deserialize(second).unwrap();
+
+    assert_eq!(
+        first.payload,
+        CustomerEvent::EmailUpdated(EmailUpdated {
+            new_email: REDACTED_PLACEHOLDER.to_string(),
+        })
+    );
+    assert_eq!(
+        second.payload,
+        CustomerEvent::EmailUpdated(EmailUpdated {
+            new_email: "jane@example.com".to_string(),
+        })
+    );
+}
+
+#[test]
+fn test_forgotten_subject_gets_a_new_key() {
+    let mut shredder = shredder();
+
+    let first = shredder
+        .serialize(
+            "customer",
+            &email_updated("customer-1", 1, "john@example.com"),
+        )
+        .unwrap();
+
+    shredder.forget("customer-1").unwrap();
+
+    let second = shredder
+        .serialize(
+            "customer",
+            &email_updated("customer-1", 2, "john@example.org"),
+        )
+        .unwrap();
+
+    assert_ne!(
+        first.payload["new_email"][KEY_ID_FIELD_KEY],
+        second.payload["new_email"][KEY_ID_FIELD_KEY]
+    );
+
+    let first: ThisEventContext =
+        shredder.deserialize(first).unwrap();
+    let second: ThisEventContext =
+        shredder.deserialize(second).unwrap();
+
+    assert_eq!(
+        first.payload,
+        CustomerEvent::EmailUpdated(EmailUpdated {
+            new_email: REDACTED_PLACEHOLDER.to_string(),
+        })
+    );
+    assert_eq!(
+        second.payload,
+        CustomerEvent::EmailUpdated(EmailUpdated {
+            new_email: "john@example.org".to_string(),
+        })
+    );
+}
+
+#[test]
+fn test_subject_id_metadata_selects_the_key() {
+    let key_store = InMemoryKeyStore::default();
+
+    let mut shredder = CryptoShredder::new(key_store.clone())
+        .with_encrypted_field("EmailUpdated", "new_email");
+
+    let mut context =
+        email_updated("customer-1", 1, "john@example.com");
+    context.metadata.insert(
+        SUBJECT_ID_KEY.to_string(),
+        "person-1".to_string(),
+    );
+
+    let serialized = shredder
+        .serialize("customer", &context)
+        .unwrap();
+
+    let mut keys = key_store;
+    assert!(keys
+        .load_key("customer-1")
+        .unwrap()
+        .is_none());
+    assert!(keys
+        .load_key("person-1")
+        .unwrap()
+        .is_some());
+
+    shredder.forget("person-1").unwrap();
+
+    let context: ThisEventContext = shredder
+        .deserialize(serialized)
+        .unwrap();
+
+    assert_eq!(
+        context.payload,
+        CustomerEvent::EmailUpdated(EmailUpdated {
+            new_email: REDACTED_PLACEHOLDER.to_string(),
+        })
+    );
+}
+
+#[test]
+fn test_nested_fields_and_custom_placeholders() {
+    let mut shredder =
+        CryptoShredder::new(InMemoryKeyStore::default())
+            .with_encrypted_field("Registered", "address.street")
+            .with_encrypted_field_placeholder(
+                "Registered",
+                "age",
+                Value::Null,
+            )
+            .with_encrypted_field("Registered", "missing.field");
+
+    let payload = json!({
+        "address": { "street": "1 Main Street", "city": "Springfield" },
+        "age": 42,
+    });
+
+    let mut event = envelope(payload.clone());
+    shredder.encrypt(&mut event).unwrap();
+
+    assert!(
+        event.payload["address"]["street"][ENCRYPTED_FIELD_KEY]
+            .is_string()
+    );
+    assert!(event.payload["age"][ENCRYPTED_FIELD_KEY].is_string());
+    assert_eq!(
+        event.payload["address"]["city"],
+        "Springfield"
+    );
+
+    let encrypted = event.clone();
+
+    shredder.decrypt(&mut event).unwrap();
+    assert_eq!(event.payload, payload);
+
+    shredder.forget("customer-1").unwrap();
+
+    let mut event = encrypted;
+    shredder.decrypt(&mut event).unwrap();
+
+    assert_eq!(
+        event.payload,
+        json!({
+            "address": {
+                "street": REDACTED_PLACEHOLDER,
+                "city": "Springfield",
+            },
+            "age": null,
+        })
+    );
+}
+
+#[test]
+fn test_encrypt_is_idempotent_and_reuses_the_key() {
+    let key_store = InMemoryKeyStore::default();
+
+    let mut shredder = CryptoShredder::new(key_store.clone())
+        .with_encrypted_field("Registered", "name");
+
+    let mut event = envelope(json!({ "name": "John Doe" }));
+    shredder.encrypt(&mut event).unwrap();
+
+    let mut keys = key_store;
+    let key = keys.load_key("customer-1").unwrap();
+
+    let encrypted = event.clone();
+    shredder.encrypt(&mut event).unwrap();
+
+    assert_eq!(event, encrypted);
+    assert_eq!(
+        keys.load_key("customer-1").unwrap(),
+        key
+    );
+
+    shredder.decrypt(&mut event).unwrap();
+    assert_eq!(
+        event.payload,
+        json!({ "name": "John Doe" })
+    );
+}
+
+#[test]
+fn test_tampered_ciphertext_fails() {
+    let mut shredder =
+        CryptoShredder::new(InMemoryKeyStore::default())
+            .with_encrypted_field("Registered", "name");
+
+    let mut event = envelope(json!({ "name": "John Doe" }));
+    shredder.encrypt(&mut event).unwrap();
+
+    // the field path is authenticated along with the ciphertext
+    let mut moved = event.clone();
+    moved.payload = json!({ "other": event.payload["name"].clone() });
+
+    let mut shredder =
+        shredder.with_encrypted_field("Registered", "other");
+
+    let mut tampered = event.clone();
+    tampered.payload["name"][ENCRYPTED_FIELD_KEY] = json!("AAAA");
+
+    assert_eq!(
+        shredder
+            .decrypt(&mut tampered)
+            .unwrap_err()
+            .code(),
+        "serialization_error"
+    );
+    assert_eq!(
+        shredder
+            .decrypt(&mut moved)
+            .unwrap_err()
+            .code(),
+        "serialization_error"
+    );
+
+    shredder.decrypt(&mut event).unwrap();
+    assert_eq!(
+        event.payload,
+        json!({ "name": "John Doe" })
+    );
+}
+
+#[test]
+fn test_ciphertext_is_bound_to_its_event() {
+    let mut shredder =
+        CryptoShredder::new(InMemoryKeyStore::default())
+            .with_encrypted_field("Registered", "name");
+
+    let mut event = envelope(json!({ "name": "John Doe" }));
+    shredder.encrypt(&mut event).unwrap();
+
+    // the subject and the event identity are authenticated along
+    // with the ciphertext
+    let mut resequenced = event.clone();
+    resequenced.sequence = 2;
+
+    let mut retyped = event.clone();
+    retyped.aggregate_type = "supplier".to_string();
+
+    for mut moved in [resequenced, retyped] {
+        assert_eq!(
+            shredder
+                .decrypt(&mut moved)
+                .unwrap_err()
+                .code(),
+            "serialization_error"
+        );
+    }
+
+    shredder.decrypt(&mut event).unwrap();
+    assert_eq!(
+        event.payload,
+        json!({ "name": "John Doe" })
+    );
+}
@@ -0,0 +1,139 @@
+use log::debug;
+use rusqlite::{
+    Connection,
+    TransactionBehavior,
+};
+use std::convert::TryFrom;
+
+use crate::errors::Error;
+
+use super::sqlite_connection::sqlite_error;
+
+/// The schema migrations in the order they are applied. The schema
+/// version of a database, recorded in its `user_version`, is the
+/// number of migrations applied to it, so migrations must only ever
+/// be appended.
+const MIGRATIONS: &[&str] = &[
+    // 1: events, snapshots and queries
+    "
+    CREATE TABLE events (
+        position INTEGER PRIMARY KEY AUTOINCREMENT,
+        aggregate_type TEXT NOT NULL,
+        aggregate_id TEXT NOT NULL,
+        sequence INTEGER NOT NULL,
+        event_type TEXT NOT NULL,
+        event_version INTEGER NOT NULL,
+        payload TEXT NOT NULL CHECK (json_valid(payload)),
+        metadata TEXT NOT NULL CHECK (json_valid(metadata)),
+        UNIQUE (aggregate_type, aggregate_id, sequence)
+    );
+
+    CREATE INDEX events_by_aggregate_type
+        ON events (aggregate_type, position);
+
+    CREATE TABLE snapshots (
+        aggregate_type TEXT NOT NULL,
+        aggregate_id TEXT NOT NULL,
+        version INTEGER NOT NULL,
+        payload TEXT NOT NULL CHECK (json_valid(payload)),
+        PRIMARY KEY (aggregate_type, aggregate_id)
+    );
+
+    CREATE TABLE queries (
+        query_type TEXT NOT NULL,
+        aggregate_id TEXT NOT NULL,
+        version INTEGER NOT NULL,
+        payload TEXT NOT NULL CHECK (json_valid(payload)),
+        PRIMARY KEY (query_type, aggregate_id)
+    );
+    ",
+    // 2: transactional outbox
+    "
+    CREATE TABLE outbox (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        event TEXT NOT NULL CHECK (json_valid(event)),
+        attempts INTEGER NOT NULL DEFAULT 0,
+        next_attempt_at TEXT NOT NULL,
+        last_error TEXT
+    );
+    ",
+    // 3: subscription checkpoints
+    "
+    CREATE TABLE checkpoints (
+        subscriber TEXT PRIMARY KEY,
+        position INTEGER NOT NULL
+    );
+    ",
+    // 4: crypto-shredding keys
+    "
+    CREATE TABLE keys (
+        subject TEXT PRIMARY KEY,
+        key BLOB NOT NULL
+    );
+    ",
+];
+
+/// The schema version of a fully migrated database
+#[allow(clippy::cast_possible_truncation)]
+pub const SQLITE_SCHEMA_VERSION: u32 = MIGRATIONS.len() as u32;
+
+/// Applies the migrations missing from `connection` in a single
+/// transaction.
+pub(crate) fn migrate(
+    connection: &mut Connection
+) -> Result<(), Error> {
+    let transaction = connection
+        .transaction_with_behavior(TransactionBehavior::Immediate)
+        .map_err(|e| {
+            sqlite_error("unable to migrate the database", e)
+        })?;
+
+    let version: u32 = transaction
+        .query_row("PRAGMA user_version", [], |row| {
+            row.get(0)
+        })
+        .map_err(|e| {
+            sqlite_error("unable to read the schema version", e)
+        })?;
+
+    if version > SQLITE_SCHEMA_VERSION {
+        return Err(Error::store(
+            &format!(
+                "the database schema version {version} is newer \
+                 than the supported version {SQLITE_SCHEMA_VERSION}"
+            ),
+            None,
+        ));
+    }
+
+    let applied = usize::try_from(version).unwrap_or(usize::MAX);
+
+    for (x, migration) in MIGRATIONS
+        .iter()
+        .enumerate()
+        .skip(applied)
+    {
+        debug!("applying schema migration {}", x + 1);
+
+        transaction
+            .execute_batch(migration)
+            .map_err(|e| {
+                sqlite_error(
+                    &format!(
+                        "unable to apply schema migration {}",
+                        x + 1
+                    ),
+                    e,
+                )
+            })?;
+    }
+
+    transaction
+        .execute_batch(&format!(
+            "PRAGMA user_version = {SQLITE_SCHEMA_VERSION}"
+        ))
+        .and_then(|()| transaction.commit())
+        .map_err(|e| {
+            sqlite_error("unable to migrate the database", e)
+        })
+}
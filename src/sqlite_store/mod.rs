@@ -0,0 +1,27 @@
+//! # sqlite_store
+//!
+//! SQLite implementations of the store interfaces, an embedded
+//! option that does not need a database server
+
+pub use migrations::SQLITE_SCHEMA_VERSION;
+pub use sqlite_checkpoint_store::SqliteCheckpointStore;
+pub use sqlite_connection::SqliteConnection;
+pub use sqlite_event_store::SqliteEventStore;
+#[cfg(feature = "crypto")]
+pub use sqlite_key_store::SqliteKeyStore;
+pub use sqlite_outbox_store::SqliteOutboxStore;
+pub use sqlite_query_store::SqliteQueryStore;
+pub use sqlite_snapshot_store::SqliteSnapshotStore;
+
+mod migrations;
+mod sqlite_checkpoint_store;
+mod sqlite_connection;
+mod sqlite_event_store;
+#[cfg(feature = "crypto")]
+mod sqlite_key_store;
+mod sqlite_outbox_store;
+mod sqlite_query_store;
+mod sqlite_snapshot_store;
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,88 @@
+use log::trace;
+use rusqlite::{
+    params,
+    OptionalExtension,
+};
+
+use crate::{
+    errors::Error,
+    stores::ICheckpointStore,
+};
+
+use super::sqlite_connection::{
+    sqlite_error,
+    SqliteConnection,
+};
+
+/// `SQLite` checkpoint store keeping the position of every named
+/// subscriber in the `checkpoints` table, so that subscriptions
+/// resume where they stopped after a restart.
+#[derive(Debug, Clone)]
+pub struct SqliteCheckpointStore {
+    connection: SqliteConnection,
+}
+
+impl SqliteCheckpointStore {
+    /// Constructor
+    #[must_use]
+    pub fn new(connection: SqliteConnection) -> Self {
+        Self { connection }
+    }
+}
+
+impl ICheckpointStore for SqliteCheckpointStore {
+    fn load(
+        &mut self,
+        subscriber: &str,
+    ) -> Result<i64, Error> {
+        let result = self
+            .connection
+            .lock()?
+            .query_row(
+                "SELECT position FROM checkpoints
+                 WHERE subscriber = ?1",
+                params![subscriber],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(|e| {
+                sqlite_error("unable to read the checkpoint store", e)
+            })?
+            .unwrap_or_default();
+
+        trace!(
+            "loaded checkpoint {result} of subscriber '{subscriber}'"
+        );
+
+        Ok(result)
+    }
+
+    fn save(
+        &mut self,
+        subscriber: &str,
+        position: i64,
+    ) -> Result<(), Error> {
+        trace!(
+            "saving checkpoint {position} of subscriber \
+             '{subscriber}'"
+        );
+
+        self.connection
+            .lock()?
+            .execute(
+                "INSERT INTO checkpoints (subscriber, position)
+                 VALUES (?1, ?2)
+                 ON CONFLICT (subscriber)
+                 DO UPDATE SET position = excluded.position",
+                params![subscriber, position],
+            )
+            .map_err(|e| {
+                sqlite_error(
+                    "unable to write to the checkpoint store",
+                    e,
+                )
+            })?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,140 @@
+use rusqlite::Connection;
+use std::{
+    path::Path,
+    sync::{
+        Arc,
+        Mutex,
+        MutexGuard,
+    },
+};
+
+use crate::errors::Error;
+
+use super::migrations::migrate;
+
+/// A migrated `SQLite` database shared by the `SQLite` stores.
+/// Cloning the connection yields a handle to the same database, so
+/// the event, snapshot and query stores of an application can share a
+/// single file.
+///
+/// # Examples
+/// ```rust
+/// use cqrs_es2::{
+///     example_impl::{
+///         Customer,
+///         CustomerCommand,
+///         CustomerContactQuery,
+///         CustomerEvent,
+///     },
+///     SqliteConnection,
+///     SqliteEventStore,
+///     SqliteQueryStore,
+///     SqliteSnapshotStore,
+/// };
+///
+/// let connection = SqliteConnection::open_in_memory().unwrap();
+///
+/// let event_store = SqliteEventStore::<
+///     CustomerCommand,
+///     CustomerEvent,
+///     Customer,
+/// >::new(connection.clone());
+///
+/// let snapshot_store = SqliteSnapshotStore::<
+///     CustomerCommand,
+///     CustomerEvent,
+///     Customer,
+/// >::new(connection.clone());
+///
+/// let query_store = SqliteQueryStore::<
+///     CustomerCommand,
+///     CustomerEvent,
+///     CustomerContactQuery,
+/// >::new(connection);
+/// ```
+#[derive(Debug, Clone)]
+pub struct SqliteConnection {
+    connection: Arc<Mutex<Connection>>,
+}
+
+impl SqliteConnection {
+    /// Opens the database file at `path`, creating it when it does
+    /// not exist, and applies the missing schema migrations.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` when the database can not be opened or
+    /// migrated.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let connection =
+            Connection::open(path.as_ref()).map_err(|e| {
+                sqlite_error(
+                    &format!(
+                        "unable to open the database '{}'",
+                        path.as_ref().display()
+                    ),
+                    e,
+                )
+            })?;
+
+        Self::from_connection(connection)
+    }
+
+    /// Opens a private in-memory database and applies the schema
+    /// migrations, mostly useful for testing.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` when the database can not be opened or
+    /// migrated.
+    pub fn open_in_memory() -> Result<Self, Error> {
+        let connection =
+            Connection::open_in_memory().map_err(|e| {
+                sqlite_error(
+                    "unable to open an in-memory database",
+                    e,
+                )
+            })?;
+
+        Self::from_connection(connection)
+    }
+
+    /// Wraps an already configured `connection` and applies the
+    /// missing schema migrations.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` when the database can not be migrated.
+    pub fn from_connection(
+        connection: Connection
+    ) -> Result<Self, Error> {
+        let mut connection = connection;
+
+        migrate(&mut connection)?;
+
+        Ok(Self {
+            connection: Arc::new(Mutex::new(connection)),
+        })
+    }
+
+    pub(crate) fn lock(
+        &self
+    ) -> Result<MutexGuard<'_, Connection>, Error> {
+        self.connection.lock().map_err(|e| {
+            Error::Store {
+                message: format!("unable to lock the database: {e}"),
+                source: None,
+            }
+        })
+    }
+}
+
+pub(crate) fn sqlite_error(
+    message: &str,
+    e: rusqlite::Error,
+) -> Error {
+    Error::store(
+        &format!("{message}: {e}"),
+        Some(e.into()),
+    )
+}
@@ -0,0 +1,570 @@
+use log::{
+    debug,
+    trace,
+};
+use rusqlite::{
+    params,
+    ErrorCode,
+    Row,
+    TransactionBehavior,
+};
+use std::{
+    convert::TryFrom,
+    fmt::Debug,
+    marker::PhantomData,
+    sync::Arc,
+};
+
+use crate::{
+    aggregates::IAggregate,
+    commands::ICommand,
+    errors::Error,
+    events::{
+        EventContext,
+        EventUpcasters,
+        IEvent,
+        PositionedEvent,
+        SerializedEvent,
+    },
+    stores::IEventStore,
+};
+#[cfg(feature = "crypto")]
+use crate::{
+    shredding::{
+        CryptoShredder,
+        SharedShredder,
+    },
+    stores::IKeyStore,
+};
+
+use super::{
+    sqlite_connection::{
+        sqlite_error,
+        SqliteConnection,
+    },
+    sqlite_outbox_store::insert_outbox_message,
+};
+
+const SELECT_EVENTS: &str = "
+    SELECT position, event_type, event_version, aggregate_type,
+           aggregate_id, sequence, payload, metadata
+    FROM events";
+
+/// `SQLite` event store persisting the events of the aggregates of
+/// type `A` in the `events` table, with the payload and the metadata
+/// of every `EventContext` kept in JSON columns.
+///
+/// The unique `(aggregate_type, aggregate_id, sequence)` constraint
+/// backs the optimistic concurrency control, so that concurrent
+/// commits from other connections to the same database are detected
+/// as well. The global position of an event is its row id, which is
+/// shared by all aggregate types.
+///
+/// A store configured with `with_outbox` also writes every committed
+/// event to the `outbox` table in the same transaction, to be
+/// drained by a `SqliteOutboxStore`.
+///
+/// A store configured with `with_shredder` writes the personal data
+/// of the events encrypted to both tables and decrypts it when they
+/// are loaded.
+#[derive(Debug)]
+pub struct SqliteEventStore<
+    C: ICommand,
+    E: IEvent,
+    A: IAggregate<C, E>,
+> {
+    connection: SqliteConnection,
+    upcasters: Arc<EventUpcasters>,
+    outbox: bool,
+    #[cfg(feature = "crypto")]
+    shredder: SharedShredder,
+    _phantom: PhantomData<(C, E, A)>,
+}
+
+impl<C: ICommand, E: IEvent, A: IAggregate<C, E>> Clone
+    for SqliteEventStore<C, E, A>
+{
+    fn clone(&self) -> Self {
+        Self {
+            connection: self.connection.clone(),
+            upcasters: Arc::clone(&self.upcasters),
+            outbox: self.outbox,
+            #[cfg(feature = "crypto")]
+            shredder: self.shredder.clone(),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<C: ICommand, E: IEvent, A: IAggregate<C, E>>
+    SqliteEventStore<C, E, A>
+{
+    /// Constructor
+    #[must_use]
+    pub fn new(connection: SqliteConnection) -> Self {
+        Self {
+            connection,
+            upcasters: Arc::default(),
+            outbox: false,
+            #[cfg(feature = "crypto")]
+            shredder: SharedShredder::default(),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Writes the committed events to the `outbox` table as well
+    #[must_use]
+    pub fn with_outbox(self) -> Self {
+        Self {
+            outbox: true,
+            ..self
+        }
+    }
+
+    /// Upcasts the loaded events to their latest schema version
+    #[must_use]
+    pub fn with_upcasters(
+        self,
+        upcasters: EventUpcasters,
+    ) -> Self {
+        Self {
+            upcasters: Arc::new(upcasters),
+            ..self
+        }
+    }
+
+    /// Encrypts the personal data of the committed events with
+    /// `shredder` before they are written to the `events` and the
+    /// `outbox` tables, see `CryptoShredder`
+    #[cfg(feature = "crypto")]
+    #[must_use]
+    pub fn with_shredder<KS: IKeyStore + Debug + Send + 'static>(
+        self,
+        shredder: CryptoShredder<KS>,
+    ) -> Self {
+        Self {
+            shredder: SharedShredder::new(shredder),
+            ..self
+        }
+    }
+
+    fn query_events<P: rusqlite::Params>(
+        &self,
+        filter: &str,
+        params: P,
+    ) -> Result<Vec<PositionedEvent<C, E>>, Error> {
+        let connection = self.connection.lock()?;
+
+        let mut statement = connection
+            .prepare_cached(&format!("{SELECT_EVENTS} {filter}"))
+            .map_err(|e| {
+                sqlite_error("unable to read the event store", e)
+            })?;
+
+        let rows = statement
+            .query_map(params, EventRow::read)
+            .and_then(Iterator::collect::<Result<Vec<_>, _>>)
+            .map_err(|e| {
+                sqlite_error("unable to read the event store", e)
+            })?;
+
+        // the connection is released before decrypting the events,
+        // as the key store of the shredder may share it, e.g., a
+        // `SqliteKeyStore`
+        drop(statement);
+        drop(connection);
+
+        rows.into_iter()
+            .map(|row| {
+                let position = row.position;
+                let event = row.into_serialized()?;
+
+                #[cfg(feature = "crypto")]
+                let event = self.shredder.decrypt(event)?;
+
+                let event =
+                    event.into_context_with(&self.upcasters)?;
+
+                Ok(PositionedEvent::new(position, event))
+            })
+            .collect()
+    }
+
+    fn current_version(
+        connection: &rusqlite::Connection,
+        aggregate_id: &str,
+    ) -> Result<i64, Error> {
+        connection
+            .query_row(
+                "SELECT MAX(sequence) FROM events
+                 WHERE aggregate_type = ?1 AND aggregate_id = ?2",
+                params![A::aggregate_type(), aggregate_id],
+                |row| row.get::<_, Option<i64>>(0),
+            )
+            .map(|x| x.unwrap_or(0))
+            .map_err(|e| {
+                sqlite_error("unable to read the event store", e)
+            })
+    }
+
+    /// Serializes the committed `events` along with their metadata,
+    /// encrypted when a shredder is configured
+    #[cfg_attr(
+        not(feature = "crypto"),
+        allow(clippy::unused_self)
+    )]
+    fn serialize(
+        &self,
+        events: &[EventContext<C, E>],
+    ) -> Result<Vec<(SerializedEvent, String)>, Error> {
+        events
+            .iter()
+            .map(|event| {
+                let serialized = SerializedEvent::from_context(
+                    A::aggregate_type(),
+                    event,
+                )?;
+
+                #[cfg(feature = "crypto")]
+                let serialized = self.shredder.encrypt(serialized)?;
+
+                let metadata =
+                    serde_json::to_string(&serialized.metadata)
+                        .map_err(|e| {
+                            Error::Serialization {
+                                message: format!(
+                                    "unable to serialize the \
+                                     metadata of aggregate '{}': {}",
+                                    event.aggregate_id, e
+                                ),
+                                source: Some(e.into()),
+                            }
+                        })?;
+
+                Ok((serialized, metadata))
+            })
+            .collect()
+    }
+
+    fn check_version(
+        connection: &rusqlite::Connection,
+        aggregate_id: &str,
+        expected_version: i64,
+    ) -> Result<(), Error> {
+        let current_version =
+            Self::current_version(connection, aggregate_id)?;
+
+        if current_version != expected_version {
+            return Err(Error::ConcurrencyConflict {
+                aggregate_id: aggregate_id.to_string(),
+                expected: expected_version,
+                actual: current_version,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+impl<C: ICommand, E: IEvent, A: IAggregate<C, E>> IEventStore<C, E, A>
+    for SqliteEventStore<C, E, A>
+{
+    fn load_events(
+        &mut self,
+        aggregate_id: &str,
+    ) -> Result<Vec<EventContext<C, E>>, Error> {
+        IEventStore::load_events_after(self, aggregate_id, 0)
+    }
+
+    fn load_events_after(
+        &mut self,
+        aggregate_id: &str,
+        version: i64,
+    ) -> Result<Vec<EventContext<C, E>>, Error> {
+        let result: Vec<_> = self
+            .query_events(
+                "WHERE aggregate_type = ?1 AND aggregate_id = ?2
+                   AND sequence > ?3
+                 ORDER BY sequence",
+                params![
+                    A::aggregate_type(),
+                    aggregate_id,
+                    version
+                ],
+            )?
+            .into_iter()
+            .map(|x| x.event)
+            .collect();
+
+        trace!(
+            "loaded {} events after version {} for aggregate '{}' \
+             of type '{}'",
+            result.len(),
+            version,
+            aggregate_id,
+            A::aggregate_type()
+        );
+
+        Ok(result)
+    }
+
+    fn load_all_events(
+        &mut self
+    ) -> Result<Vec<EventContext<C, E>>, Error> {
+        let result: Vec<_> = self
+            .query_events(
+                "WHERE aggregate_type = ?1 ORDER BY position",
+                params![A::aggregate_type()],
+            )?
+            .into_iter()
+            .map(|x| x.event)
+            .collect();
+
+        trace!(
+            "loaded {} events of all aggregates of type '{}'",
+            result.len(),
+            A::aggregate_type()
+        );
+
+        Ok(result)
+    }
+
+    fn load_all_events_after(
+        &mut self,
+        position: i64,
+        limit: usize,
+    ) -> Result<Vec<PositionedEvent<C, E>>, Error> {
+        let limit = i64::try_from(limit).unwrap_or(i64::MAX);
+
+        let result = self.query_events(
+            "WHERE aggregate_type = ?1 AND position > ?2
+             ORDER BY position
+             LIMIT ?3",
+            params![A::aggregate_type(), position, limit],
+        )?;
+
+        trace!(
+            "loaded {} events after position {} of all aggregates \
+             of type '{}'",
+            result.len(),
+            position,
+            A::aggregate_type()
+        );
+
+        Ok(result)
+    }
+
+    fn count_events_after(
+        &mut self,
+        position: i64,
+    ) -> Result<usize, Error> {
+        let count: i64 = self
+            .connection
+            .lock()?
+            .query_row(
+                "SELECT COUNT(*) FROM events
+                 WHERE aggregate_type = ?1 AND position > ?2",
+                params![A::aggregate_type(), position],
+                |row| row.get(0),
+            )
+            .map_err(|e| {
+                sqlite_error("unable to read the event store", e)
+            })?;
+
+        Ok(usize::try_from(count).unwrap_or(0))
+    }
+
+    fn commit(
+        &mut self,
+        events: Vec<EventContext<C, E>>,
+        expected_version: i64,
+    ) -> Result<(), Error> {
+        let aggregate_id = match events.first() {
+            None => return Ok(()),
+            Some(x) => x.aggregate_id.clone(),
+        };
+
+        let mut expected_sequence = expected_version;
+
+        for event in &events {
+            if event.aggregate_id != aggregate_id {
+                return Err(Error::TechnicalError(format!(
+                    "unable to commit events of multiple aggregates \
+                     at once: '{}' and '{}'",
+                    aggregate_id, event.aggregate_id
+                )));
+            }
+
+            expected_sequence += 1;
+
+            if event.sequence != expected_sequence {
+                return Err(Error::TechnicalError(format!(
+                    "expected event sequence {} for aggregate '{}' \
+                     but found {}",
+                    expected_sequence, aggregate_id, event.sequence
+                )));
+            }
+        }
+
+        // the events are encrypted once the commit passed a first
+        // concurrency check, so that rejected commits do not create
+        // orphaned keys, without holding the connection as the key
+        // store of the shredder may share it
+        #[cfg(feature = "crypto")]
+        if self.shredder.is_enabled() {
+            Self::check_version(
+                &*self.connection.lock()?,
+                &aggregate_id,
+                expected_version,
+            )?;
+        }
+
+        let rows = self.serialize(&events)?;
+
+        let mut connection = self.connection.lock()?;
+
+        let transaction = connection
+            .transaction_with_behavior(TransactionBehavior::Immediate)
+            .map_err(|e| {
+                sqlite_error("unable to write to the event store", e)
+            })?;
+
+        Self::check_version(
+            &transaction,
+            &aggregate_id,
+            expected_version,
+        )?;
+
+        debug!(
+            "committing {} events for aggregate '{}' of type '{}'",
+            events.len(),
+            aggregate_id,
+            A::aggregate_type()
+        );
+
+        let result = rows
+            .iter()
+            .try_for_each(|(event, metadata)| {
+                insert(&transaction, event, metadata)
+            });
+
+        match result {
+            Ok(()) => {},
+            Err(e)
+                if e.sqlite_error_code() ==
+                    Some(ErrorCode::ConstraintViolation) =>
+            {
+                // a concurrent commit from another connection
+                let actual = Self::current_version(
+                    &transaction,
+                    &aggregate_id,
+                )?;
+
+                return Err(Error::ConcurrencyConflict {
+                    aggregate_id,
+                    expected: expected_version,
+                    actual,
+                });
+            },
+            Err(e) => {
+                return Err(sqlite_error(
+                    "unable to write to the event store",
+                    e,
+                ));
+            },
+        }
+
+        if self.outbox {
+            for (event, _) in &rows {
+                insert_outbox_message(&transaction, event)?;
+            }
+        }
+
+        transaction.commit().map_err(|e| {
+            sqlite_error("unable to write to the event store", e)
+        })
+    }
+}
+
+fn insert(
+    transaction: &rusqlite::Transaction<'_>,
+    event: &SerializedEvent,
+    metadata: &str,
+) -> rusqlite::Result<()> {
+    transaction
+        .execute(
+            "INSERT INTO events (aggregate_type, aggregate_id, \
+             sequence,
+                 event_type, event_version, payload, metadata)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                event.aggregate_type,
+                event.aggregate_id,
+                event.sequence,
+                event.event_type,
+                event.event_version,
+                event.payload.to_string(),
+                metadata,
+            ],
+        )
+        .map(|_| ())
+}
+
+/// An event row as stored, with its JSON columns still encoded
+struct EventRow {
+    position: i64,
+    event_type: String,
+    event_version: u32,
+    aggregate_type: String,
+    aggregate_id: String,
+    sequence: i64,
+    payload: String,
+    metadata: String,
+}
+
+impl EventRow {
+    fn read(row: &Row<'_>) -> rusqlite::Result<Self> {
+        Ok(Self {
+            position: row.get(0)?,
+            event_type: row.get(1)?,
+            event_version: row.get(2)?,
+            aggregate_type: row.get(3)?,
+            aggregate_id: row.get(4)?,
+            sequence: row.get(5)?,
+            payload: row.get(6)?,
+            metadata: row.get(7)?,
+        })
+    }
+
+    fn into_serialized(self) -> Result<SerializedEvent, Error> {
+        let payload = serde_json::from_str(&self.payload)
+            .and_then(|payload| {
+                Ok((
+                    payload,
+                    serde_json::from_str(&self.metadata)?,
+                ))
+            })
+            .map_err(|e| {
+                Error::Serialization {
+                    message: format!(
+                        "unable to deserialize event {} of \
+                         aggregate '{}': {}",
+                        self.sequence, self.aggregate_id, e
+                    ),
+                    source: Some(e.into()),
+                }
+            });
+
+        let (payload, metadata) = payload?;
+
+        Ok(SerializedEvent {
+            event_type: self.event_type,
+            event_version: self.event_version,
+            aggregate_type: self.aggregate_type,
+            aggregate_id: self.aggregate_id,
+            sequence: self.sequence,
+            payload,
+            metadata,
+        })
+    }
+}
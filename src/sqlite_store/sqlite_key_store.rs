@@ -0,0 +1,139 @@
+use log::trace;
+use rusqlite::{
+    params,
+    ErrorCode,
+    OptionalExtension,
+};
+
+use crate::{
+    errors::Error,
+    stores::IKeyStore,
+};
+
+use super::sqlite_connection::{
+    sqlite_error,
+    SqliteConnection,
+};
+
+/// `SQLite` key store keeping the crypto-shredding key of every
+/// subject in the `keys` table. It may share the connection of the
+/// `SqliteEventStore` whose shredder it backs.
+///
+/// `SQLite` leaves deleted rows in the free pages of the database
+/// file until they are reused, so the connection should enable the
+/// `secure_delete` pragma for deleted keys to be overwritten.
+///
+/// # Examples
+/// ```rust
+/// use cqrs_es2::{
+///     example_impl::{
+///         Customer,
+///         CustomerCommand,
+///         CustomerEvent,
+///     },
+///     CryptoShredder,
+///     SqliteConnection,
+///     SqliteEventStore,
+///     SqliteKeyStore,
+/// };
+///
+/// let connection = SqliteConnection::open_in_memory().unwrap();
+///
+/// let shredder =
+///     CryptoShredder::new(SqliteKeyStore::new(connection.clone()))
+///         .with_encrypted_field("EmailUpdated", "new_email");
+///
+/// let event_store = SqliteEventStore::<
+///     CustomerCommand,
+///     CustomerEvent,
+///     Customer,
+/// >::new(connection)
+/// .with_shredder(shredder);
+/// ```
+#[derive(Debug, Clone)]
+pub struct SqliteKeyStore {
+    connection: SqliteConnection,
+}
+
+impl SqliteKeyStore {
+    /// Constructor
+    #[must_use]
+    pub fn new(connection: SqliteConnection) -> Self {
+        Self { connection }
+    }
+}
+
+impl IKeyStore for SqliteKeyStore {
+    fn load_key(
+        &mut self,
+        subject: &str,
+    ) -> Result<Option<Vec<u8>>, Error> {
+        trace!("loading key of subject '{subject}'");
+
+        self.connection
+            .lock()?
+            .query_row(
+                "SELECT key FROM keys WHERE subject = ?1",
+                params![subject],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(|e| {
+                sqlite_error("unable to read the key store", e)
+            })
+    }
+
+    fn save_key(
+        &mut self,
+        subject: &str,
+        key: Vec<u8>,
+    ) -> Result<(), Error> {
+        trace!("saving key of subject '{subject}'");
+
+        let result = self.connection.lock()?.execute(
+            "INSERT INTO keys (subject, key) VALUES (?1, ?2)",
+            params![subject, key],
+        );
+
+        match result {
+            Ok(_) => Ok(()),
+            Err(e)
+                if e.sqlite_error_code() ==
+                    Some(ErrorCode::ConstraintViolation) =>
+            {
+                Err(Error::store(
+                    &format!(
+                        "the key of subject '{subject}' already \
+                         exists"
+                    ),
+                    Some(e.into()),
+                ))
+            },
+            Err(e) => {
+                Err(sqlite_error(
+                    "unable to write to the key store",
+                    e,
+                ))
+            },
+        }
+    }
+
+    fn delete_key(
+        &mut self,
+        subject: &str,
+    ) -> Result<(), Error> {
+        trace!("deleting key of subject '{subject}'");
+
+        self.connection
+            .lock()?
+            .execute(
+                "DELETE FROM keys WHERE subject = ?1",
+                params![subject],
+            )
+            .map_err(|e| {
+                sqlite_error("unable to write to the key store", e)
+            })?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,222 @@
+use chrono::{
+    DateTime,
+    Utc,
+};
+use log::trace;
+use rusqlite::{
+    params,
+    Row,
+};
+use std::convert::TryFrom;
+
+use crate::{
+    errors::Error,
+    events::SerializedEvent,
+    outbox::OutboxMessage,
+    stores::IOutboxStore,
+};
+
+use super::sqlite_connection::{
+    sqlite_error,
+    SqliteConnection,
+};
+
+/// `SQLite` outbox store draining the `outbox` table, which is
+/// filled by a `SqliteEventStore` configured with `with_outbox` in
+/// the same transaction as the committed events. Both stores have
+/// to share the same `SqliteConnection`.
+#[derive(Debug, Clone)]
+pub struct SqliteOutboxStore {
+    connection: SqliteConnection,
+}
+
+impl SqliteOutboxStore {
+    /// Constructor
+    #[must_use]
+    pub fn new(connection: SqliteConnection) -> Self {
+        Self { connection }
+    }
+}
+
+impl IOutboxStore for SqliteOutboxStore {
+    fn load_pending(
+        &mut self,
+        now: DateTime<Utc>,
+        limit: usize,
+    ) -> Result<Vec<OutboxMessage>, Error> {
+        let limit = i64::try_from(limit).unwrap_or(i64::MAX);
+
+        let connection = self.connection.lock()?;
+
+        let mut statement = connection
+            .prepare_cached(
+                "SELECT id, event, attempts, next_attempt_at,
+                        last_error
+                 FROM outbox ORDER BY id LIMIT ?1",
+            )
+            .map_err(|e| {
+                sqlite_error("unable to read the outbox store", e)
+            })?;
+
+        let rows = statement
+            .query_map(params![limit], OutboxRow::read)
+            .and_then(Iterator::collect::<Result<Vec<_>, _>>)
+            .map_err(|e| {
+                sqlite_error("unable to read the outbox store", e)
+            })?;
+
+        let mut result = Vec::new();
+
+        for row in rows {
+            let message = row.into_message()?;
+
+            if message.next_attempt_at > now {
+                break;
+            }
+
+            result.push(message);
+        }
+
+        trace!(
+            "loaded {} pending outbox messages",
+            result.len()
+        );
+
+        Ok(result)
+    }
+
+    fn mark_published(
+        &mut self,
+        id: i64,
+    ) -> Result<(), Error> {
+        trace!("removing published outbox message {id}");
+
+        self.connection
+            .lock()?
+            .execute(
+                "DELETE FROM outbox WHERE id = ?1",
+                params![id],
+            )
+            .map_err(|e| {
+                sqlite_error("unable to write to the outbox store", e)
+            })?;
+
+        Ok(())
+    }
+
+    fn mark_failed(
+        &mut self,
+        id: i64,
+        next_attempt_at: DateTime<Utc>,
+        error: &str,
+    ) -> Result<(), Error> {
+        let updated = self
+            .connection
+            .lock()?
+            .execute(
+                "UPDATE outbox
+                 SET attempts = attempts + 1, next_attempt_at = ?2,
+                     last_error = ?3
+                 WHERE id = ?1",
+                params![id, next_attempt_at.to_rfc3339(), error],
+            )
+            .map_err(|e| {
+                sqlite_error("unable to write to the outbox store", e)
+            })?;
+
+        if updated == 0 {
+            return Err(Error::not_found(
+                "outbox message",
+                &id.to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// Appends `event` to the outbox as part of `transaction`, due right
+/// away
+pub(crate) fn insert_outbox_message(
+    transaction: &rusqlite::Transaction<'_>,
+    event: &SerializedEvent,
+) -> Result<(), Error> {
+    let text = serde_json::to_string(event).map_err(|e| {
+        Error::Serialization {
+            message: format!(
+                "unable to serialize the outbox message of event {} \
+                 of aggregate '{}': {}",
+                event.sequence, event.aggregate_id, e
+            ),
+            source: Some(e.into()),
+        }
+    })?;
+
+    transaction
+        .execute(
+            "INSERT INTO outbox (event, next_attempt_at)
+             VALUES (?1, ?2)",
+            params![text, Utc::now().to_rfc3339()],
+        )
+        .map_err(|e| {
+            sqlite_error("unable to write to the outbox store", e)
+        })?;
+
+    Ok(())
+}
+
+/// An outbox row as stored, with its event still encoded
+struct OutboxRow {
+    id: i64,
+    event: String,
+    attempts: u32,
+    next_attempt_at: String,
+    last_error: Option<String>,
+}
+
+impl OutboxRow {
+    fn read(row: &Row<'_>) -> rusqlite::Result<Self> {
+        Ok(Self {
+            id: row.get(0)?,
+            event: row.get(1)?,
+            attempts: row.get(2)?,
+            next_attempt_at: row.get(3)?,
+            last_error: row.get(4)?,
+        })
+    }
+
+    fn into_message(self) -> Result<OutboxMessage, Error> {
+        let event: SerializedEvent =
+            serde_json::from_str(&self.event).map_err(|e| {
+                Error::Serialization {
+                    message: format!(
+                        "unable to deserialize outbox message {}: {}",
+                        self.id, e
+                    ),
+                    source: Some(e.into()),
+                }
+            })?;
+
+        let next_attempt_at =
+            DateTime::parse_from_rfc3339(&self.next_attempt_at)
+                .map_err(|e| {
+                    Error::store(
+                        &format!(
+                            "invalid next attempt time of outbox \
+                             message {}: {}",
+                            self.id, e
+                        ),
+                        Some(e.into()),
+                    )
+                })?
+                .with_timezone(&Utc);
+
+        Ok(OutboxMessage {
+            id: self.id,
+            event,
+            attempts: self.attempts,
+            next_attempt_at,
+            last_error: self.last_error,
+        })
+    }
+}
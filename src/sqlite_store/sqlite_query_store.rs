@@ -0,0 +1,176 @@
+use log::trace;
+use rusqlite::{
+    params,
+    OptionalExtension,
+};
+use std::{
+    fmt::Debug,
+    marker::PhantomData,
+};
+
+use crate::{
+    commands::ICommand,
+    errors::Error,
+    events::IEvent,
+    queries::{
+        IQuery,
+        QueryContext,
+    },
+    stores::IQueryStore,
+};
+
+use super::sqlite_connection::{
+    sqlite_error,
+    SqliteConnection,
+};
+
+/// `SQLite` query store keeping the queries of type `Q` in the
+/// `queries` table, with the query state kept in a JSON column.
+#[derive(Debug)]
+pub struct SqliteQueryStore<C: ICommand, E: IEvent, Q: IQuery<C, E>> {
+    connection: SqliteConnection,
+    _phantom: PhantomData<(C, E, Q)>,
+}
+
+impl<C: ICommand, E: IEvent, Q: IQuery<C, E>> Clone
+    for SqliteQueryStore<C, E, Q>
+{
+    fn clone(&self) -> Self {
+        Self {
+            connection: self.connection.clone(),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<C: ICommand, E: IEvent, Q: IQuery<C, E>>
+    SqliteQueryStore<C, E, Q>
+{
+    /// Constructor
+    #[must_use]
+    pub fn new(connection: SqliteConnection) -> Self {
+        Self {
+            connection,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<C: ICommand, E: IEvent, Q: IQuery<C, E>> IQueryStore<C, E, Q>
+    for SqliteQueryStore<C, E, Q>
+{
+    fn load(
+        &mut self,
+        aggregate_id: &str,
+    ) -> Result<QueryContext<C, E, Q>, Error> {
+        let row = self
+            .connection
+            .lock()?
+            .query_row(
+                "SELECT version, payload FROM queries
+                 WHERE query_type = ?1 AND aggregate_id = ?2",
+                params![Q::query_type(), aggregate_id],
+                |row| {
+                    Ok((
+                        row.get::<_, i64>(0)?,
+                        row.get::<_, String>(1)?,
+                    ))
+                },
+            )
+            .optional()
+            .map_err(|e| {
+                sqlite_error("unable to read the query store", e)
+            })?;
+
+        let result = match row {
+            None => {
+                QueryContext::new(
+                    aggregate_id.to_string(),
+                    0,
+                    Q::default(),
+                )
+            },
+            Some((version, payload)) => {
+                let payload = serde_json::from_str(&payload)
+                    .map_err(|e| {
+                        Error::Serialization {
+                            message: format!(
+                                "unable to deserialize query of \
+                                 type '{}' for aggregate '{}': {}",
+                                Q::query_type(),
+                                aggregate_id,
+                                e
+                            ),
+                            source: Some(e.into()),
+                        }
+                    })?;
+
+                QueryContext::new(
+                    aggregate_id.to_string(),
+                    version,
+                    payload,
+                )
+            },
+        };
+
+        trace!(
+            "loaded query of type '{}' at version {} for aggregate \
+             '{}'",
+            Q::query_type(),
+            result.version,
+            aggregate_id
+        );
+
+        Ok(result)
+    }
+
+    fn save(
+        &mut self,
+        context: QueryContext<C, E, Q>,
+    ) -> Result<(), Error> {
+        let payload = serde_json::to_string(&context.payload)
+            .map_err(|e| {
+                Error::Serialization {
+                    message: format!(
+                        "unable to serialize query of type '{}' for \
+                         aggregate '{}': {}",
+                        Q::query_type(),
+                        context.aggregate_id,
+                        e
+                    ),
+                    source: Some(e.into()),
+                }
+            })?;
+
+        trace!(
+            "saving query of type '{}' at version {} for aggregate \
+             '{}'",
+            Q::query_type(),
+            context.version,
+            context.aggregate_id
+        );
+
+        self.connection
+            .lock()?
+            .execute(
+                "INSERT INTO queries (query_type, aggregate_id, \
+                 version,
+                     payload)
+                 VALUES (?1, ?2, ?3, ?4)
+                 ON CONFLICT (query_type, aggregate_id)
+                 DO UPDATE SET version = excluded.version,
+                               payload = excluded.payload",
+                params![
+                    Q::query_type(),
+                    context.aggregate_id,
+                    context.version,
+                    payload,
+                ],
+            )
+            .map_err(|e| {
+                sqlite_error("unable to write to the query store", e)
+            })?;
+
+        Ok(())
+    }
+}
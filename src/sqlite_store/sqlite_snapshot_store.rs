@@ -0,0 +1,171 @@
+use log::trace;
+use rusqlite::{
+    params,
+    OptionalExtension,
+};
+use std::{
+    fmt::Debug,
+    marker::PhantomData,
+};
+
+use crate::{
+    aggregates::{
+        AggregateContext,
+        IAggregate,
+    },
+    commands::ICommand,
+    errors::Error,
+    events::IEvent,
+    stores::ISnapshotStore,
+};
+
+use super::sqlite_connection::{
+    sqlite_error,
+    SqliteConnection,
+};
+
+/// `SQLite` snapshot store keeping the latest snapshot of every
+/// aggregate instance of type `A` in the `snapshots` table, with the
+/// aggregate state kept in a JSON column.
+#[derive(Debug)]
+pub struct SqliteSnapshotStore<
+    C: ICommand,
+    E: IEvent,
+    A: IAggregate<C, E>,
+> {
+    connection: SqliteConnection,
+    _phantom: PhantomData<(C, E, A)>,
+}
+
+impl<C: ICommand, E: IEvent, A: IAggregate<C, E>> Clone
+    for SqliteSnapshotStore<C, E, A>
+{
+    fn clone(&self) -> Self {
+        Self {
+            connection: self.connection.clone(),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<C: ICommand, E: IEvent, A: IAggregate<C, E>>
+    SqliteSnapshotStore<C, E, A>
+{
+    /// Constructor
+    #[must_use]
+    pub fn new(connection: SqliteConnection) -> Self {
+        Self {
+            connection,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<C: ICommand, E: IEvent, A: IAggregate<C, E>>
+    ISnapshotStore<C, E, A> for SqliteSnapshotStore<C, E, A>
+{
+    fn load_snapshot(
+        &mut self,
+        aggregate_id: &str,
+    ) -> Result<Option<AggregateContext<C, E, A>>, Error> {
+        let row = self
+            .connection
+            .lock()?
+            .query_row(
+                "SELECT version, payload FROM snapshots
+                 WHERE aggregate_type = ?1 AND aggregate_id = ?2",
+                params![A::aggregate_type(), aggregate_id],
+                |row| {
+                    Ok((
+                        row.get::<_, i64>(0)?,
+                        row.get::<_, String>(1)?,
+                    ))
+                },
+            )
+            .optional()
+            .map_err(|e| {
+                sqlite_error("unable to read the snapshot store", e)
+            })?;
+
+        let Some((version, payload)) = row
+        else {
+            return Ok(None);
+        };
+
+        let payload =
+            serde_json::from_str(&payload).map_err(|e| {
+                Error::Serialization {
+                    message: format!(
+                        "unable to deserialize the snapshot of \
+                         aggregate '{aggregate_id}': {e}"
+                    ),
+                    source: Some(e.into()),
+                }
+            })?;
+
+        trace!(
+            "loaded snapshot at version {} for aggregate '{}' of \
+             type '{}'",
+            version,
+            aggregate_id,
+            A::aggregate_type()
+        );
+
+        Ok(Some(AggregateContext::new(
+            aggregate_id.to_string(),
+            version,
+            payload,
+        )))
+    }
+
+    fn save_snapshot(
+        &mut self,
+        context: &AggregateContext<C, E, A>,
+    ) -> Result<(), Error> {
+        let payload = serde_json::to_string(&context.payload)
+            .map_err(|e| {
+                Error::Serialization {
+                    message: format!(
+                        "unable to serialize the snapshot of \
+                         aggregate '{}': {}",
+                        context.aggregate_id, e
+                    ),
+                    source: Some(e.into()),
+                }
+            })?;
+
+        trace!(
+            "saving snapshot at version {} for aggregate '{}' of \
+             type '{}'",
+            context.version,
+            context.aggregate_id,
+            A::aggregate_type()
+        );
+
+        self.connection
+            .lock()?
+            .execute(
+                "INSERT INTO snapshots (aggregate_type, \
+                 aggregate_id,
+                     version, payload)
+                 VALUES (?1, ?2, ?3, ?4)
+                 ON CONFLICT (aggregate_type, aggregate_id)
+                 DO UPDATE SET version = excluded.version,
+                               payload = excluded.payload",
+                params![
+                    A::aggregate_type(),
+                    context.aggregate_id,
+                    context.version,
+                    payload,
+                ],
+            )
+            .map_err(|e| {
+                sqlite_error(
+                    "unable to write to the snapshot store",
+                    e,
+                )
+            })?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,653 @@
+use chrono::{
+    Duration,
+    Utc,
+};
+use rusqlite::{
+    params,
+    Connection,
+};
+use std::{
+    fs,
+    path::PathBuf,
+};
+
+use crate::{
+    example_impl::*,
+    store_conformance::{
+        assert_event_store_conformance,
+        assert_query_store_conformance,
+        fixtures::{
+            email_updated,
+            name_added,
+        },
+    },
+    AggregateContext,
+    Error,
+    EventContext,
+    ICheckpointStore,
+    IEventStore,
+    IOutboxStore,
+    IQueryStore,
+    ISnapshotStore,
+    LocalEventPublisher,
+    OutboxRelay,
+    PositionedEvent,
+    QueryContext,
+};
+#[cfg(feature = "crypto")]
+use crate::{
+    store_conformance::assert_shredded_subject_load,
+    CryptoShredder,
+    IKeyStore,
+    ENCRYPTED_FIELD_KEY,
+};
+
+#[cfg(feature = "crypto")]
+use super::SqliteKeyStore;
+use super::{
+    SqliteCheckpointStore,
+    SqliteConnection,
+    SqliteEventStore,
+    SqliteOutboxStore,
+    SqliteQueryStore,
+    SqliteSnapshotStore,
+    SQLITE_SCHEMA_VERSION,
+};
+
+type ThisEventStore =
+    SqliteEventStore<CustomerCommand, CustomerEvent, Customer>;
+
+type ThisSnapshotStore =
+    SqliteSnapshotStore<CustomerCommand, CustomerEvent, Customer>;
+
+type ThisQueryStore = SqliteQueryStore<
+    CustomerCommand,
+    CustomerEvent,
+    CustomerContactQuery,
+>;
+
+/// A scratch database file removed when dropped
+struct TempFile(PathBuf);
+
+impl TempFile {
+    fn new() -> Self {
+        Self(std::env::temp_dir().join(format!(
+            "cqrs-es2-test-{}.db",
+            uuid::Uuid::new_v4()
+        )))
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.0);
+    }
+}
+
+fn event_store() -> ThisEventStore {
+    ThisEventStore::new(SqliteConnection::open_in_memory().unwrap())
+}
+
+fn audited(
+    context: EventContext<CustomerCommand, CustomerEvent>
+) -> EventContext<CustomerCommand, CustomerEvent> {
+    let mut context = context;
+    context.metadata.insert(
+        "principal".to_string(),
+        "admin".to_string(),
+    );
+    context
+}
+
+#[test]
+fn test_migrations_are_applied_once() {
+    let file = TempFile::new();
+
+    SqliteConnection::open(&file.0).unwrap();
+    SqliteConnection::open(&file.0).unwrap();
+
+    let connection = Connection::open(&file.0).unwrap();
+
+    let version: u32 = connection
+        .query_row("PRAGMA user_version", [], |row| {
+            row.get(0)
+        })
+        .unwrap();
+
+    assert_eq!(version, SQLITE_SCHEMA_VERSION);
+}
+
+#[test]
+fn test_newer_schema_is_rejected() {
+    let connection = Connection::open_in_memory().unwrap();
+
+    connection
+        .execute_batch(&format!(
+            "PRAGMA user_version = {}",
+            SQLITE_SCHEMA_VERSION + 1
+        ))
+        .unwrap();
+
+    assert!(matches!(
+        SqliteConnection::from_connection(connection).unwrap_err(),
+        Error::Store { .. }
+    ));
+}
+
+#[test]
+fn test_load_empty_aggregate() {
+    let mut store = event_store();
+
+    assert_eq!(
+        store.load_events("test_id_A").unwrap(),
+        Vec::new()
+    );
+
+    let context = store
+        .load_aggregate("test_id_A")
+        .unwrap();
+
+    assert_eq!(context.version, 0);
+    assert_eq!(context.payload, Customer::default());
+}
+
+#[test]
+fn test_commit_and_load_events() {
+    let mut store = event_store();
+
+    let first = vec![
+        name_added("test_id_A", 1, "John Doe"),
+        email_updated("test_id_A", 2, "john@example.com"),
+    ];
+    let second = vec![name_added("test_id_B", 1, "Jane Doe")];
+
+    store.commit(first.clone(), 0).unwrap();
+    store.commit(second.clone(), 0).unwrap();
+
+    assert_eq!(
+        store.load_events("test_id_A").unwrap(),
+        first
+    );
+    assert_eq!(
+        store.load_events("test_id_B").unwrap(),
+        second
+    );
+    assert_eq!(
+        store
+            .load_events_after("test_id_A", 1)
+            .unwrap(),
+        first[1..].to_vec()
+    );
+
+    let context = store
+        .load_aggregate("test_id_A")
+        .unwrap();
+
+    assert_eq!(context.version, 2);
+    assert_eq!(
+        context.payload.email,
+        "john@example.com"
+    );
+
+    assert_eq!(
+        store
+            .load_all_events_after(1, 1)
+            .unwrap(),
+        vec![PositionedEvent::new(
+            2,
+            email_updated("test_id_A", 2, "john@example.com")
+        )]
+    );
+    assert_eq!(
+        store.load_all_events().unwrap().len(),
+        3
+    );
+    assert_eq!(store.count_events_after(1).unwrap(), 2);
+}
+
+#[test]
+fn test_payload_and_metadata_are_json_columns() {
+    let connection = SqliteConnection::open_in_memory().unwrap();
+    let mut store = ThisEventStore::new(connection.clone());
+
+    store
+        .commit(
+            vec![audited(email_updated(
+                "test_id_A",
+                1,
+                "john@example.com",
+            ))],
+            0,
+        )
+        .unwrap();
+
+    let row: (String, String, String) = connection
+        .lock()
+        .unwrap()
+        .query_row(
+            "SELECT event_type,
+                    json_extract(payload, '$.new_email'),
+                    json_extract(metadata, '$.principal')
+             FROM events WHERE aggregate_id = ?1",
+            params!["test_id_A"],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .unwrap();
+
+    assert_eq!(
+        row,
+        (
+            "EmailUpdated".to_string(),
+            "john@example.com".to_string(),
+            "admin".to_string()
+        )
+    );
+}
+
+#[test]
+fn test_optimistic_concurrency() {
+    let mut store = event_store();
+
+    store
+        .commit(
+            vec![name_added("test_id_A", 1, "John Doe")],
+            0,
+        )
+        .unwrap();
+
+    assert_eq!(
+        store
+            .commit(
+                vec![name_added("test_id_A", 1, "Jane Doe")],
+                0
+            )
+            .unwrap_err(),
+        Error::ConcurrencyConflict {
+            aggregate_id: "test_id_A".to_string(),
+            expected: 0,
+            actual: 1,
+        }
+    );
+
+    assert!(matches!(
+        store
+            .commit(
+                vec![name_added("test_id_A", 3, "Jane Doe")],
+                1
+            )
+            .unwrap_err(),
+        Error::TechnicalError(_)
+    ));
+
+    assert_eq!(
+        store.load_events("test_id_A").unwrap(),
+        vec![name_added("test_id_A", 1, "John Doe")]
+    );
+}
+
+#[test]
+fn test_unique_constraint_rejects_duplicate_sequences() {
+    let connection = SqliteConnection::open_in_memory().unwrap();
+    let mut store = ThisEventStore::new(connection.clone());
+
+    store
+        .commit(
+            vec![name_added("test_id_A", 1, "John Doe")],
+            0,
+        )
+        .unwrap();
+
+    let result = connection.lock().unwrap().execute(
+        "INSERT INTO events (aggregate_type, aggregate_id, sequence,
+             event_type, event_version, payload, metadata)
+         VALUES ('customer', 'test_id_A', 1, 'NameAdded', 1, '{}',
+             '{}')",
+        [],
+    );
+
+    assert_eq!(
+        result.unwrap_err().sqlite_error_code(),
+        Some(rusqlite::ErrorCode::ConstraintViolation)
+    );
+}
+
+#[test]
+fn test_events_persist_across_connections() {
+    let file = TempFile::new();
+
+    ThisEventStore::new(SqliteConnection::open(&file.0).unwrap())
+        .commit(
+            vec![name_added("test_id_A", 1, "John Doe")],
+            0,
+        )
+        .unwrap();
+
+    let mut store =
+        ThisEventStore::new(SqliteConnection::open(&file.0).unwrap());
+
+    let context = store
+        .load_aggregate("test_id_A")
+        .unwrap();
+
+    assert_eq!(context.version, 1);
+    assert_eq!(context.payload.name, "John Doe");
+}
+
+#[test]
+fn test_checkpoints_persist_across_connections() {
+    let file = TempFile::new();
+
+    let mut store = SqliteCheckpointStore::new(
+        SqliteConnection::open(&file.0).unwrap(),
+    );
+
+    assert_eq!(store.load("subscriber").unwrap(), 0);
+
+    store.save("subscriber", 1).unwrap();
+    store.save("subscriber", 3).unwrap();
+    store.save("other", 2).unwrap();
+
+    let mut store = SqliteCheckpointStore::new(
+        SqliteConnection::open(&file.0).unwrap(),
+    );
+
+    assert_eq!(store.load("subscriber").unwrap(), 3);
+    assert_eq!(store.load("other").unwrap(), 2);
+}
+
+#[test]
+fn test_save_and_load_snapshots() {
+    let mut store = ThisSnapshotStore::new(
+        SqliteConnection::open_in_memory().unwrap(),
+    );
+
+    assert_eq!(
+        store
+            .load_snapshot("test_id_A")
+            .unwrap(),
+        None
+    );
+
+    let customer = Customer {
+        name: "John Doe".to_string(),
+        ..Customer::default()
+    };
+
+    let first =
+        AggregateContext::new("test_id_A".to_string(), 1, customer);
+    store.save_snapshot(&first).unwrap();
+
+    let mut second = first.clone();
+    second.version = 2;
+    second.payload.email = "john@example.com".to_string();
+    store.save_snapshot(&second).unwrap();
+
+    assert_eq!(
+        store
+            .load_snapshot("test_id_A")
+            .unwrap(),
+        Some(second)
+    );
+}
+
+#[test]
+fn test_save_and_load_queries() {
+    let mut store = ThisQueryStore::new(
+        SqliteConnection::open_in_memory().unwrap(),
+    );
+
+    let context = store.load("test_id_A").unwrap();
+
+    assert_eq!(context.version, 0);
+    assert_eq!(
+        context.payload,
+        CustomerContactQuery::default()
+    );
+
+    let mut payload = CustomerContactQuery {
+        name: "John Doe".to_string(),
+        ..CustomerContactQuery::default()
+    };
+
+    store
+        .save(QueryContext::new(
+            "test_id_A".to_string(),
+            1,
+            payload.clone(),
+        ))
+        .unwrap();
+
+    payload.email = "john@example.com".to_string();
+
+    store
+        .save(QueryContext::new(
+            "test_id_A".to_string(),
+            2,
+            payload.clone(),
+        ))
+        .unwrap();
+
+    let context = store.load("test_id_A").unwrap();
+
+    assert_eq!(context.version, 2);
+    assert_eq!(context.payload, payload);
+}
+
+#[test]
+fn test_store_conformance() {
+    assert_event_store_conformance(event_store);
+    assert_query_store_conformance(|| {
+        ThisQueryStore::new(
+            SqliteConnection::open_in_memory().unwrap(),
+        )
+    });
+}
+
+#[test]
+fn test_commit_writes_outbox() {
+    let connection = SqliteConnection::open_in_memory().unwrap();
+    let mut store =
+        ThisEventStore::new(connection.clone()).with_outbox();
+    let mut outbox = SqliteOutboxStore::new(connection.clone());
+
+    store
+        .commit(
+            vec![
+                name_added("test_id_A", 1, "John Doe"),
+                audited(email_updated(
+                    "test_id_A",
+                    2,
+                    "john@example.com",
+                )),
+            ],
+            0,
+        )
+        .unwrap();
+
+    // a rejected commit writes no outbox messages
+    assert!(store
+        .commit(
+            vec![name_added("test_id_A", 1, "Jane Doe")],
+            0
+        )
+        .is_err());
+
+    // stores without an outbox do not write any
+    ThisEventStore::new(connection)
+        .commit(
+            vec![name_added("test_id_B", 1, "Jane Doe")],
+            0,
+        )
+        .unwrap();
+
+    let messages = outbox
+        .load_pending(Utc::now(), 10)
+        .unwrap();
+
+    assert_eq!(
+        messages
+            .iter()
+            .map(|x| (x.id, x.event.event_type.as_str()))
+            .collect::<Vec<_>>(),
+        vec![(1, "NameAdded"), (2, "EmailUpdated")]
+    );
+    assert_eq!(
+        messages[1].event.metadata["principal"],
+        "admin"
+    );
+    assert_eq!(messages[0].attempts, 0);
+}
+
+#[test]
+fn test_outbox_retries_and_publishes() {
+    let connection = SqliteConnection::open_in_memory().unwrap();
+    let mut outbox = SqliteOutboxStore::new(connection.clone());
+
+    ThisEventStore::new(connection)
+        .with_outbox()
+        .commit(
+            vec![name_added("test_id_A", 1, "John Doe")],
+            0,
+        )
+        .unwrap();
+
+    let now = Utc::now();
+    let later = now + Duration::seconds(10);
+
+    outbox
+        .mark_failed(1, later, "broker unavailable")
+        .unwrap();
+
+    // the rescheduled message is not due yet
+    assert!(outbox
+        .load_pending(now, 10)
+        .unwrap()
+        .is_empty());
+
+    let messages = outbox.load_pending(later, 10).unwrap();
+
+    assert_eq!(messages[0].attempts, 1);
+    assert_eq!(messages[0].next_attempt_at, later);
+    assert_eq!(
+        messages[0].last_error.as_deref(),
+        Some("broker unavailable")
+    );
+
+    let publisher = LocalEventPublisher::default();
+
+    let report = OutboxRelay::new(outbox.clone(), publisher.clone())
+        .relay_at(later)
+        .unwrap();
+
+    assert_eq!(report.published, 1);
+    assert_eq!(publisher.published().unwrap().len(), 1);
+    assert!(outbox
+        .load_pending(later, 10)
+        .unwrap()
+        .is_empty());
+
+    assert_eq!(
+        outbox.mark_failed(1, later, "error"),
+        Err(Error::not_found("outbox message", "1"))
+    );
+}
+
+#[cfg(feature = "crypto")]
+#[test]
+fn test_shredded_subject_load() {
+    let connection = SqliteConnection::open_in_memory().unwrap();
+
+    assert_shredded_subject_load(|shredder| {
+        ThisEventStore::new(connection.clone())
+            .with_outbox()
+            .with_shredder(shredder)
+    });
+
+    // the personal data is only written encrypted, to both tables
+    for query in &[
+        "SELECT payload FROM events",
+        "SELECT event FROM outbox",
+    ] {
+        let payloads = connection
+            .lock()
+            .unwrap()
+            .prepare(query)
+            .unwrap()
+            .query_map([], |row| row.get::<_, String>(0))
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap()
+            .join("\n");
+
+        assert!(payloads.contains(ENCRYPTED_FIELD_KEY));
+        assert!(!payloads.contains("Jane Doe"));
+        assert!(!payloads.contains("jane@example.com"));
+        assert!(payloads.contains("1 Main Street"));
+    }
+}
+
+#[cfg(feature = "crypto")]
+#[test]
+fn test_key_store_shares_the_connection() {
+    let file = TempFile::new();
+    let connection = SqliteConnection::open(&file.0).unwrap();
+
+    let mut store = ThisEventStore::new(connection.clone())
+        .with_shredder(
+            CryptoShredder::new(SqliteKeyStore::new(connection))
+                .with_encrypted_field("EmailUpdated", "new_email"),
+        );
+
+    let events = vec![email_updated(
+        "test_id_A",
+        1,
+        "john@example.com",
+    )];
+
+    store.commit(events.clone(), 0).unwrap();
+
+    // a commit rejected by the concurrency check creates no key
+    assert!(matches!(
+        store
+            .commit(
+                vec![email_updated(
+                    "test_id_B",
+                    2,
+                    "jane@example.com"
+                )],
+                1
+            )
+            .unwrap_err(),
+        Error::ConcurrencyConflict { .. }
+    ));
+
+    assert_eq!(
+        store.load_events("test_id_A").unwrap(),
+        events
+    );
+
+    let mut keys =
+        SqliteKeyStore::new(SqliteConnection::open(&file.0).unwrap());
+
+    let key = keys.load_key("test_id_A").unwrap();
+
+    assert!(key.is_some());
+    assert!(keys
+        .load_key("test_id_B")
+        .unwrap()
+        .is_none());
+    assert_eq!(
+        keys.save_key("test_id_A", vec![0; 32])
+            .unwrap_err()
+            .code(),
+        "store_error"
+    );
+    assert_eq!(keys.load_key("test_id_A").unwrap(), key);
+
+    keys.delete_key("test_id_A").unwrap();
+
+    assert!(keys
+        .load_key("test_id_A")
+        .unwrap()
+        .is_none());
+}
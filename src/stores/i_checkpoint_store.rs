@@ -0,0 +1,33 @@
+use crate::errors::Error;
+
+/// The abstract central source for loading and saving the position
+/// in the global event stream each named subscriber has processed up
+/// to, so that it can resume exactly where it stopped after a
+/// restart.
+pub trait ICheckpointStore {
+    /// Load the last position processed by `subscriber`. A
+    /// subscriber that has never saved a checkpoint is at position
+    /// `0`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` when the underlying storage can not be
+    /// read.
+    fn load(
+        &mut self,
+        subscriber: &str,
+    ) -> Result<i64, Error>;
+
+    /// Save the last position processed by `subscriber`, replacing
+    /// its previous checkpoint.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` when the underlying storage can not be
+    /// written.
+    fn save(
+        &mut self,
+        subscriber: &str,
+        position: i64,
+    ) -> Result<(), Error>;
+}
@@ -0,0 +1,49 @@
+use chrono::{
+    DateTime,
+    Utc,
+};
+
+use crate::{
+    commands::ProcessedCommand,
+    errors::Error,
+};
+
+/// The abstract central source recording the command ids processed
+/// per aggregate instance, which makes retried commands idempotent.
+pub trait ICommandIdStore {
+    /// Load the record of the command `command_id` executed on the
+    /// aggregate instance `aggregate_id`, if any.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` when the underlying storage can not be
+    /// read.
+    fn load(
+        &mut self,
+        aggregate_id: &str,
+        command_id: &str,
+    ) -> Result<Option<ProcessedCommand>, Error>;
+
+    /// Save the record of a processed command.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` when the underlying storage can not be
+    /// written.
+    fn save(
+        &mut self,
+        command: ProcessedCommand,
+    ) -> Result<(), Error>;
+
+    /// Remove the records of the commands processed before `before`
+    /// and return their number.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` when the underlying storage can not be
+    /// written.
+    fn purge(
+        &mut self,
+        before: DateTime<Utc>,
+    ) -> Result<usize, Error>;
+}
@@ -0,0 +1,235 @@
+use crate::{
+    aggregates::{
+        AggregateContext,
+        IAggregate,
+    },
+    commands::ICommand,
+    errors::Error,
+    events::{
+        EventContext,
+        IEvent,
+        PositionedEvent,
+    },
+};
+
+/// The number of events loaded at once by the default
+/// `count_events_after`
+const COUNT_BATCH_SIZE: usize = 1000;
+
+/// The abstract central source for loading past events and
+/// committing new events. Every event store backend implements this
+/// interface so that the rest of the framework stays agnostic of the
+/// underlying storage.
+///
+/// # Examples
+/// ```rust
+/// use cqrs_es2::{
+///     example_impl::{
+///         Customer,
+///         CustomerCommand,
+///         CustomerEvent,
+///         NameAdded,
+///     },
+///     EventContext,
+///     IEventStore,
+///     InMemoryEventStore,
+/// };
+///
+/// let mut store = InMemoryEventStore::<
+///     CustomerCommand,
+///     CustomerEvent,
+///     Customer,
+/// >::default();
+///
+/// store
+///     .commit(
+///         vec![EventContext::new(
+///             "customer-1".to_string(),
+///             1,
+///             CustomerEvent::NameAdded(NameAdded {
+///                 changed_name: "John Doe".to_string(),
+///             }),
+///             Default::default(),
+///         )],
+///         0,
+///     )
+///     .unwrap();
+///
+/// let context = store
+///     .load_aggregate("customer-1")
+///     .unwrap();
+///
+/// assert_eq!(context.version, 1);
+/// assert_eq!(context.payload.name, "John Doe");
+/// ```
+pub trait IEventStore<C: ICommand, E: IEvent, A: IAggregate<C, E>> {
+    /// Load all events for a particular `aggregate_id` ordered by
+    /// their `sequence`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` when the underlying storage can not be
+    /// read.
+    fn load_events(
+        &mut self,
+        aggregate_id: &str,
+    ) -> Result<Vec<EventContext<C, E>>, Error>;
+
+    /// Load the events of a particular `aggregate_id` whose
+    /// `sequence` is strictly greater than `version`. The default
+    /// implementation filters the result of `load_events`, backends
+    /// should override it when they can do better.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` when the underlying storage can not be
+    /// read.
+    fn load_events_after(
+        &mut self,
+        aggregate_id: &str,
+        version: i64,
+    ) -> Result<Vec<EventContext<C, E>>, Error> {
+        Ok(self
+            .load_events(aggregate_id)?
+            .into_iter()
+            .filter(|x| x.sequence > version)
+            .collect())
+    }
+
+    /// Load the events of every aggregate instance of type `A` in
+    /// the global order they were committed in, e.g., to rebuild
+    /// projections. The default implementation returns an `Error`,
+    /// backends supporting it should override it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` when the backend does not support it or
+    /// the underlying storage can not be read.
+    fn load_all_events(
+        &mut self
+    ) -> Result<Vec<EventContext<C, E>>, Error> {
+        Err(Error::TechnicalError(format!(
+            "loading all events of aggregate type '{}' is not \
+             supported by this event store",
+            A::aggregate_type()
+        )))
+    }
+
+    /// Load at most `limit` events of every aggregate instance of
+    /// type `A` whose global position is strictly greater than
+    /// `position`, ordered by their position. Passing `0` reads the
+    /// stream from its beginning. The default implementation numbers
+    /// the result of `load_all_events` starting at `1`, backends
+    /// should override it when they can do better.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` when the backend does not support it or
+    /// the underlying storage can not be read.
+    fn load_all_events_after(
+        &mut self,
+        position: i64,
+        limit: usize,
+    ) -> Result<Vec<PositionedEvent<C, E>>, Error> {
+        Ok(self
+            .load_all_events()?
+            .into_iter()
+            .zip(1..)
+            .filter(|(_, x)| *x > position)
+            .take(limit)
+            .map(|(event, x)| PositionedEvent::new(x, event))
+            .collect())
+    }
+
+    /// Count the events of every aggregate instance of type `A`
+    /// whose global position is strictly greater than `position`,
+    /// e.g., to report the progress of a replay. The default
+    /// implementation pages through `load_all_events_after`,
+    /// backends should override it when they can count without
+    /// loading the events.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` when the backend does not support it or
+    /// the underlying storage can not be read.
+    fn count_events_after(
+        &mut self,
+        position: i64,
+    ) -> Result<usize, Error> {
+        let mut position = position;
+        let mut count = 0;
+
+        loop {
+            let events = self
+                .load_all_events_after(position, COUNT_BATCH_SIZE)?;
+
+            match events.last() {
+                None => return Ok(count),
+                Some(x) => position = x.position,
+            }
+
+            count += events.len();
+        }
+    }
+
+    /// Load the aggregate at its current state by replaying all of
+    /// its events on top of `A::default()`. An aggregate without
+    /// any events is returned at version `0`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` when the events can not be loaded.
+    fn load_aggregate(
+        &mut self,
+        aggregate_id: &str,
+    ) -> Result<AggregateContext<C, E, A>, Error> {
+        self.load_aggregate_from(AggregateContext::new(
+            aggregate_id.to_string(),
+            0,
+            A::default(),
+        ))
+    }
+
+    /// Bring an aggregate up to its current state starting from a
+    /// previous state, usually a snapshot, by replaying only the
+    /// events committed after its `version`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` when the events can not be loaded.
+    fn load_aggregate_from(
+        &mut self,
+        context: AggregateContext<C, E, A>,
+    ) -> Result<AggregateContext<C, E, A>, Error> {
+        let mut context = context;
+
+        let events = self.load_events_after(
+            &context.aggregate_id,
+            context.version,
+        )?;
+
+        for event in events {
+            context.payload.apply(&event.payload);
+            context.version = event.sequence;
+        }
+
+        Ok(context)
+    }
+
+    /// Commit new events of a single aggregate instance. The
+    /// `expected_version` is the version of the aggregate the events
+    /// were produced from, and the events must carry the consecutive
+    /// sequence numbers that follow it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error::ConcurrencyConflict` when the stored
+    /// version of the aggregate does not match `expected_version`,
+    /// and an `Error` when the events are not consistent or the
+    /// underlying storage can not be written.
+    fn commit(
+        &mut self,
+        events: Vec<EventContext<C, E>>,
+        expected_version: i64,
+    ) -> Result<(), Error>;
+}
@@ -0,0 +1,45 @@
+use crate::errors::Error;
+
+/// The abstract central source of the encryption keys used for
+/// crypto-shredding. Every subject, usually an aggregate instance,
+/// has its own key, and deleting the key makes the personal data
+/// encrypted with it unreadable for good.
+pub trait IKeyStore {
+    /// Load the key of `subject`, or `None` when it has never been
+    /// created or has been deleted.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` when the underlying storage can not be
+    /// read.
+    fn load_key(
+        &mut self,
+        subject: &str,
+    ) -> Result<Option<Vec<u8>>, Error>;
+
+    /// Save the key of `subject`, which must not have a key yet, so
+    /// that a key created concurrently for the same subject is never
+    /// replaced.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` when `subject` already has a key or when
+    /// the underlying storage can not be written.
+    fn save_key(
+        &mut self,
+        subject: &str,
+        key: Vec<u8>,
+    ) -> Result<(), Error>;
+
+    /// Delete the key of `subject`, which shreds all data encrypted
+    /// with it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` when the underlying storage can not be
+    /// written.
+    fn delete_key(
+        &mut self,
+        subject: &str,
+    ) -> Result<(), Error>;
+}
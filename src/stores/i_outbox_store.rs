@@ -0,0 +1,58 @@
+use chrono::{
+    DateTime,
+    Utc,
+};
+
+use crate::{
+    errors::Error,
+    outbox::OutboxMessage,
+};
+
+/// The abstract central source of the outbox messages. The messages
+/// are written by the event store in the same transaction as the
+/// committed events, and drained by the `OutboxRelay`.
+///
+/// The `InMemoryEventStore` fills an `InMemoryOutboxStore` and the
+/// `SqliteEventStore` fills a `SqliteOutboxStore`, both when
+/// configured with `with_outbox`. The other event stores do not
+/// write outbox messages.
+pub trait IOutboxStore {
+    /// Load at most `limit` messages that are due at `now`, ordered
+    /// by their id. Loading stops at the first message that is not
+    /// due yet, so that messages never overtake a rescheduled one.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` when the underlying storage can not be
+    /// read.
+    fn load_pending(
+        &mut self,
+        now: DateTime<Utc>,
+        limit: usize,
+    ) -> Result<Vec<OutboxMessage>, Error>;
+
+    /// Remove a message that has been published.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` when the underlying storage can not be
+    /// written.
+    fn mark_published(
+        &mut self,
+        id: i64,
+    ) -> Result<(), Error>;
+
+    /// Record a failed attempt to publish a message and postpone the
+    /// next attempt to `next_attempt_at`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` when the underlying storage can not be
+    /// written.
+    fn mark_failed(
+        &mut self,
+        id: i64,
+        next_attempt_at: DateTime<Utc>,
+        error: &str,
+    ) -> Result<(), Error>;
+}
@@ -0,0 +1,37 @@
+use crate::{
+    commands::ICommand,
+    errors::Error,
+    events::IEvent,
+    queries::{
+        IQuery,
+        QueryContext,
+    },
+};
+
+/// The abstract central source for loading and saving the query
+/// projections (aka views) of aggregate instances.
+pub trait IQueryStore<C: ICommand, E: IEvent, Q: IQuery<C, E>> {
+    /// Load the query of a particular `aggregate_id`. A query that
+    /// has never been saved is returned as `Q::default()` at version
+    /// `0`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` when the underlying storage can not be
+    /// read or the query can not be deserialized.
+    fn load(
+        &mut self,
+        aggregate_id: &str,
+    ) -> Result<QueryContext<C, E, Q>, Error>;
+
+    /// Save a query, replacing any previous version of it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` when the query can not be serialized or
+    /// the underlying storage can not be written.
+    fn save(
+        &mut self,
+        context: QueryContext<C, E, Q>,
+    ) -> Result<(), Error>;
+}
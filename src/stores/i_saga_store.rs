@@ -0,0 +1,59 @@
+use chrono::{
+    DateTime,
+    Utc,
+};
+
+use crate::{
+    commands::ICommand,
+    errors::Error,
+    events::IEvent,
+    sagas::{
+        ISaga,
+        SagaContext,
+    },
+};
+
+/// The abstract central source for loading and saving the state of
+/// saga instances.
+pub trait ISagaStore<
+    C: ICommand,
+    E: IEvent,
+    SC: ICommand,
+    S: ISaga<C, E, SC>,
+> {
+    /// Load the saga instance identified by `correlation_id`. A saga
+    /// that has never been saved is returned as `S::default()`
+    /// without any consumed events or timeouts.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` when the underlying storage can not be
+    /// read or the saga can not be deserialized.
+    fn load(
+        &mut self,
+        correlation_id: &str,
+    ) -> Result<SagaContext<C, E, SC, S>, Error>;
+
+    /// Load every saga instance having at least one timeout due at
+    /// or before `now`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` when the underlying storage can not be
+    /// read or a saga can not be deserialized.
+    fn load_due(
+        &mut self,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<SagaContext<C, E, SC, S>>, Error>;
+
+    /// Save a saga instance, replacing any previous state of it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` when the saga can not be serialized or the
+    /// underlying storage can not be written.
+    fn save(
+        &mut self,
+        context: SagaContext<C, E, SC, S>,
+    ) -> Result<(), Error>;
+}
@@ -0,0 +1,40 @@
+use crate::{
+    aggregates::{
+        AggregateContext,
+        IAggregate,
+    },
+    commands::ICommand,
+    errors::Error,
+    events::IEvent,
+};
+
+/// A snapshot store keeps the latest known state of aggregate
+/// instances so that they can be rehydrated without replaying their
+/// complete event history. Only the events committed after the
+/// snapshot `version` need to be applied on top of it.
+pub trait ISnapshotStore<C: ICommand, E: IEvent, A: IAggregate<C, E>>
+{
+    /// Load the latest snapshot of a particular `aggregate_id` if
+    /// one has been saved.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` when the underlying storage can not be
+    /// read or the snapshot can not be deserialized.
+    fn load_snapshot(
+        &mut self,
+        aggregate_id: &str,
+    ) -> Result<Option<AggregateContext<C, E, A>>, Error>;
+
+    /// Save a snapshot replacing any previous one of the same
+    /// aggregate instance.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` when the snapshot can not be serialized or
+    /// the underlying storage can not be written.
+    fn save_snapshot(
+        &mut self,
+        context: &AggregateContext<C, E, A>,
+    ) -> Result<(), Error>;
+}
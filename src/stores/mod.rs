@@ -0,0 +1,23 @@
+//! # stores
+//!
+//! A central location for store interfaces
+
+pub use i_checkpoint_store::ICheckpointStore;
+pub use i_command_id_store::ICommandIdStore;
+pub use i_event_store::IEventStore;
+#[cfg(feature = "crypto")]
+pub use i_key_store::IKeyStore;
+pub use i_outbox_store::IOutboxStore;
+pub use i_query_store::IQueryStore;
+pub use i_saga_store::ISagaStore;
+pub use i_snapshot_store::ISnapshotStore;
+
+mod i_checkpoint_store;
+mod i_command_id_store;
+mod i_event_store;
+#[cfg(feature = "crypto")]
+mod i_key_store;
+mod i_outbox_store;
+mod i_query_store;
+mod i_saga_store;
+mod i_snapshot_store;
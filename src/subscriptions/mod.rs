@@ -0,0 +1,15 @@
+//! # subscriptions
+//!
+//! A central location for subscriptions to the global event stream
+
+pub use subscription::{
+    Subscription,
+    DEFAULT_BATCH_SIZE,
+};
+pub use subscription_mode::SubscriptionMode;
+
+mod subscription;
+mod subscription_mode;
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,297 @@
+use log::{
+    debug,
+    error,
+    info,
+};
+use std::marker::PhantomData;
+
+use crate::{
+    aggregates::IAggregate,
+    commands::ICommand,
+    errors::Error,
+    events::{
+        EventContext,
+        IEvent,
+        IEventConsumer,
+    },
+    stores::{
+        ICheckpointStore,
+        IEventStore,
+    },
+};
+
+use super::subscription_mode::SubscriptionMode;
+
+/// The default number of events read from the event store at once
+pub const DEFAULT_BATCH_SIZE: usize = 100;
+
+/// A named `Subscription` delivers the global event stream of an
+/// event store to an `IEventConsumer` in position order and records
+/// a checkpoint after every delivered event. A subscription that is
+/// restarted with the same name and checkpoint store resumes right
+/// after the last event it delivered.
+///
+/// A subscription first catches up on the events committed before
+/// it was started and then goes live. It is itself an
+/// `IEventConsumer`, so once registered with the `CqrsFramework`
+/// every commit makes it read the newly committed events from the
+/// event store, which keeps the delivery in position order even
+/// when it fell behind.
+///
+/// Events are delivered at least once, i.e., an event may be
+/// redelivered when the checkpoint could not be saved, hence
+/// consumers should be idempotent, e.g., a `QueryProcessor`.
+///
+/// # Examples
+/// ```rust
+/// use cqrs_es2::{
+///     example_impl::{
+///         AddCustomerName,
+///         Customer,
+///         CustomerCommand,
+///         CustomerContactQuery,
+///         CustomerEvent,
+///     },
+///     CqrsFramework,
+///     ICheckpointStore,
+///     IQueryStore,
+///     InMemoryCheckpointStore,
+///     InMemoryEventStore,
+///     InMemoryQueryStore,
+///     QueryProcessor,
+///     Subscription,
+///     SubscriptionMode,
+/// };
+///
+/// let events = InMemoryEventStore::<
+///     CustomerCommand,
+///     CustomerEvent,
+///     Customer,
+/// >::default();
+///
+/// let mut checkpoints = InMemoryCheckpointStore::default();
+///
+/// let mut queries = InMemoryQueryStore::<
+///     CustomerCommand,
+///     CustomerEvent,
+///     CustomerContactQuery,
+/// >::default();
+///
+/// let mut subscription = Subscription::new(
+///     "customer-contacts",
+///     events.clone(),
+///     checkpoints.clone(),
+///     Box::new(QueryProcessor::new(queries.clone())),
+/// );
+///
+/// subscription.catch_up().unwrap();
+///
+/// assert_eq!(
+///     subscription.mode(),
+///     SubscriptionMode::Live
+/// );
+///
+/// let mut cqrs =
+///     CqrsFramework::new(events, vec![Box::new(subscription)]);
+///
+/// cqrs.execute(
+///     "customer-1",
+///     CustomerCommand::AddCustomerName(AddCustomerName {
+///         changed_name: "John Doe".to_string(),
+///     }),
+/// )
+/// .unwrap();
+///
+/// assert_eq!(
+///     queries
+///         .load("customer-1")
+///         .unwrap()
+///         .payload
+///         .name,
+///     "John Doe"
+/// );
+/// assert_eq!(
+///     checkpoints
+///         .load("customer-contacts")
+///         .unwrap(),
+///     1
+/// );
+/// ```
+pub struct Subscription<
+    C: ICommand,
+    E: IEvent,
+    A: IAggregate<C, E>,
+    ES: IEventStore<C, E, A>,
+    CS: ICheckpointStore,
+> {
+    name: String,
+    events: ES,
+    checkpoints: CS,
+    consumer: Box<dyn IEventConsumer<C, E>>,
+    batch_size: usize,
+    position: Option<i64>,
+    mode: SubscriptionMode,
+    _phantom: PhantomData<A>,
+}
+
+impl<
+        C: ICommand,
+        E: IEvent,
+        A: IAggregate<C, E>,
+        ES: IEventStore<C, E, A>,
+        CS: ICheckpointStore,
+    > Subscription<C, E, A, ES, CS>
+{
+    /// Constructor
+    pub fn new(
+        name: &str,
+        events: ES,
+        checkpoints: CS,
+        consumer: Box<dyn IEventConsumer<C, E>>,
+    ) -> Self {
+        Self {
+            name: name.to_string(),
+            events,
+            checkpoints,
+            consumer,
+            batch_size: DEFAULT_BATCH_SIZE,
+            position: None,
+            mode: SubscriptionMode::CatchingUp,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Sets the number of events read from the event store at once,
+    /// `DEFAULT_BATCH_SIZE` by default
+    #[must_use]
+    pub fn with_batch_size(
+        self,
+        batch_size: usize,
+    ) -> Self {
+        Self {
+            batch_size: batch_size.max(1),
+            ..self
+        }
+    }
+
+    /// The name of the subscriber the checkpoints are saved for
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The current mode of the subscription
+    #[must_use]
+    pub fn mode(&self) -> SubscriptionMode {
+        self.mode
+    }
+
+    /// The position of the last delivered event, loaded from the
+    /// checkpoint store on first use
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` when the checkpoint can not be loaded.
+    pub fn position(&mut self) -> Result<i64, Error> {
+        if let Some(x) = self.position {
+            return Ok(x);
+        }
+
+        let position = self.checkpoints.load(&self.name)?;
+
+        debug!(
+            "subscription '{}' starts after position {}",
+            self.name, position
+        );
+
+        self.position = Some(position);
+
+        Ok(position)
+    }
+
+    /// Reads and delivers a single batch of the events committed
+    /// after the current position. Returns the number of delivered
+    /// events.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` when the events can not be loaded or the
+    /// checkpoint can not be saved. The events delivered before the
+    /// failure remain checkpointed.
+    pub fn poll(&mut self) -> Result<usize, Error> {
+        let position = self.position()?;
+
+        let events = self
+            .events
+            .load_all_events_after(position, self.batch_size)?;
+
+        for event in &events {
+            self.consumer.update(&event.event);
+
+            self.checkpoints
+                .save(&self.name, event.position)?;
+            self.position = Some(event.position);
+        }
+
+        Ok(events.len())
+    }
+
+    /// Delivers all events committed after the current position,
+    /// batch by batch, and switches the subscription to live mode
+    /// once the stream is exhausted. Returns the number of delivered
+    /// events.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Error` when the events can not be loaded or the
+    /// checkpoint can not be saved.
+    pub fn catch_up(&mut self) -> Result<usize, Error> {
+        let mut delivered = 0;
+
+        loop {
+            let count = self.poll()?;
+
+            delivered += count;
+
+            if count < self.batch_size {
+                break;
+            }
+        }
+
+        if self.mode == SubscriptionMode::CatchingUp {
+            info!(
+                "subscription '{}' caught up after {} events and is \
+                 now live",
+                self.name, delivered
+            );
+
+            self.mode = SubscriptionMode::Live;
+        }
+
+        Ok(delivered)
+    }
+}
+
+impl<
+        C: ICommand,
+        E: IEvent,
+        A: IAggregate<C, E>,
+        ES: IEventStore<C, E, A>,
+        CS: ICheckpointStore,
+    > IEventConsumer<C, E> for Subscription<C, E, A, ES, CS>
+{
+    fn update(
+        &mut self,
+        event: &EventContext<C, E>,
+    ) {
+        // the event is already committed and read back from the
+        // event store along with its position
+        if let Err(e) = self.catch_up() {
+            error!(
+                "subscription '{}' is unable to read event {} of \
+                 aggregate '{}': {}",
+                self.name, event.sequence, event.aggregate_id, e
+            );
+        }
+    }
+}
@@ -0,0 +1,11 @@
+/// The mode of a `Subscription`
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum SubscriptionMode {
+    /// The subscription is reading the events committed before it
+    /// was started, or while it was stopped.
+    CatchingUp,
+
+    /// The subscription has read all past events and receives new
+    /// events as they are committed.
+    Live,
+}
@@ -0,0 +1,208 @@
+use std::sync::{
+    Arc,
+    Mutex,
+};
+
+use crate::{
+    example_impl::*,
+    store_conformance::fixtures::name_added,
+    CqrsFramework,
+    EventContext,
+    ICheckpointStore,
+    IEventConsumer,
+    IEventStore,
+    InMemoryCheckpointStore,
+    InMemoryEventStore,
+};
+
+use super::{
+    subscription::Subscription,
+    subscription_mode::SubscriptionMode,
+};
+
+type ThisEventStore =
+    InMemoryEventStore<CustomerCommand, CustomerEvent, Customer>;
+
+type ThisSubscription = Subscription<
+    CustomerCommand,
+    CustomerEvent,
+    Customer,
+    ThisEventStore,
+    InMemoryCheckpointStore,
+>;
+
+type Delivered = Arc<Mutex<Vec<(String, i64)>>>;
+
+struct RecordingConsumer {
+    delivered: Delivered,
+}
+
+impl IEventConsumer<CustomerCommand, CustomerEvent>
+    for RecordingConsumer
+{
+    fn update(
+        &mut self,
+        event: &EventContext<CustomerCommand, CustomerEvent>,
+    ) {
+        self.delivered.lock().unwrap().push((
+            event.aggregate_id.clone(),
+            event.sequence,
+        ));
+    }
+}
+
+fn new_subscription(
+    name: &str,
+    events: &ThisEventStore,
+    checkpoints: &InMemoryCheckpointStore,
+) -> (ThisSubscription, Delivered) {
+    let delivered = Delivered::default();
+
+    let subscription = Subscription::new(
+        name,
+        events.clone(),
+        checkpoints.clone(),
+        Box::new(RecordingConsumer {
+            delivered: Arc::clone(&delivered),
+        }),
+    );
+
+    (subscription, delivered)
+}
+
+fn committed_events() -> ThisEventStore {
+    let mut store = ThisEventStore::default();
+
+    store
+        .commit(
+            vec![
+                name_added("test_id_A", 1, "John Doe"),
+                name_added("test_id_A", 2, "John Doe"),
+            ],
+            0,
+        )
+        .unwrap();
+    store
+        .commit(
+            vec![name_added("test_id_B", 1, "John Doe")],
+            0,
+        )
+        .unwrap();
+    store
+        .commit(
+            vec![name_added("test_id_A", 3, "John Doe")],
+            2,
+        )
+        .unwrap();
+
+    store
+}
+
+#[test]
+fn test_catch_up_in_batches() {
+    let events = committed_events();
+    let mut checkpoints = InMemoryCheckpointStore::default();
+
+    let (subscription, delivered) =
+        new_subscription("test", &events, &checkpoints);
+    let mut subscription = subscription.with_batch_size(2);
+
+    assert_eq!(subscription.name(), "test");
+    assert_eq!(
+        subscription.mode(),
+        SubscriptionMode::CatchingUp
+    );
+
+    assert_eq!(subscription.poll().unwrap(), 2);
+    assert_eq!(
+        subscription.mode(),
+        SubscriptionMode::CatchingUp
+    );
+    assert_eq!(checkpoints.load("test").unwrap(), 2);
+
+    assert_eq!(subscription.catch_up().unwrap(), 2);
+    assert_eq!(
+        subscription.mode(),
+        SubscriptionMode::Live
+    );
+    assert_eq!(subscription.position().unwrap(), 4);
+    assert_eq!(checkpoints.load("test").unwrap(), 4);
+
+    assert_eq!(
+        *delivered.lock().unwrap(),
+        vec![
+            ("test_id_A".to_string(), 1),
+            ("test_id_A".to_string(), 2),
+            ("test_id_B".to_string(), 1),
+            ("test_id_A".to_string(), 3),
+        ]
+    );
+
+    // nothing new to deliver
+    assert_eq!(subscription.catch_up().unwrap(), 0);
+}
+
+#[test]
+fn test_resume_from_checkpoint() {
+    let mut events = committed_events();
+    let checkpoints = InMemoryCheckpointStore::default();
+
+    let (mut subscription, _) =
+        new_subscription("test", &events, &checkpoints);
+
+    subscription.catch_up().unwrap();
+
+    events
+        .commit(
+            vec![name_added("test_id_B", 2, "John Doe")],
+            1,
+        )
+        .unwrap();
+
+    // a restarted subscriber only receives what it missed
+    let (mut restarted, delivered) =
+        new_subscription("test", &events, &checkpoints);
+
+    assert_eq!(restarted.position().unwrap(), 4);
+    assert_eq!(restarted.catch_up().unwrap(), 1);
+    assert_eq!(
+        *delivered.lock().unwrap(),
+        vec![("test_id_B".to_string(), 2)]
+    );
+
+    // checkpoints are kept per subscriber name
+    let (mut other, delivered) =
+        new_subscription("other", &events, &checkpoints);
+
+    assert_eq!(other.catch_up().unwrap(), 5);
+    assert_eq!(delivered.lock().unwrap().len(), 5);
+}
+
+#[test]
+fn test_live_mode() {
+    let events = committed_events();
+    let mut checkpoints = InMemoryCheckpointStore::default();
+
+    let (mut subscription, delivered) =
+        new_subscription("test", &events, &checkpoints);
+
+    subscription.catch_up().unwrap();
+
+    let mut cqrs =
+        CqrsFramework::new(events, vec![Box::new(subscription)]);
+
+    cqrs.execute(
+        "test_id_B",
+        CustomerCommand::UpdateEmail(UpdateEmail {
+            new_email: "j@d.com".to_string(),
+        }),
+    )
+    .unwrap();
+
+    assert_eq!(delivered.lock().unwrap().len(), 5);
+    assert_eq!(
+        delivered.lock().unwrap().last(),
+        Some(&("test_id_B".to_string(), 2))
+    );
+    assert_eq!(checkpoints.load("test").unwrap(), 5);
+}
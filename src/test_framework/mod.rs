@@ -1,5 +1,8 @@
 pub use test_consumer::*;
 pub use test_handler::*;
+pub use test_saga::*;
 
+pub mod store_conformance;
 mod test_consumer;
 mod test_handler;
+mod test_saga;
@@ -0,0 +1,395 @@
+use std::collections::HashMap;
+
+use super::fixtures::{
+    address_updated,
+    email_updated,
+    name_added,
+};
+use crate::{
+    errors::Error,
+    example_impl::{
+        Customer,
+        CustomerCommand,
+        CustomerEvent,
+    },
+    stores::IEventStore,
+};
+#[cfg(feature = "crypto")]
+use crate::{
+    memory_store::InMemoryKeyStore,
+    shredding::{
+        CryptoShredder,
+        REDACTED_PLACEHOLDER,
+    },
+};
+
+/// Verifies that loading an aggregate without any events returns no
+/// events and `Customer::default()` at version `0`.
+///
+/// # Panics
+///
+/// Panics when `store` does not conform.
+pub fn assert_empty_aggregate_load<ES>(store: &mut ES)
+where
+    ES: IEventStore<CustomerCommand, CustomerEvent, Customer>, {
+    assert_eq!(
+        store
+            .load_events("conformance-empty")
+            .expect(
+                "loading the events of an empty aggregate failed"
+            ),
+        Vec::new(),
+        "an empty aggregate must not have any events"
+    );
+
+    let context = store
+        .load_aggregate("conformance-empty")
+        .expect("loading an empty aggregate failed");
+
+    assert_eq!(
+        context.aggregate_id, "conformance-empty",
+        "an empty aggregate must keep its id"
+    );
+    assert_eq!(
+        context.version, 0,
+        "an empty aggregate must be at version 0"
+    );
+    assert_eq!(
+        context.payload,
+        Customer::default(),
+        "an empty aggregate must be at its default state"
+    );
+}
+
+/// Verifies that the events committed to an empty `store` are loaded
+/// back per aggregate instance in the order of their sequence, and
+/// that replaying them yields the expected aggregate state.
+///
+/// # Panics
+///
+/// Panics when `store` does not conform.
+pub fn assert_append_and_load_ordering<ES>(store: &mut ES)
+where
+    ES: IEventStore<CustomerCommand, CustomerEvent, Customer>, {
+    let id_a = "conformance-a";
+    let id_b = "conformance-b";
+
+    let commits = vec![
+        (
+            vec![
+                name_added(id_a, 1, "John Doe"),
+                email_updated(id_a, 2, "john@example.com"),
+            ],
+            0,
+        ),
+        (vec![name_added(id_b, 1, "Jane Doe")], 0),
+        (
+            vec![address_updated(
+                id_a,
+                3,
+                "1 Main Street",
+            )],
+            2,
+        ),
+        (
+            vec![
+                email_updated(id_b, 2, "jane@example.com"),
+                address_updated(id_b, 3, "2 Main Street"),
+            ],
+            1,
+        ),
+        (
+            vec![email_updated(
+                id_a,
+                4,
+                "doe@example.com",
+            )],
+            3,
+        ),
+    ];
+
+    let mut expected_a = Vec::new();
+    let mut expected_b = Vec::new();
+
+    for (events, expected_version) in commits {
+        for event in &events {
+            if event.aggregate_id == id_a {
+                expected_a.push(event.clone());
+            }
+            else {
+                expected_b.push(event.clone());
+            }
+        }
+
+        store
+            .commit(events, expected_version)
+            .expect("committing consecutive events failed");
+    }
+
+    assert_eq!(
+        store
+            .load_events(id_a)
+            .expect("loading the events failed"),
+        expected_a,
+        "the events must be loaded in the order of their sequence"
+    );
+    assert_eq!(
+        store
+            .load_events(id_b)
+            .expect("loading the events failed"),
+        expected_b,
+        "the events must be loaded in the order of their sequence"
+    );
+    assert_eq!(
+        store
+            .load_events_after(id_a, 2)
+            .expect("loading the events after a version failed"),
+        expected_a[2..].to_vec(),
+        "only the events after the version must be loaded"
+    );
+
+    let context = store
+        .load_aggregate(id_a)
+        .expect("loading the aggregate failed");
+
+    assert_eq!(
+        context.version, 4,
+        "the aggregate must be at the version of its last event"
+    );
+    assert_eq!(
+        context.payload,
+        Customer {
+            customer_id: String::new(),
+            name: "John Doe".to_string(),
+            email: "doe@example.com".to_string(),
+            addresses: vec!["1 Main Street".to_string()],
+        },
+        "replaying the events must yield the aggregate state"
+    );
+}
+
+/// Verifies that committing against a stale or a future version of
+/// an aggregate is rejected without changing the stored events.
+///
+/// # Panics
+///
+/// Panics when `store` does not conform.
+pub fn assert_version_conflict<ES>(store: &mut ES)
+where
+    ES: IEventStore<CustomerCommand, CustomerEvent, Customer>, {
+    let id = "conformance-conflict";
+
+    let committed = vec![
+        name_added(id, 1, "John Doe"),
+        email_updated(id, 2, "john@example.com"),
+    ];
+
+    store
+        .commit(committed.clone(), 0)
+        .expect("committing consecutive events failed");
+
+    assert_eq!(
+        store.commit(vec![name_added(id, 2, "Jane Doe")], 1),
+        Err(Error::ConcurrencyConflict {
+            aggregate_id: id.to_string(),
+            expected: 1,
+            actual: 2,
+        }),
+        "committing against a stale version must be a conflict"
+    );
+
+    assert!(
+        store
+            .commit(vec![name_added(id, 6, "Jane Doe")], 5)
+            .is_err(),
+        "committing against a future version must fail"
+    );
+
+    assert_eq!(
+        store
+            .load_events(id)
+            .expect("loading the events failed"),
+        committed,
+        "a rejected commit must not change the stored events"
+    );
+}
+
+/// Verifies that the metadata of the committed events is loaded back
+/// unchanged.
+///
+/// # Panics
+///
+/// Panics when `store` does not conform.
+pub fn assert_metadata_round_trip<ES>(store: &mut ES)
+where
+    ES: IEventStore<CustomerCommand, CustomerEvent, Customer>, {
+    let id = "conformance-metadata";
+
+    let mut metadata = HashMap::new();
+    metadata.insert(
+        "correlation_id".to_string(),
+        "c-1".to_string(),
+    );
+    metadata.insert(
+        "principal".to_string(),
+        "Zoë \"admin\"".to_string(),
+    );
+    metadata.insert("empty".to_string(), String::new());
+
+    let mut first = name_added(id, 1, "John Doe");
+    first.metadata = metadata;
+
+    let events = vec![
+        first,
+        email_updated(id, 2, "john@example.com"),
+    ];
+
+    store
+        .commit(events.clone(), 0)
+        .expect("committing events with metadata failed");
+
+    assert_eq!(
+        store
+            .load_events(id)
+            .expect("loading the events failed"),
+        events,
+        "the metadata must be loaded back unchanged"
+    );
+}
+
+/// Verifies that the events of a subject shredded with
+/// `CryptoShredder::forget` still load, with their encrypted fields
+/// redacted, while the events of the other subjects load unchanged,
+/// and that the events the subject commits afterwards load with its
+/// new key.
+///
+/// The check is not part of `assert_event_store_conformance`, as it
+/// needs `new_store` to create an empty store encrypting the
+/// committed events with the given shredder.
+///
+/// # Panics
+///
+/// Panics when the store does not conform.
+#[cfg(feature = "crypto")]
+pub fn assert_shredded_subject_load<ES, F>(new_store: F)
+where
+    ES: IEventStore<CustomerCommand, CustomerEvent, Customer>,
+    F: FnOnce(CryptoShredder<InMemoryKeyStore>) -> ES, {
+    let id_a = "conformance-shredded";
+    let id_b = "conformance-kept";
+
+    let key_store = InMemoryKeyStore::default();
+
+    let mut store = new_store(
+        CryptoShredder::new(key_store.clone())
+            .with_encrypted_field("NameAdded", "changed_name")
+            .with_encrypted_field("EmailUpdated", "new_email"),
+    );
+
+    let events_a = vec![
+        name_added(id_a, 1, "John Doe"),
+        email_updated(id_a, 2, "john@example.com"),
+        address_updated(id_a, 3, "1 Main Street"),
+    ];
+    let events_b = vec![
+        name_added(id_b, 1, "Jane Doe"),
+        email_updated(id_b, 2, "jane@example.com"),
+    ];
+
+    store
+        .commit(events_a.clone(), 0)
+        .expect("committing events with personal data failed");
+    store
+        .commit(events_b.clone(), 0)
+        .expect("committing events with personal data failed");
+
+    assert_eq!(
+        store
+            .load_events(id_a)
+            .expect("loading the encrypted events failed"),
+        events_a,
+        "the encrypted fields must be decrypted when loaded"
+    );
+
+    CryptoShredder::new(key_store)
+        .forget(id_a)
+        .expect("forgetting the subject failed");
+
+    assert_eq!(
+        store.load_events(id_a).expect(
+            "loading the events of a shredded subject failed"
+        ),
+        vec![
+            name_added(id_a, 1, REDACTED_PLACEHOLDER),
+            email_updated(id_a, 2, REDACTED_PLACEHOLDER),
+            events_a[2].clone(),
+        ],
+        "the encrypted fields of a shredded subject must be redacted"
+    );
+
+    let renamed = name_added(id_a, 4, "John Smith");
+
+    store
+        .commit(vec![renamed.clone()], 3)
+        .expect("committing events of a shredded subject failed");
+
+    assert_eq!(
+        store.load_events(id_a).expect(
+            "loading the events of a shredded subject failed"
+        ),
+        vec![
+            name_added(id_a, 1, REDACTED_PLACEHOLDER),
+            email_updated(id_a, 2, REDACTED_PLACEHOLDER),
+            events_a[2].clone(),
+            renamed,
+        ],
+        "the events committed after shredding must be decrypted \
+         with the new key only"
+    );
+    assert_eq!(
+        store
+            .load_events(id_b)
+            .expect("loading the encrypted events failed"),
+        events_b,
+        "the other subjects must not be shredded"
+    );
+    assert_eq!(
+        store
+            .load_all_events()
+            .expect("loading all events failed")
+            .len(),
+        6,
+        "the events of a shredded subject must still be loaded"
+    );
+
+    let context = store
+        .load_aggregate(id_a)
+        .expect("loading the aggregate of a shredded subject failed");
+
+    assert_eq!(
+        context.payload,
+        Customer {
+            customer_id: String::new(),
+            name: "John Smith".to_string(),
+            email: REDACTED_PLACEHOLDER.to_string(),
+            addresses: vec!["1 Main Street".to_string()],
+        },
+        "replaying the events of a shredded subject must succeed"
+    );
+}
+
+/// Runs every event store check, each against a new empty store
+/// created by `new_store`.
+///
+/// # Panics
+///
+/// Panics when the stores do not conform.
+pub fn assert_event_store_conformance<ES, F>(new_store: F)
+where
+    ES: IEventStore<CustomerCommand, CustomerEvent, Customer>,
+    F: Fn() -> ES, {
+    assert_empty_aggregate_load(&mut new_store());
+    assert_append_and_load_ordering(&mut new_store());
+    assert_version_conflict(&mut new_store());
+    assert_metadata_round_trip(&mut new_store());
+}
@@ -0,0 +1,81 @@
+//! Factories of the `Customer` events committed by the conformance
+//! checks, shared with the unit tests of the crate
+
+use std::collections::HashMap;
+
+use crate::{
+    events::EventContext,
+    example_impl::{
+        AddressUpdated,
+        CustomerCommand,
+        CustomerEvent,
+        EmailUpdated,
+        NameAdded,
+    },
+};
+
+/// A `NameAdded` event changing the name to `name`
+pub(crate) fn name_added_event(name: &str) -> CustomerEvent {
+    CustomerEvent::NameAdded(NameAdded {
+        changed_name: name.to_string(),
+    })
+}
+
+/// An `EmailUpdated` event changing the email to `email`
+pub(crate) fn email_updated_event(email: &str) -> CustomerEvent {
+    CustomerEvent::EmailUpdated(EmailUpdated {
+        new_email: email.to_string(),
+    })
+}
+
+/// An `AddressUpdated` event adding the address `address`
+pub(crate) fn address_updated_event(address: &str) -> CustomerEvent {
+    CustomerEvent::AddressUpdated(AddressUpdated {
+        new_address: address.to_string(),
+    })
+}
+
+/// The `name_added_event` at `sequence` of the aggregate
+/// `aggregate_id`, without metadata
+pub(crate) fn name_added(
+    aggregate_id: &str,
+    sequence: i64,
+    name: &str,
+) -> EventContext<CustomerCommand, CustomerEvent> {
+    EventContext::new(
+        aggregate_id.to_string(),
+        sequence,
+        name_added_event(name),
+        HashMap::default(),
+    )
+}
+
+/// The `email_updated_event` at `sequence` of the aggregate
+/// `aggregate_id`, without metadata
+pub(crate) fn email_updated(
+    aggregate_id: &str,
+    sequence: i64,
+    email: &str,
+) -> EventContext<CustomerCommand, CustomerEvent> {
+    EventContext::new(
+        aggregate_id.to_string(),
+        sequence,
+        email_updated_event(email),
+        HashMap::default(),
+    )
+}
+
+/// The `address_updated_event` at `sequence` of the aggregate
+/// `aggregate_id`, without metadata
+pub(crate) fn address_updated(
+    aggregate_id: &str,
+    sequence: i64,
+    address: &str,
+) -> EventContext<CustomerCommand, CustomerEvent> {
+    EventContext::new(
+        aggregate_id.to_string(),
+        sequence,
+        address_updated_event(address),
+        HashMap::default(),
+    )
+}
@@ -0,0 +1,122 @@
+//! # Store conformance
+//!
+//! A reusable test suite verifying that a store backend behaves like
+//! the in-memory stores, written against `example_impl::Customer`
+//! and `example_impl::CustomerContactQuery`.
+//!
+//! Every check expects an empty store and panics with a description
+//! of the violated expectation. The checks can be called one by one,
+//! all at once through `assert_event_store_conformance` and
+//! `assert_query_store_conformance`, or turned into one test per
+//! check with the `event_store_conformance_tests!` and
+//! `query_store_conformance_tests!` macros. With the `crypto`
+//! feature, `assert_shredded_subject_load` verifies a store
+//! configured with a `CryptoShredder`.
+//!
+//! # Examples
+//! ```rust
+//! use cqrs_es2::{
+//!     store_conformance::{
+//!         assert_event_store_conformance,
+//!         assert_query_store_conformance,
+//!     },
+//!     InMemoryEventStore,
+//!     InMemoryQueryStore,
+//! };
+//!
+//! assert_event_store_conformance(InMemoryEventStore::default);
+//! assert_query_store_conformance(InMemoryQueryStore::default);
+//! ```
+//!
+//! ```rust
+//! mod in_memory_conformance {
+//!     use cqrs_es2::{
+//!         event_store_conformance_tests,
+//!         query_store_conformance_tests,
+//!         InMemoryEventStore,
+//!         InMemoryQueryStore,
+//!     };
+//!
+//!     event_store_conformance_tests!(InMemoryEventStore::default());
+//!     query_store_conformance_tests!(InMemoryQueryStore::default());
+//! }
+//! ```
+
+#[cfg(feature = "crypto")]
+pub use event_store_conformance::assert_shredded_subject_load;
+pub use event_store_conformance::{
+    assert_append_and_load_ordering,
+    assert_empty_aggregate_load,
+    assert_event_store_conformance,
+    assert_metadata_round_trip,
+    assert_version_conflict,
+};
+pub use query_store_conformance::{
+    assert_query_empty_load,
+    assert_query_store_conformance,
+    assert_query_upsert,
+};
+
+pub(crate) mod fixtures;
+
+mod event_store_conformance;
+mod query_store_conformance;
+
+#[cfg(test)]
+mod test;
+
+/// Generates one test per event store conformance check, each
+/// against a new empty store created by evaluating `$new_store`.
+#[macro_export]
+macro_rules! event_store_conformance_tests {
+    ($new_store:expr) => {
+        #[test]
+        fn event_store_empty_aggregate_load() {
+            $crate::store_conformance::assert_empty_aggregate_load(
+                &mut $new_store,
+            );
+        }
+
+        #[test]
+        fn event_store_append_and_load_ordering() {
+            $crate::store_conformance::assert_append_and_load_ordering(
+                &mut $new_store,
+            );
+        }
+
+        #[test]
+        fn event_store_version_conflict() {
+            $crate::store_conformance::assert_version_conflict(
+                &mut $new_store,
+            );
+        }
+
+        #[test]
+        fn event_store_metadata_round_trip() {
+            $crate::store_conformance::assert_metadata_round_trip(
+                &mut $new_store,
+            );
+        }
+    };
+}
+
+/// Generates one test per query store conformance check, each
+/// against a new empty store created by evaluating `$new_store`.
+#[macro_export]
+macro_rules! query_store_conformance_tests {
+    ($new_store:expr) => {
+        #[test]
+        fn query_store_empty_load() {
+            $crate::store_conformance::assert_query_empty_load(
+                &mut $new_store,
+            );
+        }
+
+        #[test]
+        fn query_store_upsert() {
+            $crate::store_conformance::assert_query_upsert(
+                &mut $new_store,
+            );
+        }
+    };
+}
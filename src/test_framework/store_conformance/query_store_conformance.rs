@@ -0,0 +1,145 @@
+use crate::{
+    example_impl::{
+        CustomerCommand,
+        CustomerContactQuery,
+        CustomerEvent,
+    },
+    queries::QueryContext,
+    stores::IQueryStore,
+};
+
+/// Verifies that loading a query that has never been saved returns
+/// `CustomerContactQuery::default()` at version `0`.
+///
+/// # Panics
+///
+/// Panics when `store` does not conform.
+pub fn assert_query_empty_load<QS>(store: &mut QS)
+where
+    QS: IQueryStore<
+        CustomerCommand,
+        CustomerEvent,
+        CustomerContactQuery,
+    >, {
+    let context = store
+        .load("conformance-empty")
+        .expect("loading a missing query failed");
+
+    assert_eq!(
+        context.aggregate_id, "conformance-empty",
+        "a missing query must keep its aggregate id"
+    );
+    assert_eq!(
+        context.version, 0,
+        "a missing query must be at version 0"
+    );
+    assert_eq!(
+        context.payload,
+        CustomerContactQuery::default(),
+        "a missing query must be at its default state"
+    );
+}
+
+/// Verifies that saving a query inserts it the first time and
+/// replaces it afterwards, without touching the queries of other
+/// aggregate instances.
+///
+/// # Panics
+///
+/// Panics when `store` does not conform.
+pub fn assert_query_upsert<QS>(store: &mut QS)
+where
+    QS: IQueryStore<
+        CustomerCommand,
+        CustomerEvent,
+        CustomerContactQuery,
+    >, {
+    let id_a = "conformance-a";
+    let id_b = "conformance-b";
+
+    let first = CustomerContactQuery {
+        name: "John Doe".to_string(),
+        ..CustomerContactQuery::default()
+    };
+
+    let second = CustomerContactQuery {
+        email: "john@example.com".to_string(),
+        ..first.clone()
+    };
+
+    let other = CustomerContactQuery {
+        name: "Jane Doe".to_string(),
+        ..CustomerContactQuery::default()
+    };
+
+    store
+        .save(QueryContext::new(
+            id_a.to_string(),
+            1,
+            first.clone(),
+        ))
+        .expect("inserting a query failed");
+    store
+        .save(QueryContext::new(
+            id_b.to_string(),
+            1,
+            other.clone(),
+        ))
+        .expect("inserting a query failed");
+
+    let context = store
+        .load(id_a)
+        .expect("loading a query failed");
+
+    assert_eq!(
+        (context.version, context.payload),
+        (1, first),
+        "an inserted query must be loaded back"
+    );
+
+    store
+        .save(QueryContext::new(
+            id_a.to_string(),
+            2,
+            second.clone(),
+        ))
+        .expect("updating a query failed");
+
+    let context = store
+        .load(id_a)
+        .expect("loading a query failed");
+
+    assert_eq!(
+        (context.version, context.payload),
+        (2, second),
+        "an updated query must replace the previous one"
+    );
+
+    let context = store
+        .load(id_b)
+        .expect("loading a query failed");
+
+    assert_eq!(
+        (context.version, context.payload),
+        (1, other),
+        "updating a query must not change other queries"
+    );
+}
+
+/// Runs every query store check, each against a new empty store
+/// created by `new_store`.
+///
+/// # Panics
+///
+/// Panics when the stores do not conform.
+pub fn assert_query_store_conformance<QS, F>(new_store: F)
+where
+    QS: IQueryStore<
+        CustomerCommand,
+        CustomerEvent,
+        CustomerContactQuery,
+    >,
+    F: Fn() -> QS, {
+    assert_query_empty_load(&mut new_store());
+    assert_query_upsert(&mut new_store());
+}
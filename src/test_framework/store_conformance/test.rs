@@ -0,0 +1,48 @@
+use crate::{
+    example_impl::*,
+    InMemoryEventStore,
+    InMemoryQueryStore,
+};
+
+mod in_memory_event_store {
+    use super::*;
+
+    crate::event_store_conformance_tests!(InMemoryEventStore::<
+        CustomerCommand,
+        CustomerEvent,
+        Customer,
+    >::default());
+}
+
+mod in_memory_query_store {
+    use super::*;
+
+    crate::query_store_conformance_tests!(InMemoryQueryStore::<
+        CustomerCommand,
+        CustomerEvent,
+        CustomerContactQuery,
+    >::default());
+}
+
+#[test]
+fn test_in_memory_stores_conform() {
+    super::assert_event_store_conformance(
+        InMemoryEventStore::<CustomerCommand, CustomerEvent, Customer>::default,
+    );
+    super::assert_query_store_conformance(
+        InMemoryQueryStore::<
+            CustomerCommand,
+            CustomerEvent,
+            CustomerContactQuery,
+        >::default,
+    );
+}
+
+#[cfg(feature = "crypto")]
+#[test]
+fn test_in_memory_event_store_shredding_conforms() {
+    super::assert_shredded_subject_load(|shredder| {
+        InMemoryEventStore::<CustomerCommand, CustomerEvent, Customer>::default()
+            .with_shredder(shredder)
+    });
+}
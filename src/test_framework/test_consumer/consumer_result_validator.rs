@@ -36,6 +36,7 @@ impl<
 
     /// Verifies that the expected projection has been produced by
     /// consumer handler
+    #[allow(clippy::needless_pass_by_value)]
     pub fn then_expect(
         self,
         expected: Q,
@@ -1,8 +1,15 @@
+use std::fmt::{
+    Debug,
+    Write,
+};
+
 use crate::{
     errors::Error,
     events::IEvent,
 };
 
+use super::user_error_validator::UserErrorValidator;
+
 /// Validation object for the `HandlerTester`
 pub struct HandlerResultValidator<E: IEvent> {
     result: Result<Vec<E>, Error>,
@@ -15,11 +22,127 @@ impl<E: IEvent> HandlerResultValidator<E> {
 
     /// Verifies that the expected events have been produced by the
     /// command handler
+    #[allow(clippy::needless_pass_by_value)]
     pub fn then_expect(
         self,
         expected: Vec<E>,
     ) {
-        let events = match self.result {
+        let events = self.events();
+
+        assert!(
+            events == expected,
+            "unexpected events (- expected, + received):\n{}",
+            events_diff(&expected, &events)
+        );
+    }
+
+    /// Verifies that the command handler produced one event per
+    /// predicate, each event matching the predicate at its position
+    pub fn then_expect_events_matching(
+        self,
+        predicates: &[&dyn Fn(&E) -> bool],
+    ) {
+        let events = self.events();
+
+        let matches = events.len() == predicates.len() &&
+            events
+                .iter()
+                .zip(predicates)
+                .all(|(event, predicate)| predicate(event));
+
+        if !matches {
+            let mut report = String::new();
+
+            for (x, event) in events.iter().enumerate() {
+                let mark = match predicates.get(x) {
+                    Some(predicate) if predicate(event) => ' ',
+                    _ => '+',
+                };
+
+                let _ = writeln!(report, "{mark} [{x}] {event:?}");
+            }
+
+            for x in events.len()..predicates.len() {
+                let _ = writeln!(report, "- [{x}] <missing>");
+            }
+
+            panic!(
+                "expected {} events matching the predicates, \
+                 received {} (+ not matching, - missing):\n{}",
+                predicates.len(),
+                events.len(),
+                report
+            );
+        }
+    }
+
+    /// Verifies that an `Error` with the expected message is
+    /// produced from the command handler
+    pub fn then_expect_error(
+        self,
+        error_message: &str,
+    ) {
+        self.then_expect_user_error()
+            .and_message(error_message);
+    }
+
+    /// Verifies that a user or validation error with the expected
+    /// code is produced from the command handler, and provides a
+    /// validator object to check its message and params
+    pub fn then_expect_error_code(
+        self,
+        code: &str,
+    ) -> UserErrorValidator {
+        self.then_expect_user_error()
+            .and_code(code)
+    }
+
+    /// Verifies that a user or validation error is produced from the
+    /// command handler, and provides a validator object to check its
+    /// code, message and params
+    pub fn then_expect_user_error(self) -> UserErrorValidator {
+        match self.error() {
+            Error::UserError(e) | Error::Validation(e) => {
+                UserErrorValidator::new(e)
+            },
+            e => {
+                panic!(
+                    "expected user error but found {} error: {}",
+                    e.category(),
+                    e
+                )
+            },
+        }
+    }
+
+    /// Verifies that an `Error::TechnicalError` with the expected
+    /// message is produced from the command handler
+    pub fn then_expect_technical_error(
+        self,
+        error_message: &str,
+    ) {
+        match self.error() {
+            Error::TechnicalError(message) => {
+                assert_eq!(message, error_message);
+            },
+            e => {
+                panic!(
+                    "expected technical error but found {} error: {}",
+                    e.category(),
+                    e
+                )
+            },
+        }
+    }
+
+    /// Hands back the raw result of the command handler for custom
+    /// checks
+    pub fn then_inspect(self) -> Result<Vec<E>, Error> {
+        self.result
+    }
+
+    fn events(self) -> Vec<E> {
+        match self.result {
             Ok(x) => x,
             Err(e) => {
                 panic!(
@@ -27,17 +150,11 @@ impl<E: IEvent> HandlerResultValidator<E> {
                     e
                 );
             },
-        };
-        assert_eq!(&events[..], &expected[..]);
+        }
     }
 
-    /// Verifies that an `Error` with the expected message is
-    /// produced from the command handler
-    pub fn then_expect_error(
-        self,
-        error_message: &str,
-    ) {
-        let err = match self.result {
+    fn error(self) -> Error {
+        match self.result {
             Ok(events) => {
                 panic!(
                     "expected error, received events: '{:?}'",
@@ -45,22 +162,34 @@ impl<E: IEvent> HandlerResultValidator<E> {
                 );
             },
             Err(e) => e,
-        };
+        }
+    }
+}
 
-        match err {
-            Error::TechnicalError(e) => {
-                panic!(
-                    "expected user error but found technical error: \
-                     {}",
-                    e
-                )
+/// Renders the differences between two event lists line by line,
+/// prefixing the expected events with `-`, the received events with
+/// `+` and the events found in both with a space
+pub(crate) fn events_diff<E: Debug + PartialEq>(
+    expected: &[E],
+    received: &[E],
+) -> String {
+    let mut diff = String::new();
+
+    for x in 0..expected.len().max(received.len()) {
+        match (expected.get(x), received.get(x)) {
+            (Some(a), Some(b)) if a == b => {
+                let _ = writeln!(diff, "  [{x}] {a:?}");
             },
-            Error::UserError(e) => {
-                assert_eq!(
-                    e.message,
-                    Some(error_message.to_string())
-                );
+            (a, b) => {
+                if let Some(a) = a {
+                    let _ = writeln!(diff, "- [{x}] {a:?}");
+                }
+                if let Some(b) = b {
+                    let _ = writeln!(diff, "+ [{x}] {b:?}");
+                }
             },
         }
     }
+
+    diff
 }
@@ -0,0 +1,87 @@
+use crate::{
+    errors::Error,
+    events::{
+        IEvent,
+        IEventHandler,
+    },
+};
+
+use super::handler_result_validator::{
+    events_diff,
+    HandlerResultValidator,
+};
+
+/// Validation object for a scenario of several commands run by the
+/// `HandlerTester`, holding the cumulative events and the final
+/// aggregate state
+pub struct HandlerScenarioValidator<E: IEvent, A: IEventHandler<E>> {
+    result: Result<Vec<E>, Error>,
+    aggregate: A,
+}
+
+impl<E: IEvent, A: IEventHandler<E>> HandlerScenarioValidator<E, A> {
+    pub fn new(
+        result: Result<Vec<E>, Error>,
+        aggregate: A,
+    ) -> Self {
+        Self { result, aggregate }
+    }
+
+    /// Verifies that the expected events have been produced by all
+    /// the commands of the scenario, in order
+    #[allow(clippy::needless_pass_by_value)]
+    pub fn then_expect_events(
+        self,
+        expected: Vec<E>,
+    ) -> Self {
+        let events = match &self.result {
+            Ok(x) => x,
+            Err(e) => {
+                panic!(
+                    "expected success, received error: '{}'",
+                    e
+                );
+            },
+        };
+
+        assert!(
+            events == &expected,
+            "unexpected events (- expected, + received):\n{}",
+            events_diff(&expected, events)
+        );
+
+        self
+    }
+
+    /// Verifies the final aggregate state, with all the given events
+    /// and the events produced by the scenario applied
+    pub fn then_expect_state<F: FnOnce(&A)>(
+        self,
+        check: F,
+    ) -> Self {
+        check(&self.aggregate);
+        self
+    }
+
+    /// Verifies that an `Error` with the expected message is
+    /// produced by one of the commands of the scenario
+    pub fn then_expect_error(
+        self,
+        error_message: &str,
+    ) {
+        self.then_result()
+            .then_expect_error(error_message);
+    }
+
+    /// Provides a validator object for the cumulative result of the
+    /// scenario, either all the produced events or the error of the
+    /// first failing command
+    pub fn then_result(self) -> HandlerResultValidator<E> {
+        HandlerResultValidator::new(self.result)
+    }
+
+    /// Hands back the final aggregate state for custom checks
+    pub fn into_aggregate(self) -> A {
+        self.aggregate
+    }
+}
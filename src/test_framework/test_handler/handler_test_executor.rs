@@ -11,9 +11,13 @@ use crate::{
     },
 };
 
-use super::handler_result_validator::HandlerResultValidator;
+use super::{
+    handler_result_validator::HandlerResultValidator,
+    handler_scenario_validator::HandlerScenarioValidator,
+};
 
-/// Holds the initial event state and accepts a command
+/// Holds the initial event state and accepts a command or a scenario
+/// of several commands
 pub struct HandlerResultExecutor<
     C: ICommand,
     E: IEvent,
@@ -52,4 +56,42 @@ impl<
 
         HandlerResultValidator::new(result)
     }
+
+    /// Consumes a scenario of commands, applying the events produced
+    /// by every command to the aggregate before handling the next
+    /// one, and provides a validator object to test the cumulative
+    /// events and the final aggregate state against. The scenario
+    /// stops at the first failing command.
+    pub fn when_all(
+        self,
+        commands: Vec<C>,
+    ) -> HandlerScenarioValidator<E, A> {
+        let mut handler = A::default();
+
+        for event in self.events {
+            handler.apply(&event);
+        }
+
+        let mut events = Vec::new();
+
+        for command in commands {
+            match handler.handle(command) {
+                Ok(x) => {
+                    for event in &x {
+                        handler.apply(event);
+                    }
+
+                    events.extend(x);
+                },
+                Err(e) => {
+                    return HandlerScenarioValidator::new(
+                        Err(e),
+                        handler,
+                    );
+                },
+            }
+        }
+
+        HandlerScenarioValidator::new(Ok(events), handler)
+    }
 }
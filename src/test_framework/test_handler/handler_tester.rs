@@ -20,6 +20,7 @@ use super::handler_test_executor::HandlerResultExecutor;
 /// ```rust
 /// use cqrs_es2::{
 ///     example_impl::{
+///         AddAddress,
 ///         AddCustomerName,
 ///         Customer,
 ///         CustomerCommand,
@@ -58,7 +59,24 @@ use super::handler_test_executor::HandlerResultExecutor;
 ///     ))
 ///     .then_expect_error(
 ///         "a name has already been added for this customer",
-///     )
+///     );
+///
+/// CustomTester::default()
+///     .given_no_previous_events()
+///     .when_all(vec![
+///         CustomerCommand::AddAddress(AddAddress {
+///             new_address: "1 Main St".to_string(),
+///         }),
+///         CustomerCommand::AddAddress(AddAddress {
+///             new_address: "2 Main St".to_string(),
+///         }),
+///     ])
+///     .then_expect_state(|customer: &Customer| {
+///         assert_eq!(
+///             customer.addresses,
+///             vec!["1 Main St", "2 Main St"]
+///         );
+///     });
 /// ```
 pub struct HandlerTester<
     C: ICommand,
@@ -1,8 +1,10 @@
 pub use handler_tester::HandlerTester;
 
 mod handler_result_validator;
+mod handler_scenario_validator;
 mod handler_test_executor;
 mod handler_tester;
+mod user_error_validator;
 
 #[cfg(test)]
 mod test;
@@ -1,6 +1,25 @@
-use crate::example_impl::*;
+use std::collections::HashMap;
 
-use super::handler_tester::HandlerTester;
+use crate::{
+    errors::{
+        Error,
+        UserError,
+    },
+    example_impl::*,
+    store_conformance::fixtures::{
+        address_updated_event,
+        email_updated_event,
+        name_added_event,
+    },
+};
+
+use super::{
+    handler_result_validator::{
+        events_diff,
+        HandlerResultValidator,
+    },
+    handler_tester::HandlerTester,
+};
 
 type ThisTester =
     HandlerTester<CustomerCommand, CustomerEvent, Customer>;
@@ -40,7 +59,7 @@ fn test_handler_tester() {
         ))
         .then_expect_error(
             "a name has already been added for this customer",
-        )
+        );
 }
 
 #[test]
@@ -84,5 +103,250 @@ fn test_handler_tester_failure_test_b() {
                 new_email: test_name.to_string(),
             },
         ))
-        .then_expect_error("some error message")
+        .then_expect_error("some error message");
+}
+
+fn user_error() -> Error {
+    let mut params = HashMap::new();
+    params.insert(
+        "name".to_string(),
+        "is required".to_string(),
+    );
+    params.insert(
+        "email".to_string(),
+        "is invalid".to_string(),
+    );
+
+    Error::Validation(UserError {
+        code: Some("invalid_customer".to_string()),
+        message: Some("the command is invalid".to_string()),
+        params: Some(params),
+    })
+}
+
+#[test]
+fn test_handler_tester_events_matching() {
+    ThisTester::default()
+        .given_no_previous_events()
+        .when(CustomerCommand::AddCustomerName(
+            AddCustomerName {
+                changed_name: "John Doe".to_string(),
+            },
+        ))
+        .then_expect_events_matching(&[&|x| {
+            matches!(
+                x,
+                CustomerEvent::NameAdded(NameAdded { changed_name })
+                    if changed_name.starts_with("John")
+            )
+        }]);
+}
+
+#[test]
+#[should_panic(
+    expected = "expected 2 events matching the predicates"
+)]
+fn test_handler_tester_events_matching_failure() {
+    HandlerResultValidator::new(Ok(vec![name_added_event(
+        "John Doe",
+    )]))
+    .then_expect_events_matching(&[
+        &|x| matches!(x, CustomerEvent::EmailUpdated(_)),
+        &|_| true,
+    ]);
+}
+
+#[test]
+fn test_handler_tester_error_code_and_params() {
+    let mut params = HashMap::new();
+    params.insert(
+        "name".to_string(),
+        "is required".to_string(),
+    );
+    params.insert(
+        "email".to_string(),
+        "is invalid".to_string(),
+    );
+
+    HandlerResultValidator::<CustomerEvent>::new(Err(user_error()))
+        .then_expect_error_code("invalid_customer")
+        .and_message("the command is invalid")
+        .and_param("email", "is invalid")
+        .and_params(params);
+
+    HandlerResultValidator::<CustomerEvent>::new(Err(user_error()))
+        .then_expect_error("the command is invalid");
+}
+
+#[test]
+#[should_panic(expected = "unexpected error code")]
+fn test_handler_tester_wrong_error_code() {
+    let _ = HandlerResultValidator::<CustomerEvent>::new(Err(
+        user_error(),
+    ))
+    .then_expect_error_code("another_code");
+}
+
+#[test]
+#[should_panic(expected = "unexpected error param 'name'")]
+fn test_handler_tester_wrong_error_param() {
+    let _ = HandlerResultValidator::<CustomerEvent>::new(Err(
+        user_error(),
+    ))
+    .then_expect_user_error()
+    .and_param("name", "is too long");
+}
+
+#[test]
+fn test_handler_tester_technical_error() {
+    HandlerResultValidator::<CustomerEvent>::new(Err(
+        Error::TechnicalError("the database is down".to_string()),
+    ))
+    .then_expect_technical_error("the database is down");
+}
+
+#[test]
+#[should_panic(
+    expected = "expected technical error but found Validation"
+)]
+fn test_handler_tester_technical_error_failure() {
+    HandlerResultValidator::<CustomerEvent>::new(Err(user_error()))
+        .then_expect_technical_error("the database is down");
+}
+
+#[test]
+#[should_panic(expected = "expected user error but found Technical")]
+fn test_handler_tester_user_error_failure() {
+    HandlerResultValidator::<CustomerEvent>::new(Err(
+        Error::TechnicalError("the database is down".to_string()),
+    ))
+    .then_expect_error("some error message");
+}
+
+#[test]
+fn test_handler_tester_inspect() {
+    let result = ThisTester::default()
+        .given(vec![name_added_event("John Doe")])
+        .when(CustomerCommand::AddCustomerName(
+            AddCustomerName {
+                changed_name: "John Doe".to_string(),
+            },
+        ))
+        .then_inspect();
+
+    assert_eq!(result.unwrap_err().code(), "user_error");
+}
+
+#[test]
+#[should_panic(
+    expected = "unexpected events (- expected, + received)"
+)]
+fn test_handler_tester_events_diff_on_failure() {
+    HandlerResultValidator::new(Ok(vec![name_added_event(
+        "John Doe",
+    )]))
+    .then_expect(vec![name_added_event("Jane Doe")]);
+}
+
+#[test]
+fn test_events_diff() {
+    let expected = vec![
+        name_added_event("John Doe"),
+        email_updated_event("john@example.com"),
+    ];
+    let received = vec![
+        name_added_event("John Doe"),
+        email_updated_event("doe@example.com"),
+        email_updated_event("jane@example.com"),
+    ];
+
+    assert_eq!(
+        events_diff(&expected, &received),
+        format!(
+            "  [0] {:?}\n- [1] {:?}\n+ [1] {:?}\n+ [2] {:?}\n",
+            expected[0], expected[1], received[1], received[2]
+        )
+    );
+}
+
+fn add_address(address: &str) -> CustomerCommand {
+    CustomerCommand::AddAddress(AddAddress {
+        new_address: address.to_string(),
+    })
+}
+
+#[test]
+fn test_handler_tester_scenario() {
+    ThisTester::default()
+        .given(vec![name_added_event("John Doe")])
+        .when_all(vec![
+            add_address("1 Main St"),
+            add_address("2 Main St"),
+        ])
+        .then_expect_events(vec![
+            address_updated_event("1 Main St"),
+            address_updated_event("2 Main St"),
+        ])
+        .then_expect_state(|customer| {
+            assert_eq!(customer.name, "John Doe");
+            assert_eq!(
+                customer.addresses,
+                vec!["1 Main St", "2 Main St"]
+            );
+        });
+}
+
+#[test]
+fn test_handler_tester_scenario_stops_at_first_error() {
+    let customer = ThisTester::default()
+        .given_no_previous_events()
+        .when_all(vec![
+            add_address("1 Main St"),
+            add_address("1 Main St"),
+            add_address("2 Main St"),
+        ])
+        .into_aggregate();
+
+    assert_eq!(customer.addresses, vec!["1 Main St"]);
+
+    ThisTester::default()
+        .given_no_previous_events()
+        .when_all(vec![
+            add_address("1 Main St"),
+            add_address("1 Main St"),
+        ])
+        .then_expect_error(
+            "this address has already been added for this customer",
+        );
+}
+
+#[test]
+fn test_handler_tester_scenario_result() {
+    ThisTester::default()
+        .given_no_previous_events()
+        .when_all(vec![
+            CustomerCommand::AddCustomerName(AddCustomerName {
+                changed_name: "John Doe".to_string(),
+            }),
+            add_address("1 Main St"),
+        ])
+        .then_result()
+        .then_expect_events_matching(&[
+            &|e| matches!(e, CustomerEvent::NameAdded(_)),
+            &|e| matches!(e, CustomerEvent::AddressUpdated(_)),
+        ]);
+}
+
+#[test]
+#[should_panic(
+    expected = "unexpected events (- expected, + received)"
+)]
+fn test_handler_tester_scenario_events_failure() {
+    ThisTester::default()
+        .given_no_previous_events()
+        .when_all(vec![
+            add_address("1 Main St"),
+            add_address("2 Main St"),
+        ])
+        .then_expect_events(vec![address_updated_event("1 Main St")]);
 }
@@ -0,0 +1,85 @@
+use std::collections::HashMap;
+
+use crate::errors::UserError;
+
+/// Validation object for the `UserError` produced by a command
+/// handler under test
+pub struct UserErrorValidator {
+    error: UserError,
+}
+
+impl UserErrorValidator {
+    pub fn new(error: UserError) -> Self {
+        Self { error }
+    }
+
+    /// Verifies the code of the error
+    pub fn and_code(
+        self,
+        code: &str,
+    ) -> Self {
+        assert_eq!(
+            self.error.code.as_deref(),
+            Some(code),
+            "unexpected error code of {}",
+            self.error
+        );
+        self
+    }
+
+    /// Verifies the message of the error
+    pub fn and_message(
+        self,
+        message: &str,
+    ) -> Self {
+        assert_eq!(
+            self.error.message.as_deref(),
+            Some(message),
+            "unexpected error message of {}",
+            self.error
+        );
+        self
+    }
+
+    /// Verifies that the params of the error hold `value` for `key`,
+    /// regardless of the other params
+    pub fn and_param(
+        self,
+        key: &str,
+        value: &str,
+    ) -> Self {
+        assert_eq!(
+            self.error
+                .params
+                .as_ref()
+                .and_then(|x| x.get(key))
+                .map(String::as_str),
+            Some(value),
+            "unexpected error param '{}' of {}",
+            key,
+            self.error
+        );
+        self
+    }
+
+    /// Verifies that the params of the error are exactly `expected`
+    #[allow(clippy::needless_pass_by_value)]
+    pub fn and_params(
+        self,
+        expected: HashMap<String, String>,
+    ) -> Self {
+        assert_eq!(
+            self.error.params.as_ref(),
+            Some(&expected),
+            "unexpected error params of {}",
+            self.error
+        );
+        self
+    }
+
+    /// Hands back the error for further checks
+    #[must_use]
+    pub fn into_inner(self) -> UserError {
+        self.error
+    }
+}
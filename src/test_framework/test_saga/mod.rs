@@ -0,0 +1,8 @@
+pub use saga_tester::SagaTester;
+
+mod saga_result_validator;
+mod saga_test_executor;
+mod saga_tester;
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,102 @@
+use crate::{
+    commands::ICommand,
+    errors::Error,
+    sagas::SagaAction,
+};
+
+/// Validation object for the `SagaTester`
+pub struct SagaResultValidator<SC: ICommand, S> {
+    result: Result<Vec<SagaAction<SC>>, Error>,
+    saga: S,
+}
+
+impl<SC: ICommand, S: std::fmt::Debug + PartialEq>
+    SagaResultValidator<SC, S>
+{
+    pub fn new(
+        result: Result<Vec<SagaAction<SC>>, Error>,
+        saga: S,
+    ) -> Self {
+        Self { result, saga }
+    }
+
+    /// Verifies that the expected actions have been requested by the
+    /// saga
+    #[allow(clippy::needless_pass_by_value)]
+    pub fn then_expect(
+        self,
+        expected: Vec<SagaAction<SC>>,
+    ) -> Self {
+        assert_eq!(&self.actions()[..], &expected[..]);
+        self
+    }
+
+    /// Verifies that the expected commands have been dispatched by
+    /// the saga, regardless of their target aggregates and of the
+    /// other actions
+    #[allow(clippy::needless_pass_by_value)]
+    pub fn then_expect_commands(
+        self,
+        expected: Vec<SC>,
+    ) -> Self {
+        let commands: Vec<_> = self
+            .actions()
+            .iter()
+            .filter_map(|x| {
+                match x {
+                    SagaAction::Dispatch { command, .. } => {
+                        Some(command)
+                    },
+                    _ => None,
+                }
+            })
+            .collect();
+
+        assert_eq!(
+            commands,
+            expected.iter().collect::<Vec<_>>()
+        );
+        self
+    }
+
+    /// Verifies the state of the saga after the test
+    #[allow(clippy::needless_pass_by_value)]
+    pub fn then_expect_state(
+        self,
+        expected: S,
+    ) -> Self {
+        assert_eq!(&self.saga, &expected);
+        self
+    }
+
+    /// Verifies that an `Error` with the expected message is
+    /// produced by the saga
+    pub fn then_expect_error(
+        self,
+        error_message: &str,
+    ) {
+        match self.result {
+            Ok(actions) => {
+                panic!(
+                    "expected error, received actions: '{:?}'",
+                    actions
+                );
+            },
+            Err(e) => {
+                assert_eq!(e.to_string(), error_message);
+            },
+        }
+    }
+
+    fn actions(&self) -> &Vec<SagaAction<SC>> {
+        match &self.result {
+            Ok(x) => x,
+            Err(e) => {
+                panic!(
+                    "expected success, received error: '{}'",
+                    e
+                );
+            },
+        }
+    }
+}
@@ -0,0 +1,79 @@
+use std::marker::PhantomData;
+
+use crate::{
+    commands::ICommand,
+    errors::Error,
+    events::{
+        EventContext,
+        IEvent,
+    },
+    sagas::ISaga,
+};
+
+use super::saga_result_validator::SagaResultValidator;
+
+/// Holds the initial state of a saga and accepts an event, a timeout
+/// or a failed command
+pub struct SagaResultExecutor<
+    C: ICommand,
+    E: IEvent,
+    SC: ICommand,
+    S: ISaga<C, E, SC>,
+> {
+    pub saga: S,
+    _phantom: PhantomData<(C, E, SC)>,
+}
+
+impl<C: ICommand, E: IEvent, SC: ICommand, S: ISaga<C, E, SC>>
+    SagaResultExecutor<C, E, SC, S>
+{
+    pub fn new(saga: S) -> Self {
+        Self {
+            saga,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Consumes an event using the state details previously passed
+    /// and provides a validator object to test against
+    pub fn when(
+        self,
+        event: &EventContext<C, E>,
+    ) -> SagaResultValidator<SC, S> {
+        let mut saga = self.saga;
+
+        let result = saga.handle(event);
+
+        SagaResultValidator::new(result, saga)
+    }
+
+    /// Fires the timeout `name` using the state details previously
+    /// passed and provides a validator object to test against
+    pub fn when_timeout(
+        self,
+        name: &str,
+    ) -> SagaResultValidator<SC, S> {
+        let mut saga = self.saga;
+
+        let result = saga.on_timeout(name);
+
+        SagaResultValidator::new(result, saga)
+    }
+
+    /// Reports a dispatched command as failed with `error` using the
+    /// state details previously passed and provides a validator
+    /// object to test the compensation against
+    #[allow(clippy::needless_pass_by_value)]
+    pub fn when_command_fails(
+        self,
+        aggregate_id: &str,
+        command: SC,
+        error: Error,
+    ) -> SagaResultValidator<SC, S> {
+        let mut saga = self.saga;
+
+        let result = saga.compensate(aggregate_id, &command, &error);
+
+        SagaResultValidator::new(result, saga)
+    }
+}
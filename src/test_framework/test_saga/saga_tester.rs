@@ -0,0 +1,118 @@
+use std::marker::PhantomData;
+
+use crate::{
+    commands::ICommand,
+    events::IEvent,
+    sagas::ISaga,
+};
+
+use super::saga_test_executor::SagaResultExecutor;
+
+/// `SagaTester` provides a consistent way to test saga
+/// implementations
+///
+/// # Examples
+/// ```rust
+/// use std::time::Duration;
+///
+/// use cqrs_es2::{
+///     example_impl::{
+///         CustomerCommand,
+///         CustomerEvent,
+///         EmailUpdated,
+///         EmailVerificationSaga,
+///         NotificationCommand,
+///         SendEmail,
+///         REMINDER_SUBJECT,
+///         REMINDER_TIMEOUT,
+///         VERIFICATION_SUBJECT,
+///     },
+///     EventContext,
+///     SagaAction,
+///     SagaTester,
+/// };
+///
+/// type CustomTester = SagaTester<
+///     CustomerCommand,
+///     CustomerEvent,
+///     NotificationCommand,
+///     EmailVerificationSaga,
+/// >;
+///
+/// CustomTester::default()
+///     .given_no_previous_state()
+///     .when(&EventContext::new(
+///         "customer-1".to_string(),
+///         1,
+///         CustomerEvent::EmailUpdated(EmailUpdated {
+///             new_email: "j@d.com".to_string(),
+///         }),
+///         Default::default(),
+///     ))
+///     .then_expect(vec![
+///         SagaAction::dispatch(
+///             "customer-1",
+///             NotificationCommand::SendEmail(SendEmail {
+///                 to: "j@d.com".to_string(),
+///                 subject: VERIFICATION_SUBJECT.to_string(),
+///             }),
+///         ),
+///         SagaAction::schedule_timeout(
+///             REMINDER_TIMEOUT,
+///             Duration::from_secs(24 * 60 * 60),
+///         ),
+///     ]);
+///
+/// CustomTester::default()
+///     .given(EmailVerificationSaga {
+///         customer_id: "customer-1".to_string(),
+///         email: "j@d.com".to_string(),
+///         failed: false,
+///     })
+///     .when_timeout(REMINDER_TIMEOUT)
+///     .then_expect_commands(vec![NotificationCommand::SendEmail(
+///         SendEmail {
+///             to: "j@d.com".to_string(),
+///             subject: REMINDER_SUBJECT.to_string(),
+///         },
+///     )]);
+/// ```
+pub struct SagaTester<
+    C: ICommand,
+    E: IEvent,
+    SC: ICommand,
+    S: ISaga<C, E, SC>,
+> {
+    _phantom: PhantomData<(C, E, SC, S)>,
+}
+
+impl<C: ICommand, E: IEvent, SC: ICommand, S: ISaga<C, E, SC>>
+    SagaTester<C, E, SC, S>
+{
+    /// Initiates a saga test with no previous state
+    #[must_use]
+    pub fn given_no_previous_state(
+        &self
+    ) -> SagaResultExecutor<C, E, SC, S> {
+        SagaResultExecutor::new(S::default())
+    }
+
+    /// Initiates a saga test with a previous state
+    #[must_use]
+    pub fn given(
+        &self,
+        saga: S,
+    ) -> SagaResultExecutor<C, E, SC, S> {
+        SagaResultExecutor::new(saga)
+    }
+}
+
+impl<C: ICommand, E: IEvent, SC: ICommand, S: ISaga<C, E, SC>> Default
+    for SagaTester<C, E, SC, S>
+{
+    fn default() -> Self {
+        SagaTester {
+            _phantom: PhantomData,
+        }
+    }
+}
@@ -0,0 +1,136 @@
+use std::{
+    collections::HashMap,
+    time::Duration,
+};
+
+use crate::{
+    example_impl::*,
+    store_conformance::fixtures::email_updated,
+    Error,
+    EventContext,
+    SagaAction,
+};
+
+use super::saga_tester::SagaTester;
+
+type ThisTester = SagaTester<
+    CustomerCommand,
+    CustomerEvent,
+    NotificationCommand,
+    EmailVerificationSaga,
+>;
+
+fn send_email(subject: &str) -> NotificationCommand {
+    NotificationCommand::SendEmail(SendEmail {
+        to: "j@d.com".to_string(),
+        subject: subject.to_string(),
+    })
+}
+
+fn started_saga() -> EmailVerificationSaga {
+    EmailVerificationSaga {
+        customer_id: "test_id_A".to_string(),
+        email: "j@d.com".to_string(),
+        failed: false,
+    }
+}
+
+#[test]
+fn test_saga_tester() {
+    ThisTester::default()
+        .given_no_previous_state()
+        .when(&email_updated(
+            "test_id_A",
+            2,
+            "j@d.com",
+        ))
+        .then_expect(vec![
+            SagaAction::dispatch(
+                "test_id_A",
+                send_email(VERIFICATION_SUBJECT),
+            ),
+            SagaAction::schedule_timeout(
+                REMINDER_TIMEOUT,
+                Duration::from_secs(24 * 60 * 60),
+            ),
+        ])
+        .then_expect_state(started_saga());
+
+    ThisTester::default()
+        .given(started_saga())
+        .when(&EventContext::new(
+            "test_id_A".to_string(),
+            3,
+            CustomerEvent::NameAdded(NameAdded {
+                changed_name: "John Doe".to_string(),
+            }),
+            HashMap::default(),
+        ))
+        .then_expect(Vec::new())
+        .then_expect_state(started_saga());
+}
+
+#[test]
+fn test_saga_tester_timeout() {
+    ThisTester::default()
+        .given(started_saga())
+        .when_timeout(REMINDER_TIMEOUT)
+        .then_expect_commands(vec![send_email(REMINDER_SUBJECT)])
+        .then_expect(vec![
+            SagaAction::dispatch(
+                "test_id_A",
+                send_email(REMINDER_SUBJECT),
+            ),
+            SagaAction::Complete,
+        ]);
+
+    ThisTester::default()
+        .given(started_saga())
+        .when_timeout("unknown")
+        .then_expect(Vec::new());
+}
+
+#[test]
+fn test_saga_tester_compensation() {
+    let mut failed = started_saga();
+    failed.failed = true;
+
+    ThisTester::default()
+        .given(started_saga())
+        .when_command_fails(
+            "test_id_A",
+            send_email(VERIFICATION_SUBJECT),
+            Error::new("an email address is required"),
+        )
+        .then_expect(vec![
+            SagaAction::cancel_timeout(REMINDER_TIMEOUT),
+            SagaAction::Complete,
+        ])
+        .then_expect_state(failed);
+}
+
+#[test]
+#[should_panic(expected = "assertion `left == right` failed")]
+fn test_saga_tester_wrong_commands() {
+    ThisTester::default()
+        .given_no_previous_state()
+        .when(&email_updated(
+            "test_id_A",
+            2,
+            "j@d.com",
+        ))
+        .then_expect_commands(vec![send_email(REMINDER_SUBJECT)]);
+}
+
+#[test]
+#[should_panic(expected = "expected error, received actions")]
+fn test_saga_tester_unexpected_success() {
+    ThisTester::default()
+        .given_no_previous_state()
+        .when(&email_updated(
+            "test_id_A",
+            2,
+            "j@d.com",
+        ))
+        .then_expect_error("some error");
+}